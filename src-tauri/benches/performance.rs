@@ -259,6 +259,9 @@ fn bench_load_session_messages(c: &mut Criterion) {
                 rt.block_on(async {
                     claude_code_history_viewer_lib::commands::session::load_session_messages(
                         black_box(path_str.clone()),
+                        None,
+                        None,
+                        None,
                     )
                     .await
                 })
@@ -292,6 +295,9 @@ fn bench_load_session_messages_paginated(c: &mut Criterion) {
                             black_box(0),
                             black_box(size),
                             black_box(Some(false)),
+                            None,
+                            None,
+                            None,
                         )
                         .await
                     })
@@ -313,6 +319,9 @@ fn bench_load_session_messages_paginated(c: &mut Criterion) {
                             black_box(off),
                             black_box(50),
                             black_box(Some(false)),
+                            None,
+                            None,
+                            None,
                         )
                         .await
                     })
@@ -340,6 +349,7 @@ fn bench_get_session_message_count(c: &mut Criterion) {
                     claude_code_history_viewer_lib::commands::session::get_session_message_count(
                         black_box(path_str.clone()),
                         black_box(Some(false)),
+                        None,
                     )
                     .await
                 })
@@ -376,6 +386,7 @@ fn bench_search_messages(c: &mut Criterion) {
                         black_box(q.to_string()),
                         black_box(serde_json::json!({})),
                         None,
+                        None,
                     )
                     .await
                 })
@@ -0,0 +1,103 @@
+//! Structured command errors
+//!
+//! Serialized as `{ "kind": "not_found", "message": "..." }` so the frontend
+//! can branch on a stable `kind` while still showing a human-readable message.
+
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// Error returned by commands that need distinguishable failure kinds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum CommandError {
+    /// The requested file or resource does not exist
+    NotFound(String),
+    /// The OS or a safety check refused access
+    PermissionDenied(String),
+    /// The path resolves outside the `~/.claude` directory
+    OutsideClaudeDir(String),
+    /// An argument failed validation
+    InvalidInput(String),
+    /// Any other I/O failure
+    Io(String),
+}
+
+impl CommandError {
+    /// Build an error from an I/O failure, keeping not-found and
+    /// permission errors distinguishable. The message is `"{context}: {err}"`.
+    pub fn io(context: &str, err: &io::Error) -> Self {
+        let message = format!("{context}: {err}");
+        match err.kind() {
+            io::ErrorKind::NotFound => CommandError::NotFound(message),
+            io::ErrorKind::PermissionDenied => CommandError::PermissionDenied(message),
+            _ => CommandError::Io(message),
+        }
+    }
+
+    /// Stable machine-readable kind, matching the serialized `kind` field
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CommandError::NotFound(_) => "not_found",
+            CommandError::PermissionDenied(_) => "permission_denied",
+            CommandError::OutsideClaudeDir(_) => "outside_claude_dir",
+            CommandError::InvalidInput(_) => "invalid_input",
+            CommandError::Io(_) => "io",
+        }
+    }
+
+    /// Human-readable message
+    pub fn message(&self) -> &str {
+        match self {
+            CommandError::NotFound(message)
+            | CommandError::PermissionDenied(message)
+            | CommandError::OutsideClaudeDir(message)
+            | CommandError::InvalidInput(message)
+            | CommandError::Io(message) => message,
+        }
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<io::Error> for CommandError {
+    fn from(err: io::Error) -> Self {
+        CommandError::io("I/O error", &err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_error_serializes_kind_and_message() {
+        let error = CommandError::OutsideClaudeDir("Path must be within ~/.claude".to_string());
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(json["kind"], "outside_claude_dir");
+        assert_eq!(json["message"], "Path must be within ~/.claude");
+        assert_eq!(json["kind"], error.kind());
+        assert_eq!(error.to_string(), "Path must be within ~/.claude");
+    }
+
+    #[test]
+    fn test_command_error_from_io_error_kind() {
+        let not_found = io::Error::new(io::ErrorKind::NotFound, "gone");
+        assert_eq!(
+            CommandError::io("Failed to read", &not_found),
+            CommandError::NotFound("Failed to read: gone".to_string())
+        );
+
+        let denied = io::Error::new(io::ErrorKind::PermissionDenied, "nope");
+        assert_eq!(CommandError::from(denied).kind(), "permission_denied");
+
+        let other = io::Error::other("disk full");
+        assert_eq!(CommandError::from(other).kind(), "io");
+    }
+}
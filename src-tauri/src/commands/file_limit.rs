@@ -0,0 +1,177 @@
+//! Bounded file concurrency for batch scans
+//!
+//! Batch commands (stats, search, project scanning) process thousands of
+//! session files in parallel. A counting semaphore caps how many of those
+//! files are open at once so large installs cannot hit the OS
+//! file-descriptor limit.
+
+use lazy_static::lazy_static;
+use std::sync::{Condvar, Mutex, PoisonError};
+
+/// Environment variable overriding the default `max_open_files`
+pub const MAX_OPEN_FILES_ENV: &str = "CLAUDE_HISTORY_MAX_OPEN_FILES";
+
+/// Platform default for concurrently open session files.
+///
+/// macOS ships with a soft limit of 256 descriptors per process, Linux
+/// usually 1024; Windows has no comparable per-process limit but the CRT
+/// caps stdio handles at 512. The defaults leave headroom for the app itself.
+pub fn default_max_open_files() -> usize {
+    if cfg!(target_os = "macos") {
+        64
+    } else {
+        256
+    }
+}
+
+/// Counting semaphore limiting concurrently open files
+pub struct FileLimiter {
+    max_open_files: usize,
+    open: Mutex<usize>,
+    released: Condvar,
+}
+
+/// Slot held while a file is open; released on drop
+pub struct FilePermit<'a> {
+    limiter: &'a FileLimiter,
+}
+
+impl FileLimiter {
+    /// Create a limiter allowing `max_open_files` concurrent files (at least 1)
+    pub fn new(max_open_files: usize) -> Self {
+        Self {
+            max_open_files: max_open_files.max(1),
+            open: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    pub fn max_open_files(&self) -> usize {
+        self.max_open_files
+    }
+
+    /// Block until a slot is free
+    pub fn acquire(&self) -> FilePermit<'_> {
+        let mut open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+        while *open >= self.max_open_files {
+            open = self
+                .released
+                .wait(open)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *open += 1;
+        FilePermit { limiter: self }
+    }
+
+    /// Run `f` while holding a slot.
+    ///
+    /// `f` must not start nested parallel work that acquires slots itself,
+    /// or a rayon worker could wait on a slot it already holds.
+    pub fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+        let _permit = self.acquire();
+        f()
+    }
+}
+
+impl Drop for FilePermit<'_> {
+    fn drop(&mut self) {
+        let mut open = self
+            .limiter
+            .open
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *open -= 1;
+        self.limiter.released.notify_one();
+    }
+}
+
+lazy_static! {
+    /// Process-wide limiter shared by all batch commands
+    static ref FILE_LIMITER: FileLimiter = FileLimiter::new(
+        std::env::var(MAX_OPEN_FILES_ENV)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or_else(default_max_open_files)
+    );
+}
+
+/// Run `f`, which opens one session file, under the process-wide limit
+pub fn with_file_permit<T>(f: impl FnOnce() -> T) -> T {
+    FILE_LIMITER.run(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_limiter_blocks_until_permits_are_released() {
+        const LIMIT: usize = 2;
+        const WORKERS: usize = 6;
+        let limiter = FileLimiter::new(LIMIT);
+        let started = AtomicUsize::new(0);
+        let finished = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            // Hold every slot so the workers have to wait on the condvar
+            let held: Vec<FilePermit<'_>> = (0..LIMIT).map(|_| limiter.acquire()).collect();
+
+            for _ in 0..WORKERS {
+                scope.spawn(|| {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    limiter.run(|| {
+                        peak.fetch_max(*limiter.open.lock().unwrap(), Ordering::SeqCst);
+                        std::thread::sleep(std::time::Duration::from_millis(5));
+                        finished.fetch_add(1, Ordering::SeqCst);
+                    });
+                });
+            }
+
+            while started.load(Ordering::SeqCst) < WORKERS {
+                std::thread::yield_now();
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            assert_eq!(finished.load(Ordering::SeqCst), 0);
+            assert_eq!(*limiter.open.lock().unwrap(), LIMIT);
+
+            // Releasing the slots lets every blocked worker through
+            drop(held);
+        });
+
+        assert_eq!(finished.load(Ordering::SeqCst), WORKERS);
+        assert!(peak.load(Ordering::SeqCst) <= LIMIT);
+        assert_eq!(*limiter.open.lock().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_scan_over_many_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("projects").join("-tmp-many");
+        fs::create_dir_all(&project).unwrap();
+        for i in 0..3000 {
+            fs::write(
+                project.join(format!("session-{i}.jsonl")),
+                format!(
+                    r#"{{"type":"user","uuid":"u{i}","sessionId":"s{i}","timestamp":"2025-01-01T00:00:00Z","message":{{"role":"user","content":"needle {i}"}}}}"#
+                ),
+            )
+            .unwrap();
+        }
+
+        let results = crate::commands::session::search_messages_in(
+            temp_dir.path().to_string_lossy().to_string(),
+            "needle".to_string(),
+            serde_json::json!({}),
+            Some(5000),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(results.len(), 3000);
+    }
+}
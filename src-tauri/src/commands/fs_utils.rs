@@ -1,5 +1,10 @@
+use memmap2::Mmap;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
 
 /// Cross-platform atomic rename.
 ///
@@ -25,3 +30,217 @@ pub fn atomic_rename(from: &Path, to: &Path) -> Result<(), String> {
         )
     })
 }
+
+/// Write a file via a temp file and atomic rename
+pub fn write_file_atomically(path: &Path, data: &[u8]) -> Result<(), String> {
+    write_file_atomically_checked(path, data, || Ok(()))
+}
+
+/// Like `write_file_atomically`, but runs `before_rename` once the data is
+/// on disk and abandons the write if it fails
+pub fn write_file_atomically_checked(
+    path: &Path,
+    data: &[u8],
+    before_rename: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    let temp_path = PathBuf::from(format!("{}.tmp", path.display()));
+    let mut temp_file =
+        fs::File::create(&temp_path).map_err(|e| format!("Failed to create temp file: {e}"))?;
+    if let Err(e) = temp_file
+        .write_all(data)
+        .and_then(|()| temp_file.sync_all())
+    {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Failed to write {}: {e}", path.display()));
+    }
+    drop(temp_file);
+    if let Err(e) = before_rename() {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    atomic_rename(&temp_path, path)
+}
+
+/// Read-only contents of a session file
+pub(crate) enum SessionBytes {
+    /// Zero-length file (mapping an empty file is not portable)
+    Empty,
+    Mapped(Mmap),
+}
+
+impl Deref for SessionBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            SessionBytes::Empty => &[],
+            SessionBytes::Mapped(mmap) => mmap,
+        }
+    }
+}
+
+/// Memory-map a session file for read-only access.
+///
+/// A zero-length file yields empty contents rather than an error; open and
+/// map failures (missing, locked or concurrently removed files) return a
+/// descriptive error naming the file.
+#[allow(unsafe_code)] // Required for mmap performance optimization
+pub(crate) fn map_session_file(file_path: &str) -> Result<SessionBytes, String> {
+    let file = fs::File::open(file_path)
+        .map_err(|e| format!("Failed to open session file {file_path}: {e}"))?;
+
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read metadata of session file {file_path}: {e}"))?
+        .len();
+    if len == 0 {
+        return Ok(SessionBytes::Empty);
+    }
+
+    // SAFETY: We're only reading the file, and the file handle is kept open
+    // for the duration of the mmap's lifetime. Session files are append-only.
+    let mmap = unsafe { Mmap::map(&file) }.map_err(|e| {
+        format!(
+            "Failed to memory-map session file {file_path} (it may be locked or was removed): {e}"
+        )
+    })?;
+    Ok(SessionBytes::Mapped(mmap))
+}
+
+/// SHA-256 of a file's contents as lowercase hex; None if it cannot be read.
+///
+/// The digest is stable across runs and Rust releases, so it can be stored
+/// (project manifests) and compared later, and it cannot be forged the way a
+/// checksum can.
+pub fn content_hash(path: &Path) -> Option<String> {
+    let bytes = map_session_file(&path.to_string_lossy()).ok()?;
+    let mut hex = String::with_capacity(64);
+    for byte in Sha256::digest(&bytes[..]) {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    Some(hex)
+}
+
+/// Validate a user-supplied data root (e.g. a backup copy of `~/.claude`).
+///
+/// Returns the canonical root path, or an error if it does not exist
+/// or is not a directory.
+pub fn validate_root_dir(root: &str) -> Result<PathBuf, String> {
+    let root_path = PathBuf::from(root);
+    if !root_path.is_dir() {
+        return Err(format!("Root directory does not exist: {root}"));
+    }
+    root_path
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve root directory {root}: {e}"))
+}
+
+/// Resolve the projects directory of a data root.
+///
+/// Accepts either a Claude data directory containing `projects/`
+/// or a `projects` directory itself.
+pub fn projects_dir_of_root(root: &Path) -> PathBuf {
+    let projects = root.join("projects");
+    if projects.is_dir() {
+        projects
+    } else {
+        root.to_path_buf()
+    }
+}
+
+/// Ensure `path` resolves to a location inside `root`.
+///
+/// Both paths are canonicalized so `..` segments and symlinks cannot be
+/// used to escape the root. Returns the canonical path on success.
+pub fn ensure_within_root(path: &Path, root: &Path) -> Result<PathBuf, String> {
+    let canonical_path = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve path: {e}"))?;
+
+    // Canonicalize root too so both paths use the same format
+    // (on Windows, canonicalize adds \\?\ prefix)
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+    if !canonical_path.starts_with(&canonical_root) {
+        return Err(format!("Path must be within {} directory", root.display()));
+    }
+
+    Ok(canonical_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_ensure_within_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(root.join("projects")).unwrap();
+        let inside = root.join("projects").join("a.jsonl");
+        fs::write(&inside, "").unwrap();
+        let outside = temp_dir.path().join("b.jsonl");
+        fs::write(&outside, "").unwrap();
+
+        assert!(ensure_within_root(&inside, &root).is_ok());
+        assert!(ensure_within_root(&outside, &root).is_err());
+        assert!(ensure_within_root(
+            &root.join("projects").join("..").join("..").join("b.jsonl"),
+            &root
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_root_dir_and_projects_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(validate_root_dir(&temp_dir.path().join("missing").to_string_lossy()).is_err());
+
+        let root = validate_root_dir(&temp_dir.path().to_string_lossy()).unwrap();
+        assert_eq!(projects_dir_of_root(&root), root);
+
+        fs::create_dir_all(root.join("projects")).unwrap();
+        assert_eq!(projects_dir_of_root(&root), root.join("projects"));
+    }
+
+    #[test]
+    fn test_content_hash_is_sha256() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("s.jsonl");
+        fs::write(&path, "abc").unwrap();
+        assert_eq!(
+            content_hash(&path).as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert!(content_hash(&temp_dir.path().join("missing.jsonl")).is_none());
+    }
+
+    #[test]
+    fn test_map_session_file_empty_and_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        let empty = temp_dir.path().join("empty.jsonl");
+        fs::write(&empty, "").unwrap();
+        let bytes = map_session_file(&empty.to_string_lossy()).unwrap();
+        assert!(bytes.is_empty());
+
+        // File already removed when the map is attempted
+        let gone = temp_dir.path().join("gone.jsonl");
+        fs::write(&gone, "{}\n").unwrap();
+        fs::remove_file(&gone).unwrap();
+        let error = map_session_file(&gone.to_string_lossy()).err().unwrap();
+        assert!(error.contains("gone.jsonl"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_map_session_file_survives_removal_after_mapping() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        fs::write(&path, "{\"a\":1}\n").unwrap();
+
+        let bytes = map_session_file(&path.to_string_lossy()).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(&bytes[..], b"{\"a\":1}\n");
+    }
+}
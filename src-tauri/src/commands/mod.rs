@@ -1,5 +1,7 @@
 pub mod claude_settings;
+pub mod error;
 pub mod feedback;
+pub mod file_limit;
 pub mod fs_utils;
 pub mod mcp_presets;
 pub mod metadata;
@@ -7,6 +9,7 @@ pub mod project;
 pub mod session;
 pub mod settings;
 pub mod stats;
+pub mod storage;
 pub mod unified_presets;
 pub mod watcher;
 
@@ -1,8 +1,30 @@
-use crate::models::{ClaudeProject, GitCommit};
+use crate::commands::error::CommandError;
+use crate::commands::file_limit::with_file_permit;
+use crate::commands::fs_utils::{
+    content_hash, projects_dir_of_root, validate_root_dir, write_file_atomically,
+};
+use crate::commands::session::{
+    compute_session_time_bounds, derive_title_from_file, read_session_metadata,
+    validate_delete_path,
+};
+use crate::commands::stats::{extract_session_token_stats_sync, total_session_tokens};
+use crate::commands::storage::directory_size;
+use crate::models::{
+    BranchGroup, BranchWorktree, ClaudeProject, DecodeConfidence, DecodeMethod, EncodingDiagnosis,
+    GitCommit, IndexRepairResult, IndexStatus, IntegrityReport, IntegrityResult, IntegrityStatus,
+    ManifestSession, ProjectCard, ProjectManifest, ProjectSummary, SessionTokenStats, StaleProject,
+    TreeNode,
+};
 use crate::utils::{
+    decode_project_path, decode_project_path_with, decode_storage_dir_name,
     detect_git_worktree_info, estimate_message_count_from_size, extract_project_name,
+    normalize_path, parse_message_timestamp, recorded_original_path, resolve_path_within,
 };
 use chrono::{DateTime, Utc};
+use ignore::WalkBuilder;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -111,17 +133,780 @@ pub async fn validate_claude_folder(path: String) -> Result<bool, String> {
 
 #[tauri::command]
 pub async fn scan_projects(claude_path: String) -> Result<Vec<ClaudeProject>, String> {
-    #[cfg(debug_assertions)]
-    let start_time = std::time::Instant::now();
     let projects_path = PathBuf::from(&claude_path).join("projects");
 
     if !projects_path.exists() {
         return Ok(vec![]);
     }
 
+    Ok(scan_projects_dir(&projects_path))
+}
+
+/// List projects under an explicit data root (e.g. a backup of `~/.claude`)
+/// instead of the default location.
+///
+/// `root` may be a Claude data directory containing `projects/`
+/// or a `projects` directory itself.
+#[tauri::command]
+pub async fn list_projects_in(root: String) -> Result<Vec<ClaudeProject>, String> {
+    let root_path = validate_root_dir(&root)?;
+    Ok(scan_projects_dir(&projects_dir_of_root(&root_path)))
+}
+
+/// Session files of a project, excluding subagent transcripts
+fn project_session_files(project_dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(project_dir)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .filter(|e| !is_subagent_file(e.path()))
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+/// Latest message timestamp across session files
+fn latest_activity(session_files: &[PathBuf]) -> Option<DateTime<Utc>> {
+    session_files
+        .par_iter()
+        .filter_map(|file| {
+            with_file_permit(|| compute_session_time_bounds(file))
+                .ok()?
+                .1
+        })
+        .max()
+}
+
+/// Summary of a project with the given latest activity
+fn project_summary(project_dir: &Path, last_activity: Option<DateTime<Utc>>) -> ProjectSummary {
+    let project_path = project_dir.to_string_lossy().to_string();
+    let raw_project_name = project_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    ProjectSummary {
+        name: extract_project_name(&raw_project_name),
+        actual_path: decode_project_path(&project_path),
+        path: project_path,
+        last_activity: last_activity.map(|t| t.to_rfc3339()),
+    }
+}
+
+/// Compute the summary of one project from its sessions' message timestamps
+fn summarize_project_activity(project_dir: &Path) -> (Option<DateTime<Utc>>, ProjectSummary) {
+    let last_activity = latest_activity(&project_session_files(project_dir));
+    (last_activity, project_summary(project_dir, last_activity))
+}
+
+/// Project directories directly below `<claude_path>/projects`
+fn project_dirs_of(claude_path: &str) -> Vec<PathBuf> {
+    let projects_path = PathBuf::from(claude_path).join("projects");
+
+    if !projects_path.exists() {
+        return vec![];
+    }
+
+    WalkDir::new(&projects_path)
+        .min_depth(1)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_dir())
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+/// List projects ordered by their most recent message timestamp.
+///
+/// Uses message timestamps (via session time bounds) rather than file mtime,
+/// so renaming a session does not reorder projects. Projects without any
+/// timestamped message are listed last.
+#[tauri::command]
+pub async fn list_projects_by_activity(claude_path: String) -> Result<Vec<ProjectSummary>, String> {
+    let mut summaries: Vec<(Option<DateTime<Utc>>, ProjectSummary)> = project_dirs_of(&claude_path)
+        .par_iter()
+        .map(|project_dir| summarize_project_activity(project_dir))
+        .collect();
+
+    // Most recent first; `None` sorts below any timestamp
+    summaries.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+
+    Ok(summaries.into_iter().map(|(_, summary)| summary).collect())
+}
+
+/// List projects without activity in the last `older_than_days` days.
+///
+/// Activity is the latest message timestamp across a project's sessions, as
+/// in `list_projects_by_activity`; projects without any timestamped message
+/// count as stale. Each entry carries the size of its session directory and
+/// whether the decoded project directory still exists. Oldest first.
+#[tauri::command]
+pub async fn stale_projects(
+    claude_path: String,
+    older_than_days: u64,
+) -> Result<Vec<StaleProject>, String> {
+    let days = i64::try_from(older_than_days)
+        .map_err(|_| format!("Invalid day count: {older_than_days}"))?;
+    let cutoff = Utc::now()
+        .checked_sub_signed(chrono::Duration::days(days))
+        .ok_or_else(|| format!("Invalid day count: {older_than_days}"))?;
+
+    let mut stale: Vec<(Option<DateTime<Utc>>, StaleProject)> = project_dirs_of(&claude_path)
+        .par_iter()
+        .filter_map(|project_dir| {
+            let (last_activity, project) = summarize_project_activity(project_dir);
+            if last_activity.is_some_and(|t| t >= cutoff) {
+                return None;
+            }
+            Some((
+                last_activity,
+                StaleProject {
+                    total_bytes: directory_size(project_dir),
+                    actual_path_exists: Path::new(&project.actual_path).is_dir(),
+                    project,
+                },
+            ))
+        })
+        .collect();
+
+    // Oldest first; projects without activity lead
+    stale.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| a.1.project.name.cmp(&b.1.project.name))
+    });
+
+    Ok(stale.into_iter().map(|(_, project)| project).collect())
+}
+
+/// Gather everything a project card shows in one call.
+///
+/// Session count, latest activity, git status and disk size are computed in
+/// parallel. The token total requires parsing every message, so it is only
+/// computed when `include_tokens` is set and is `None` otherwise.
+#[tauri::command]
+pub async fn project_card(
+    project_path: String,
+    include_tokens: Option<bool>,
+) -> Result<ProjectCard, String> {
+    let project_dir = PathBuf::from(&project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Project directory not found: {project_path}"));
+    }
+
+    let session_files = project_session_files(&project_dir);
+    let ((last_activity, total_tokens), (total_bytes, (project, git_info))) = rayon::join(
+        || {
+            rayon::join(
+                || latest_activity(&session_files),
+                || {
+                    include_tokens
+                        .unwrap_or(false)
+                        .then(|| total_session_tokens(&session_files))
+                },
+            )
+        },
+        || {
+            rayon::join(
+                || directory_size(&project_dir),
+                || {
+                    let project = project_summary(&project_dir, None);
+                    let git_info = detect_git_worktree_info(&project.actual_path);
+                    (project, git_info)
+                },
+            )
+        },
+    );
+
+    Ok(ProjectCard {
+        project: ProjectSummary {
+            last_activity: last_activity.map(|t| t.to_rfc3339()),
+            ..project
+        },
+        git_info,
+        session_count: session_files.len(),
+        total_tokens,
+        total_bytes,
+    })
+}
+
+/// Catalog entry of one session file
+fn manifest_session(path: &Path) -> ManifestSession {
+    let file_path = path.to_string_lossy().to_string();
+    let stats = extract_session_token_stats_sync(&path.to_path_buf());
+    let (first, last) = compute_session_time_bounds(path).unwrap_or((None, None));
+    let tokens =
+        |count: fn(&SessionTokenStats) -> u32| stats.as_ref().map_or(0, |s| u64::from(count(s)));
+
+    ManifestSession {
+        session_id: stats.as_ref().map_or_else(
+            || {
+                path.file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            },
+            |s| s.session_id.clone(),
+        ),
+        title: derive_title_from_file(&file_path).ok(),
+        message_count: stats.as_ref().map_or(0, |s| s.message_count),
+        input_tokens: tokens(|s| s.total_input_tokens),
+        output_tokens: tokens(|s| s.total_output_tokens),
+        cache_creation_tokens: tokens(|s| s.total_cache_creation_tokens),
+        cache_read_tokens: tokens(|s| s.total_cache_read_tokens),
+        total_tokens: tokens(|s| s.total_tokens),
+        first_timestamp: first.map(|t| t.to_rfc3339()),
+        last_timestamp: last.map(|t| t.to_rfc3339()),
+        size_bytes: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        content_hash: content_hash(path),
+        file_path,
+    }
+}
+
+/// Export a machine-readable catalog of a project's sessions.
+///
+/// Lists every session (subagent transcripts excluded) with its id, path,
+/// title, message count, token totals, date range, size and a SHA-256 of its
+/// contents, alongside the decoded project path and git info, so backups and
+/// external tools can consume it without parsing each session. Sessions are
+/// processed in parallel.
+#[tauri::command]
+pub async fn export_project_manifest(project_path: String) -> Result<ProjectManifest, String> {
+    let project_dir = PathBuf::from(&project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Project directory not found: {project_path}"));
+    }
+
+    let mut session_files = project_session_files(&project_dir);
+    session_files.sort();
+    let (sessions, (project, git_info)) = rayon::join(
+        || {
+            session_files
+                .par_iter()
+                .map(|path| with_file_permit(|| manifest_session(path)))
+                .collect::<Vec<_>>()
+        },
+        || {
+            let project = project_summary(&project_dir, None);
+            let git_info = detect_git_worktree_info(&project.actual_path);
+            (project, git_info)
+        },
+    );
+
+    let last_activity = sessions
+        .iter()
+        .filter_map(|s| s.last_timestamp.as_deref())
+        .filter_map(parse_message_timestamp)
+        .max();
+
+    Ok(ProjectManifest {
+        project: ProjectSummary {
+            last_activity: last_activity.map(|t| t.to_rfc3339()),
+            ..project
+        },
+        git_info,
+        generated_at: Utc::now().to_rfc3339(),
+        sessions,
+    })
+}
+
+/// Whether a recorded hash is a SHA-256 hex digest
+fn is_sha256_hex(hash: &str) -> bool {
+    hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Check each session of a previously exported manifest against its file.
+///
+/// Every listed file is re-hashed with SHA-256, in parallel under the
+/// open-file limit, and reported as matched, changed, missing or
+/// unverifiable (no SHA-256 recorded, e.g. a manifest from before digests
+/// were recorded, or unreadable). A backup is intact and untampered when
+/// everything matched.
+#[tauri::command]
+pub async fn verify_sessions_against_manifest(
+    manifest: ProjectManifest,
+) -> Result<IntegrityReport, String> {
+    let sessions: Vec<IntegrityResult> = manifest
+        .sessions
+        .into_par_iter()
+        .map(|session| {
+            let path = PathBuf::from(&session.file_path);
+            let (status, actual_hash) = if path.is_file() {
+                let actual_hash = with_file_permit(|| content_hash(&path));
+                let status = match (&session.content_hash, &actual_hash) {
+                    (Some(expected), Some(_)) if !is_sha256_hex(expected) => {
+                        IntegrityStatus::Unverifiable
+                    }
+                    (Some(expected), Some(actual)) if expected.eq_ignore_ascii_case(actual) => {
+                        IntegrityStatus::Matched
+                    }
+                    (Some(_), Some(_)) => IntegrityStatus::Changed,
+                    _ => IntegrityStatus::Unverifiable,
+                };
+                (status, actual_hash)
+            } else {
+                (IntegrityStatus::Missing, None)
+            };
+            IntegrityResult {
+                session_id: session.session_id,
+                file_path: session.file_path,
+                status,
+                expected_hash: session.content_hash,
+                actual_hash,
+            }
+        })
+        .collect();
+
+    let count = |status: IntegrityStatus| sessions.iter().filter(|s| s.status == status).count();
+    Ok(IntegrityReport {
+        matched: count(IntegrityStatus::Matched),
+        changed: count(IntegrityStatus::Changed),
+        missing: count(IntegrityStatus::Missing),
+        unverifiable: count(IntegrityStatus::Unverifiable),
+        sessions,
+    })
+}
+
+/// Bucket name for sessions without a recorded git branch
+const UNKNOWN_BRANCH: &str = "(unknown)";
+
+/// Group a project's sessions by the git branch recorded in their metadata.
+///
+/// Only the leading metadata lines of each session are read. Sessions without
+/// a branch go into an `(unknown)` bucket, which sorts last. Each group lists
+/// the working directories it was recorded in, with their worktree status, so
+/// work on one branch across several worktrees shows up together.
+#[tauri::command]
+pub async fn group_sessions_by_branch(project_path: String) -> Result<Vec<BranchGroup>, String> {
+    let project_dir = PathBuf::from(&project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Project directory not found: {project_path}"));
+    }
+
+    let metadata: Vec<(String, Option<String>, Option<String>)> =
+        project_session_files(&project_dir)
+            .par_iter()
+            .map(|file| {
+                let file_path = file.to_string_lossy().to_string();
+                let metadata =
+                    with_file_permit(|| read_session_metadata(&file_path)).unwrap_or_default();
+                let branch = metadata.git_branch.filter(|b| !b.trim().is_empty());
+                (file_path, branch, metadata.cwd)
+            })
+            .collect();
+
+    // branch -> (session paths, working directories); None sorts first in the map
+    let mut buckets: BTreeMap<Option<String>, (Vec<String>, BTreeSet<String>)> = BTreeMap::new();
+    for (file_path, branch, cwd) in metadata {
+        let bucket = buckets.entry(branch).or_default();
+        bucket.0.push(file_path);
+        bucket.1.extend(cwd);
+    }
+
+    let mut groups: Vec<BranchGroup> = buckets
+        .into_iter()
+        .map(|(branch, (mut session_paths, cwds))| {
+            session_paths.sort();
+            BranchGroup {
+                branch: branch.unwrap_or_else(|| UNKNOWN_BRANCH.to_string()),
+                session_paths,
+                worktrees: cwds
+                    .into_iter()
+                    .map(|cwd| BranchWorktree {
+                        git_info: detect_git_worktree_info(&cwd),
+                        cwd,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    // Move the unknown bucket (first in the map) to the end
+    if groups.first().is_some_and(|g| g.branch == UNKNOWN_BRANCH) {
+        groups.rotate_left(1);
+    }
+
+    Ok(groups)
+}
+
+/// Default depth of `project_tree_snapshot`
+const DEFAULT_TREE_DEPTH: usize = 3;
+
+/// Maximum number of entries (excluding the root) in a tree snapshot
+const MAX_TREE_ENTRIES: usize = 5000;
+
+impl TreeNode {
+    fn from_path(path: &Path, is_dir: bool) -> Self {
+        Self {
+            name: path
+                .file_name()
+                .map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy())
+                .to_string(),
+            path: path.to_string_lossy().to_string(),
+            is_dir,
+            children: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    /// Sort children recursively: directories first, then by name
+    fn sort(&mut self) {
+        self.children
+            .sort_by(|a, b| (Reverse(a.is_dir), &a.name).cmp(&(Reverse(b.is_dir), &b.name)));
+        self.children.iter_mut().for_each(TreeNode::sort);
+    }
+}
+
+/// Walk `root` up to `max_depth` levels into a nested tree.
+///
+/// `.gitignore` rules and hidden files are skipped. Unreadable directories
+/// are kept with no children. Stops after `MAX_TREE_ENTRIES` entries.
+fn build_project_tree(root: &Path, max_depth: usize) -> TreeNode {
+    // Open directories from the root down to the current entry's parent;
+    // the walk is depth-first, so finished directories are always on top
+    let mut stack = vec![TreeNode::from_path(root, true)];
+    let mut entries = 0;
+    let mut truncated = false;
+
+    let walker = WalkBuilder::new(root)
+        .max_depth(Some(max_depth))
+        .require_git(false)
+        .sort_by_file_name(Ord::cmp)
+        .build();
+
+    for entry in walker.filter_map(std::result::Result::ok) {
+        let depth = entry.depth();
+        if depth == 0 {
+            continue;
+        }
+        if entries == MAX_TREE_ENTRIES {
+            truncated = true;
+            break;
+        }
+        entries += 1;
+
+        while stack.len() > depth {
+            let node = stack.pop().expect("stack holds the root");
+            stack
+                .last_mut()
+                .expect("stack holds the root")
+                .children
+                .push(node);
+        }
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        stack.push(TreeNode::from_path(entry.path(), is_dir));
+    }
+
+    while stack.len() > 1 {
+        let node = stack.pop().expect("stack holds the root");
+        stack
+            .last_mut()
+            .expect("stack holds the root")
+            .children
+            .push(node);
+    }
+    let mut tree = stack.pop().expect("stack holds the root");
+    tree.truncated = truncated;
+    tree.sort();
+    tree
+}
+
+/// Snapshot the directory structure of a project's working directory.
+///
+/// # Arguments
+/// * `project_path` - Claude session storage path of the project
+/// * `max_depth` - Levels below the project root to list (default 3)
+///
+/// The storage path is decoded to the actual project directory. Files
+/// matched by `.gitignore` and hidden files are left out, and the snapshot
+/// stops after 5000 entries, marking the root as `truncated`.
+#[tauri::command]
+pub async fn project_tree_snapshot(
+    project_path: String,
+    max_depth: Option<usize>,
+) -> Result<TreeNode, String> {
+    let actual_path = decode_project_path_with(&project_path, true);
+    let root = Path::new(&actual_path);
+    if !root.is_dir() {
+        return Err(format!("Project directory not found: {actual_path}"));
+    }
+
+    Ok(build_project_tree(
+        root,
+        max_depth.unwrap_or(DEFAULT_TREE_DEPTH),
+    ))
+}
+
+/// Resolve a file path from a tool call against a project's directory.
+///
+/// # Arguments
+/// * `project_path` - Claude session storage path of the project
+/// * `relative_path` - Path as written in the tool call, relative to the
+///   project directory (absolute paths inside the project are accepted too)
+///
+/// `.` and `..` are resolved lexically, without touching the filesystem.
+/// Paths that end up outside the project directory are rejected.
+#[tauri::command]
+pub async fn resolve_tool_path(
+    project_path: String,
+    relative_path: String,
+) -> Result<String, String> {
+    let root = decode_project_path(&project_path);
+    let resolved = resolve_path_within(Path::new(&root), &relative_path)?;
+    Ok(resolved.to_string_lossy().to_string())
+}
+
+/// Encode a real project path into its Claude session storage directory name.
+///
+/// Inverse of the project path decoding, e.g. `/Users/jack/app` →
+/// `-Users-jack-app`. Lossy: hyphens and other punctuation in directory
+/// names encode the same as separators.
+#[tauri::command]
+pub async fn encode_project_path(real_path: String) -> Result<String, String> {
+    if real_path.trim().is_empty() {
+        return Err("Project path is empty".to_string());
+    }
+    Ok(crate::utils::encode_project_path(&real_path))
+}
+
+/// Decode a session storage path and report how trustworthy the result is.
+///
+/// `high` means the path was recorded by Claude or fully verified on disk,
+/// `medium` that only its leading directories exist and `low` that hyphens
+/// were split by heuristic alone, so the UI can flag a possibly wrong path.
+#[tauri::command]
+pub async fn decode_project_path_with_confidence(
+    storage_path: String,
+) -> Result<(String, DecodeConfidence), String> {
+    if storage_path.trim().is_empty() {
+        return Err("Storage path is empty".to_string());
+    }
+    Ok(crate::utils::decode_project_path_with_confidence(
+        &storage_path,
+    ))
+}
+
+/// Index file in which Claude records a project's `originalPath`
+const SESSIONS_INDEX_FILE: &str = "sessions-index.json";
+
+/// Parsed `sessions-index.json` of a project; `None` if missing or invalid
+fn read_sessions_index(project_dir: &Path) -> Option<serde_json::Value> {
+    let content = fs::read_to_string(project_dir.join(SESSIONS_INDEX_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Compare a project's recorded `originalPath` with its directory name.
+///
+/// A recorded path is consistent when it exists and encodes to the storage
+/// directory name. It is only flagged as a mismatch when it is not and the
+/// directory name decodes to a different path that is verified on disk, so
+/// an ambiguous decode never overrides a correct recorded path.
+fn sessions_index_status(project_dir: &Path) -> IndexStatus {
+    let index = read_sessions_index(project_dir);
+    let original_path = index
+        .as_ref()
+        .and_then(|v| v.get("originalPath"))
+        .and_then(serde_json::Value::as_str)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string);
+    let (decoded_path, decode_confidence) = decode_storage_dir_name(&project_dir.to_string_lossy());
+
+    let original_path_exists = original_path
+        .as_deref()
+        .is_some_and(|p| Path::new(p).is_dir());
+    let dir_name = project_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let is_mismatch = original_path.as_deref().is_some_and(|original| {
+        let is_consistent =
+            original_path_exists && crate::utils::encode_project_path(original) == dir_name;
+        !is_consistent
+            && decode_confidence == DecodeConfidence::High
+            && normalize_path(Path::new(original)) != normalize_path(Path::new(&decoded_path))
+    });
+
+    IndexStatus {
+        has_index: index.is_some(),
+        original_path,
+        original_path_exists,
+        decoded_path,
+        decode_confidence,
+        is_mismatch,
+    }
+}
+
+/// Check whether a project's `sessions-index.json` points at the right place.
+///
+/// Claude records the project's real path as `originalPath`, which goes stale
+/// when the project is moved. Reports the recorded path, whether it exists,
+/// and the path decoded from the storage directory name, flagging a mismatch
+/// when only the decoded path is verified on disk.
+#[tauri::command]
+pub async fn validate_sessions_index(project_path: String) -> Result<IndexStatus, String> {
+    let project_dir = Path::new(&project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Project directory not found: {project_path}"));
+    }
+    Ok(sessions_index_status(project_dir))
+}
+
+/// Rewrite a stale `originalPath` to the filesystem-verified decode.
+/// Other fields of the index are kept.
+fn repair_sessions_index_in(project_dir: &Path) -> Result<IndexRepairResult, CommandError> {
+    let status = sessions_index_status(project_dir);
+    if !status.is_mismatch {
+        return Ok(IndexRepairResult {
+            backup_path: None,
+            previous_path: status.original_path.clone(),
+            status,
+        });
+    }
+
+    let index_path = project_dir.join(SESSIONS_INDEX_FILE);
+    let mut index = read_sessions_index(project_dir).ok_or_else(|| {
+        CommandError::InvalidInput(format!("Unreadable index: {}", index_path.display()))
+    })?;
+    let Some(fields) = index.as_object_mut() else {
+        return Err(CommandError::InvalidInput(
+            "Index is not a JSON object".to_string(),
+        ));
+    };
+    fields.insert(
+        "originalPath".to_string(),
+        serde_json::Value::String(status.decoded_path.clone()),
+    );
+    let content = serde_json::to_vec_pretty(&index)
+        .map_err(|e| CommandError::Io(format!("Failed to serialize index: {e}")))?;
+
+    let backup = format!(
+        "{}.{}.bak",
+        index_path.display(),
+        Utc::now().format("%Y%m%d%H%M%S")
+    );
+    fs::copy(&index_path, &backup).map_err(|e| CommandError::io("Failed to back up index", &e))?;
+    write_file_atomically(&index_path, &content).map_err(CommandError::Io)?;
+
+    Ok(IndexRepairResult {
+        backup_path: Some(backup),
+        previous_path: status.original_path,
+        status: sessions_index_status(project_dir),
+    })
+}
+
+/// Fix a project that shows the wrong path after being moved.
+///
+/// When [`validate_sessions_index`] reports a mismatch, `originalPath` is
+/// rewritten to the path decoded from the storage directory name. The
+/// original index is backed up as `sessions-index.json.<timestamp>.bak`
+/// first. Does nothing when the index is consistent or the decode is not
+/// verified on disk.
+#[tauri::command]
+pub async fn repair_sessions_index(
+    project_path: String,
+) -> Result<IndexRepairResult, CommandError> {
+    let index_path = Path::new(&project_path).join(SESSIONS_INDEX_FILE);
+    validate_delete_path(&index_path.to_string_lossy())?;
+    repair_sessions_index_in(Path::new(&project_path))
+}
+
+/// Diagnose why a project's name may decode wrong.
+///
+/// Claude encodes a project path by replacing every character other than an
+/// ASCII letter or digit with a hyphen, so non-ASCII and punctuated paths
+/// cannot be decoded from the directory name alone. Reports the raw encoded
+/// name, the decoded path and the method that produced it, whether encoding
+/// the result gives the name back, and any non-ASCII or unusual characters.
+#[tauri::command]
+pub async fn diagnose_project_encoding(storage_path: String) -> Result<EncodingDiagnosis, String> {
+    let storage_dir = Path::new(&storage_path);
+    let encoded_name = storage_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid storage path: {storage_path}"))?;
+
+    let original_path = recorded_original_path(&storage_path);
+    let (decoded_path, confidence) =
+        crate::utils::decode_project_path_with_confidence(&storage_path);
+    let method = if original_path.as_deref() == Some(decoded_path.as_str()) {
+        DecodeMethod::OriginalPath
+    } else {
+        match confidence {
+            DecodeConfidence::High => DecodeMethod::Filesystem,
+            DecodeConfidence::Medium => DecodeMethod::PartialFilesystem,
+            DecodeConfidence::Low => DecodeMethod::Heuristic,
+        }
+    };
+
+    let unusual_chars: BTreeSet<String> = encoded_name
+        .chars()
+        .filter(|c| !c.is_ascii_alphanumeric() && *c != '-')
+        .map(String::from)
+        .collect();
+
+    Ok(EncodingDiagnosis {
+        round_trips: crate::utils::encode_project_path(&decoded_path) == encoded_name,
+        name_has_non_ascii: !encoded_name.is_ascii(),
+        unusual_chars: unusual_chars.into_iter().collect(),
+        path_has_non_ascii: !decoded_path.is_ascii(),
+        encoded_name,
+        decoded_path,
+        method,
+        confidence,
+        original_path,
+    })
+}
+
+/// Find the storage directory of `real_path` inside a projects directory.
+///
+/// Tries the encoded directory name first, then decodes every project and
+/// compares it with `real_path` to cover names the encoding cannot predict.
+fn find_project_storage_in(projects_path: &Path, real_path: &str) -> Option<PathBuf> {
+    let target = normalize_path(Path::new(real_path))?;
+
+    let direct = projects_path.join(crate::utils::encode_project_path(&target.to_string_lossy()));
+    if direct.is_dir() {
+        return Some(direct);
+    }
+
+    WalkDir::new(projects_path)
+        .min_depth(1)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_dir())
+        .map(|e| e.path().to_path_buf())
+        .find(|dir| {
+            let decoded = decode_project_path(&dir.to_string_lossy());
+            normalize_path(Path::new(&decoded)).is_some_and(|p| p == target)
+        })
+}
+
+/// Locate the Claude session storage directory of a real project path.
+///
+/// Looks under `~/.claude/projects` for the encoded directory name. Because
+/// hyphens in directory names make the encoding ambiguous, falls back to
+/// decoding each project and matching it against `real_path`. Returns
+/// `None` when no project matches.
+#[tauri::command]
+pub async fn find_project_storage(real_path: String) -> Result<Option<String>, String> {
+    if real_path.trim().is_empty() {
+        return Err("Project path is empty".to_string());
+    }
+    let home_dir =
+        dirs::home_dir().ok_or("HOME_DIRECTORY_NOT_FOUND:Could not determine home directory")?;
+    let projects_path = home_dir.join(".claude").join("projects");
+
+    Ok(find_project_storage_in(&projects_path, &real_path)
+        .map(|dir| dir.to_string_lossy().to_string()))
+}
+
+/// Scan a projects directory (one subdirectory per project)
+fn scan_projects_dir(projects_path: &Path) -> Vec<ClaudeProject> {
+    #[cfg(debug_assertions)]
+    let start_time = std::time::Instant::now();
+
     let mut projects = Vec::new();
 
-    for entry in WalkDir::new(&projects_path)
+    for entry in WalkDir::new(projects_path)
         .min_depth(1)
         .max_depth(1)
         .into_iter()
@@ -201,7 +986,7 @@ pub async fn scan_projects(claude_path: String) -> Result<Vec<ClaudeProject>, St
         );
     }
 
-    Ok(projects)
+    projects
 }
 
 #[cfg(test)]
@@ -263,6 +1048,35 @@ mod tests {
         assert!(result.unwrap());
     }
 
+    // Test list_projects_in
+    #[tokio::test]
+    async fn test_list_projects_in_custom_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let backup_root = temp_dir.path().join("claude-backup");
+        let project_dir = backup_root.join("projects").join("-home-user-app");
+        fs::create_dir_all(&project_dir).unwrap();
+        create_test_jsonl_file(&project_dir, "session.jsonl", "{}\n");
+
+        // Claude data directory containing projects/
+        let projects = list_projects_in(backup_root.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].session_count, 1);
+
+        // projects/ directory itself
+        let projects = list_projects_in(backup_root.join("projects").to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_projects_in_missing_root() {
+        let result = list_projects_in("/nonexistent/backup/root".to_string()).await;
+        assert!(result.unwrap_err().contains("does not exist"));
+    }
+
     // Test scan_projects
     #[tokio::test]
     async fn test_scan_projects_empty() {
@@ -502,4 +1316,417 @@ mod tests {
             panic!("get_git_log failed: {}", result.unwrap_err());
         }
     }
+
+    #[tokio::test]
+    async fn test_list_projects_by_activity_uses_message_timestamps() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_dir = temp_dir.path().join("projects");
+        let old_project = projects_dir.join("-tmp-old");
+        let new_project = projects_dir.join("-tmp-new");
+        let empty_project = projects_dir.join("-tmp-empty");
+        for dir in [&old_project, &new_project, &empty_project] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        create_test_jsonl_file(
+            &new_project,
+            "a.jsonl",
+            r#"{"type":"user","timestamp":"2025-03-01T09:00:00Z"}"#,
+        );
+        // Written last (newest mtime) but with older messages
+        create_test_jsonl_file(
+            &old_project,
+            "b.jsonl",
+            "{\"type\":\"user\",\"timestamp\":\"2025-01-01T09:00:00Z\"}\n{\"type\":\"assistant\",\"timestamp\":\"2025-01-02T09:00:00Z\"}",
+        );
+
+        let summaries = list_projects_by_activity(temp_dir.path().to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        let names: Vec<&str> = summaries.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(summaries.len(), 3);
+        assert!(names[0].ends_with("-tmp-new"));
+        assert!(names[1].ends_with("-tmp-old"));
+        assert!(names[2].ends_with("-tmp-empty"));
+        assert_eq!(
+            summaries[1].last_activity.as_deref(),
+            Some("2025-01-02T09:00:00+00:00")
+        );
+        assert!(summaries[2].last_activity.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stale_projects() {
+        let temp_dir = TempDir::new().unwrap();
+        let claude_dir = temp_dir.path().join(".claude");
+        let projects_dir = claude_dir.join("projects");
+        let old_project = projects_dir.join("-nonexistent-gone-app");
+        let recent_project = projects_dir.join("-tmp-recent");
+        let empty_project = projects_dir.join("-tmp-empty");
+        for dir in [&old_project, &recent_project, &empty_project] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        let old_line = r#"{"type":"user","timestamp":"2024-01-01T09:00:00Z"}"#;
+        create_test_jsonl_file(&old_project, "a.jsonl", old_line);
+        create_test_jsonl_file(
+            &recent_project,
+            "b.jsonl",
+            &format!(
+                r#"{{"type":"user","timestamp":"{}"}}"#,
+                Utc::now().to_rfc3339()
+            ),
+        );
+
+        let stale = stale_projects(claude_dir.to_string_lossy().to_string(), 30)
+            .await
+            .unwrap();
+
+        let paths: Vec<&str> = stale.iter().map(|s| s.project.path.as_str()).collect();
+        assert_eq!(stale.len(), 2);
+        assert!(paths[0].ends_with("-tmp-empty"));
+        assert!(paths[1].ends_with("-nonexistent-gone-app"));
+        assert_eq!(stale[1].total_bytes, old_line.len() as u64);
+        assert_eq!(
+            stale[1].project.last_activity.as_deref(),
+            Some("2024-01-01T09:00:00+00:00")
+        );
+        assert!(!stale[1].actual_path_exists);
+    }
+
+    #[tokio::test]
+    async fn test_group_sessions_by_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().to_path_buf();
+        create_test_jsonl_file(
+            &project,
+            "a.jsonl",
+            r#"{"type":"user","version":"1.0.0","cwd":"/repo","gitBranch":"feature/x"}"#,
+        );
+        create_test_jsonl_file(
+            &project,
+            "b.jsonl",
+            r#"{"type":"user","version":"1.0.0","cwd":"/repo-wt","gitBranch":"feature/x"}"#,
+        );
+        create_test_jsonl_file(
+            &project,
+            "c.jsonl",
+            r#"{"type":"user","version":"1.0.0","cwd":"/repo","gitBranch":""}"#,
+        );
+        create_test_jsonl_file(
+            &project,
+            "d.jsonl",
+            r#"{"type":"user","version":"1.0.0","cwd":"/repo","gitBranch":"main"}"#,
+        );
+
+        let groups = group_sessions_by_branch(project.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        let branches: Vec<&str> = groups.iter().map(|g| g.branch.as_str()).collect();
+        assert_eq!(branches, vec!["feature/x", "main", "(unknown)"]);
+        assert_eq!(groups[0].session_paths.len(), 2);
+        let cwds: Vec<&str> = groups[0].worktrees.iter().map(|w| w.cwd.as_str()).collect();
+        assert_eq!(cwds, vec!["/repo", "/repo-wt"]);
+        assert!(groups[2].session_paths[0].ends_with("c.jsonl"));
+    }
+
+    #[tokio::test]
+    async fn test_project_tree_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("my-app");
+        fs::create_dir_all(project.join("src/nested/deep")).unwrap();
+        fs::create_dir_all(project.join("target/debug")).unwrap();
+        fs::write(project.join(".gitignore"), "target/\n*.log\n").unwrap();
+        fs::write(project.join("README.md"), "").unwrap();
+        fs::write(project.join("debug.log"), "").unwrap();
+        fs::write(project.join("src/main.rs"), "").unwrap();
+        fs::write(project.join("src/nested/deep/mod.rs"), "").unwrap();
+
+        let storage = temp_dir.path().join("storage");
+        fs::create_dir_all(&storage).unwrap();
+        fs::write(
+            storage.join("sessions-index.json"),
+            serde_json::json!({ "originalPath": project.to_string_lossy() }).to_string(),
+        )
+        .unwrap();
+
+        let tree = project_tree_snapshot(storage.to_string_lossy().to_string(), Some(2))
+            .await
+            .unwrap();
+
+        let names: Vec<&str> = tree.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["src", "README.md"]);
+        let src = &tree.children[0];
+        assert!(src.is_dir);
+        let src_names: Vec<&str> = src.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(src_names, vec!["nested", "main.rs"]);
+        // Depth limit reached
+        assert!(src.children[0].children.is_empty());
+        assert!(!tree.truncated);
+    }
+
+    #[test]
+    fn test_find_project_storage_in() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects = temp_dir.path().join("projects");
+
+        let direct = projects.join("-srv-app");
+        fs::create_dir_all(&direct).unwrap();
+        assert_eq!(
+            find_project_storage_in(&projects, "/srv/app/"),
+            Some(direct)
+        );
+
+        // Name that the encoding does not produce, matched via originalPath
+        let indexed = projects.join("renamed-storage");
+        fs::create_dir_all(&indexed).unwrap();
+        fs::write(
+            indexed.join("sessions-index.json"),
+            r#"{"originalPath":"/srv/my-tool"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            find_project_storage_in(&projects, "/srv/my-tool"),
+            Some(indexed)
+        );
+
+        assert_eq!(find_project_storage_in(&projects, "/srv/other"), None);
+    }
+
+    #[test]
+    fn test_repair_sessions_index() {
+        let temp_dir = TempDir::new().unwrap();
+        // `/tmp` exists everywhere, so the directory name decodes verified
+        let storage = temp_dir.path().join(".claude/projects/-tmp");
+        fs::create_dir_all(&storage).unwrap();
+        fs::write(
+            storage.join(SESSIONS_INDEX_FILE),
+            r#"{"originalPath":"/nonexistent/old-home","version":1}"#,
+        )
+        .unwrap();
+
+        let status = sessions_index_status(&storage);
+        assert!(status.has_index);
+        assert!(!status.original_path_exists);
+        assert_eq!(status.decoded_path, "/tmp");
+        assert!(status.is_mismatch);
+
+        let result = repair_sessions_index_in(&storage).unwrap();
+        assert_eq!(
+            result.previous_path.as_deref(),
+            Some("/nonexistent/old-home")
+        );
+        assert!(!result.status.is_mismatch);
+        assert_eq!(result.status.original_path.as_deref(), Some("/tmp"));
+        let backup = fs::read_to_string(result.backup_path.unwrap()).unwrap();
+        assert!(backup.contains("/nonexistent/old-home"));
+        let index = read_sessions_index(&storage).unwrap();
+        assert_eq!(index["version"], 1);
+
+        // Already consistent: nothing to do
+        let again = repair_sessions_index_in(&storage).unwrap();
+        assert!(again.backup_path.is_none());
+    }
+
+    #[test]
+    fn test_sessions_index_status_keeps_unverified_decode() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = temp_dir.path().join(".claude/projects/-nonexistent-app");
+        fs::create_dir_all(&storage).unwrap();
+        fs::write(
+            storage.join(SESSIONS_INDEX_FILE),
+            r#"{"originalPath":"/nonexistent/other"}"#,
+        )
+        .unwrap();
+
+        let status = sessions_index_status(&storage);
+        assert_ne!(status.decode_confidence, DecodeConfidence::High);
+        assert!(!status.is_mismatch);
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_project_encoding() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects = temp_dir.path().join(".claude/projects");
+
+        // Non-ASCII path: only the recorded originalPath recovers it
+        let storage = projects.join("-Users-jos--projets");
+        fs::create_dir_all(&storage).unwrap();
+        fs::write(
+            storage.join("sessions-index.json"),
+            r#"{"originalPath":"/Users/josé/projets"}"#,
+        )
+        .unwrap();
+        let diagnosis = diagnose_project_encoding(storage.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(diagnosis.encoded_name, "-Users-jos--projets");
+        assert_eq!(diagnosis.method, DecodeMethod::OriginalPath);
+        assert!(diagnosis.round_trips);
+        assert!(diagnosis.path_has_non_ascii);
+        assert!(!diagnosis.name_has_non_ascii);
+
+        // Directory name that the encoding cannot produce
+        let storage = projects.join("-nonexistent-caf\u{e9}_x");
+        fs::create_dir_all(&storage).unwrap();
+        let diagnosis = diagnose_project_encoding(storage.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(diagnosis.method, DecodeMethod::Heuristic);
+        assert!(!diagnosis.round_trips);
+        assert!(diagnosis.name_has_non_ascii);
+        assert_eq!(diagnosis.unusual_chars, vec!["_", "\u{e9}"]);
+    }
+
+    #[tokio::test]
+    async fn test_export_project_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("-srv-app");
+        fs::create_dir_all(project.join("subagents")).unwrap();
+        let lines = [
+            r#"{"type":"user","uuid":"u1","sessionId":"s-1","timestamp":"2025-01-01T00:00:00Z","message":{"role":"user","content":"Fix the login bug"}}"#,
+            r#"{"type":"assistant","uuid":"u2","sessionId":"s-1","timestamp":"2025-01-01T00:05:00Z","message":{"role":"assistant","content":"done","usage":{"input_tokens":10,"output_tokens":5}}}"#,
+        ];
+        fs::write(project.join("b.jsonl"), lines.join("\n")).unwrap();
+        fs::write(project.join("a.jsonl"), "").unwrap();
+        fs::write(project.join("subagents/agent-x.jsonl"), lines[0]).unwrap();
+
+        let manifest = export_project_manifest(project.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(manifest.sessions.len(), 2);
+        let empty = &manifest.sessions[0];
+        assert_eq!(empty.session_id, "a");
+        assert_eq!(empty.message_count, 0);
+        assert_eq!(
+            empty.content_hash.as_deref(),
+            Some("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+
+        let session = &manifest.sessions[1];
+        assert_eq!(session.session_id, "s-1");
+        assert_eq!(session.title.as_deref(), Some("Fix the login bug"));
+        assert_eq!(session.message_count, 2);
+        assert_eq!(session.total_tokens, 15);
+        assert_eq!(
+            session.last_timestamp.as_deref(),
+            Some("2025-01-01T00:05:00+00:00")
+        );
+        assert_eq!(manifest.project.last_activity, session.last_timestamp);
+
+        let missing = export_project_manifest("/nonexistent/project".to_string()).await;
+        assert!(missing.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_sessions_against_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("-srv-app");
+        fs::create_dir_all(&project).unwrap();
+        let line = r#"{"type":"user","uuid":"u1","sessionId":"s-1","timestamp":"2025-01-01T00:00:00Z","message":{"role":"user","content":"hi"}}"#;
+        for name in ["intact", "edited", "deleted", "unhashed", "checksummed"] {
+            fs::write(project.join(format!("{name}.jsonl")), line).unwrap();
+        }
+
+        let mut manifest = export_project_manifest(project.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        fs::write(project.join("edited.jsonl"), format!("{line}\n{line}")).unwrap();
+        fs::remove_file(project.join("deleted.jsonl")).unwrap();
+        for session in &mut manifest.sessions {
+            if session.file_path.ends_with("unhashed.jsonl") {
+                session.content_hash = None;
+            }
+            if session.file_path.ends_with("checksummed.jsonl") {
+                session.content_hash = Some("00000000".to_string());
+            }
+        }
+
+        let report = verify_sessions_against_manifest(manifest).await.unwrap();
+        let statuses: Vec<(&str, IntegrityStatus)> = report
+            .sessions
+            .iter()
+            .map(|s| {
+                let name = Path::new(&s.file_path)
+                    .file_stem()
+                    .unwrap()
+                    .to_str()
+                    .unwrap();
+                (name, s.status)
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("checksummed", IntegrityStatus::Unverifiable),
+                ("deleted", IntegrityStatus::Missing),
+                ("edited", IntegrityStatus::Changed),
+                ("intact", IntegrityStatus::Matched),
+                ("unhashed", IntegrityStatus::Unverifiable),
+            ]
+        );
+        assert_eq!(
+            (
+                report.matched,
+                report.changed,
+                report.missing,
+                report.unverifiable
+            ),
+            (1, 1, 1, 2)
+        );
+        assert_ne!(
+            report.sessions[2].expected_hash,
+            report.sessions[2].actual_hash
+        );
+        assert_eq!(
+            report.sessions[3].actual_hash.as_ref().map(String::len),
+            Some(64)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_project_card() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("-srv-app");
+        fs::create_dir_all(project.join("subagents")).unwrap();
+        let line = |ts: &str| {
+            format!(
+                r#"{{"type":"assistant","uuid":"u-{ts}","sessionId":"s","timestamp":"{ts}","message":{{"role":"assistant","content":"hi","usage":{{"input_tokens":10,"output_tokens":5}}}}}}"#
+            )
+        };
+        fs::write(project.join("a.jsonl"), line("2025-01-01T00:00:00Z")).unwrap();
+        fs::write(project.join("b.jsonl"), line("2025-03-01T00:00:00Z")).unwrap();
+        fs::write(
+            project.join("subagents/c.jsonl"),
+            line("2025-06-01T00:00:00Z"),
+        )
+        .unwrap();
+
+        let path = project.to_string_lossy().to_string();
+        let card = project_card(path.clone(), None).await.unwrap();
+        assert_eq!(card.session_count, 2);
+        assert_eq!(
+            card.project.last_activity.as_deref(),
+            Some("2025-03-01T00:00:00+00:00")
+        );
+        assert_eq!(card.total_tokens, None);
+        assert!(card.total_bytes > 0);
+
+        let card = project_card(path, Some(true)).await.unwrap();
+        assert_eq!(card.total_tokens, Some(30));
+
+        assert!(project_card(
+            temp_dir
+                .path()
+                .join("missing")
+                .to_string_lossy()
+                .to_string(),
+            None
+        )
+        .await
+        .is_err());
+    }
 }
@@ -0,0 +1,515 @@
+//! Session bundle module
+//!
+//! Packages a session JSONL file together with its companion directory into a
+//! single `.tar.gz` so it can be moved to another machine, and unpacks such a
+//! bundle back into a project directory.
+//!
+//! Bundle layout (relative to the archive root):
+//! - `<session_id>.jsonl`
+//! - `<session_id>/...` (companion directory contents, if any)
+
+use crate::commands::fs_utils::{atomic_rename, ensure_within_root};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::{Component, Path, PathBuf};
+use tauri::command;
+use walkdir::WalkDir;
+
+lazy_static! {
+    /// Session ids and encoded project directory names only use these characters
+    static ref BUNDLE_NAME_REGEX: Regex = Regex::new(r"^[A-Za-z0-9_-]+$").unwrap();
+}
+
+/// Result structure for bundle export
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionBundleExportResult {
+    pub bundle_path: String,
+    pub session_id: String,
+    /// Archive-relative paths of the files that were packed
+    pub files: Vec<String>,
+    /// Uncompressed size of the packed files
+    pub total_bytes: u64,
+    pub bundle_bytes: u64,
+}
+
+/// Result structure for bundle import
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionBundleImportResult {
+    pub session_id: String,
+    pub file_path: String,
+    pub project_path: String,
+    /// Absolute paths of the files that were written
+    pub files_written: Vec<String>,
+    pub total_bytes: u64,
+}
+
+/// Exports a session (JSONL + companion directory) into a `.tar.gz` bundle.
+///
+/// # Arguments
+/// * `file_path` - Absolute path to the session JSONL file
+/// * `out_path` - Where the bundle should be written
+#[command]
+pub async fn export_session_bundle(
+    file_path: String,
+    out_path: String,
+) -> Result<SessionBundleExportResult, String> {
+    let session_path = PathBuf::from(&file_path);
+    if !session_path.is_file() {
+        return Err(format!("Session file not found: {file_path}"));
+    }
+    if session_path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+        return Err("Session file must have a .jsonl extension".to_string());
+    }
+
+    let session_id = session_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .ok_or_else(|| "Invalid session filename".to_string())?;
+
+    let out = PathBuf::from(&out_path);
+    let temp_out = PathBuf::from(format!("{out_path}.tmp"));
+
+    let packed = write_bundle(&session_path, &session_id, &temp_out).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_out);
+    })?;
+    atomic_rename(&temp_out, &out)?;
+
+    let bundle_bytes = fs::metadata(&out).map(|m| m.len()).unwrap_or(0);
+    let total_bytes = packed.iter().map(|(_, size)| size).sum();
+
+    Ok(SessionBundleExportResult {
+        bundle_path: out_path,
+        session_id,
+        files: packed.into_iter().map(|(name, _)| name).collect(),
+        total_bytes,
+        bundle_bytes,
+    })
+}
+
+/// Write the bundle archive, returning the packed (relative path, size) pairs
+fn write_bundle(
+    session_path: &Path,
+    session_id: &str,
+    out: &Path,
+) -> Result<Vec<(String, u64)>, String> {
+    let file = File::create(out).map_err(|e| format!("Failed to create bundle file: {e}"))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let mut packed = Vec::new();
+
+    let jsonl_name = format!("{session_id}.jsonl");
+    builder
+        .append_path_with_name(session_path, &jsonl_name)
+        .map_err(|e| format!("Failed to add session file to bundle: {e}"))?;
+    packed.push((
+        jsonl_name,
+        fs::metadata(session_path).map(|m| m.len()).unwrap_or(0),
+    ));
+
+    let companion_dir = session_path.with_extension("");
+    if companion_dir.is_dir() {
+        // Symlinks are skipped so a bundle never carries links out of the session
+        for entry in WalkDir::new(&companion_dir)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|e| e.file_type().is_file())
+        {
+            let Ok(relative) = entry.path().strip_prefix(&companion_dir) else {
+                continue;
+            };
+            let name = Path::new(session_id).join(relative);
+            builder
+                .append_path_with_name(entry.path(), &name)
+                .map_err(|e| format!("Failed to add {} to bundle: {e}", entry.path().display()))?;
+            packed.push((
+                name.to_string_lossy().replace('\\', "/"),
+                entry.metadata().map(|m| m.len()).unwrap_or(0),
+            ));
+        }
+    }
+
+    builder
+        .into_inner()
+        .and_then(GzEncoder::finish)
+        .map_err(|e| format!("Failed to finish bundle: {e}"))?;
+
+    Ok(packed)
+}
+
+/// Imports a session bundle into a project directory.
+///
+/// # Arguments
+/// * `bundle_path` - Path to a bundle created by `export_session_bundle`
+/// * `target_project` - Project storage directory directly inside `~/.claude/projects`
+///   (e.g. `~/.claude/projects/-home-user-app`); created if it does not exist
+///
+/// # Security
+/// - The target must resolve to a direct child of `~/.claude/projects`
+/// - Every archive entry must be a plain relative path (no `..`, no absolute paths)
+/// - Only regular files and directories are extracted (no symlinks or hard links)
+/// - All entries must belong to the single session in the bundle
+/// - Existing sessions are never overwritten
+#[command]
+pub async fn import_session_bundle(
+    bundle_path: String,
+    target_project: String,
+) -> Result<SessionBundleImportResult, String> {
+    import_session_bundle_in(&bundle_path, &target_project, &claude_dir()?)
+}
+
+fn import_session_bundle_in(
+    bundle_path: &str,
+    target_project: &str,
+    claude_dir: &Path,
+) -> Result<SessionBundleImportResult, String> {
+    let project_dir = validate_target_project(target_project, claude_dir)?;
+
+    // First pass: validate the whole archive before touching the filesystem
+    let session_id = inspect_bundle(Path::new(bundle_path))?;
+
+    let session_file = project_dir.join(format!("{session_id}.jsonl"));
+    let companion_dir = project_dir.join(&session_id);
+    if session_file.exists() || companion_dir.exists() {
+        return Err(format!(
+            "Session {session_id} already exists in {}",
+            project_dir.display()
+        ));
+    }
+
+    fs::create_dir_all(&project_dir)
+        .map_err(|e| format!("Failed to create project directory: {e}"))?;
+
+    // Second pass: extract
+    let mut archive = open_bundle(Path::new(bundle_path))?;
+    let mut files_written = Vec::new();
+    let mut total_bytes = 0u64;
+
+    for entry in archive
+        .entries()
+        .map_err(|e| format!("Failed to read bundle: {e}"))?
+    {
+        let mut entry = entry.map_err(|e| format!("Failed to read bundle entry: {e}"))?;
+        let relative = entry
+            .path()
+            .map_err(|e| format!("Invalid entry path: {e}"))?
+            .into_owned();
+        let dest = project_dir.join(&relative);
+
+        if entry.header().entry_type().is_dir() {
+            fs::create_dir_all(&dest).map_err(|e| format!("Failed to create directory: {e}"))?;
+            continue;
+        }
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {e}"))?;
+        }
+        let unpacked = entry
+            .unpack_in(&project_dir)
+            .map_err(|e| format!("Failed to extract {}: {e}", relative.display()))?;
+        if !unpacked {
+            return Err(format!("Refusing to extract {}", relative.display()));
+        }
+
+        total_bytes += entry.size();
+        files_written.push(dest.to_string_lossy().to_string());
+    }
+
+    Ok(SessionBundleImportResult {
+        session_id,
+        file_path: session_file.to_string_lossy().to_string(),
+        project_path: project_dir.to_string_lossy().to_string(),
+        files_written,
+        total_bytes,
+    })
+}
+
+fn open_bundle(bundle_path: &Path) -> Result<tar::Archive<GzDecoder<File>>, String> {
+    let file = File::open(bundle_path).map_err(|e| format!("Failed to open bundle: {e}"))?;
+    Ok(tar::Archive::new(GzDecoder::new(file)))
+}
+
+/// The user's Claude data directory (`~/.claude`)
+pub(crate) fn claude_dir() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|home| home.join(".claude"))
+        .ok_or_else(|| "Could not determine home directory".to_string())
+}
+
+/// Validate the target project directory path and its encoded name.
+///
+/// The target must resolve to a direct child of `<claude_dir>/projects`; it
+/// does not have to exist yet. Returns the resolved project path.
+pub(crate) fn validate_target_project(
+    target_project: &str,
+    claude_dir: &Path,
+) -> Result<PathBuf, String> {
+    let project_dir = PathBuf::from(target_project);
+
+    if !project_dir.is_absolute() {
+        return Err("Target project path must be absolute".to_string());
+    }
+    if project_dir
+        .components()
+        .any(|c| matches!(c, Component::ParentDir))
+    {
+        return Err("Target project path must not contain '..'".to_string());
+    }
+    if let Ok(metadata) = fs::symlink_metadata(&project_dir) {
+        if metadata.file_type().is_symlink() {
+            return Err("Target project path cannot be a symlink".to_string());
+        }
+        if !metadata.is_dir() {
+            return Err("Target project path is not a directory".to_string());
+        }
+    }
+
+    let name = project_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| "Invalid target project path".to_string())?;
+    if !BUNDLE_NAME_REGEX.is_match(&name) {
+        return Err(format!(
+            "'{name}' is not a valid encoded project directory name"
+        ));
+    }
+
+    let projects_root = claude_dir
+        .join("projects")
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve projects directory: {e}"))?;
+    let parent = project_dir
+        .parent()
+        .ok_or_else(|| "Invalid target project path".to_string())?;
+    if ensure_within_root(parent, &projects_root)? != projects_root {
+        return Err(format!(
+            "Target project must be directly inside {}",
+            projects_root.display()
+        ));
+    }
+
+    Ok(projects_root.join(name))
+}
+
+/// Validate every entry of the bundle and return the session id it contains
+fn inspect_bundle(bundle_path: &Path) -> Result<String, String> {
+    let mut archive = open_bundle(bundle_path)?;
+    let mut session_ids = HashSet::new();
+    let mut roots = HashSet::new();
+
+    for entry in archive
+        .entries()
+        .map_err(|e| format!("Failed to read bundle: {e}"))?
+    {
+        let entry = entry.map_err(|e| format!("Failed to read bundle entry: {e}"))?;
+        let entry_type = entry.header().entry_type();
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            return Err("Bundle contains unsupported entry types (links or devices)".to_string());
+        }
+
+        let path = entry
+            .path()
+            .map_err(|e| format!("Invalid entry path: {e}"))?
+            .into_owned();
+        let root = validate_entry_path(&path)?;
+
+        let is_top_level_file = path.components().count() == 1 && entry_type.is_file();
+        if is_top_level_file {
+            let id = root
+                .strip_suffix(".jsonl")
+                .ok_or_else(|| format!("Unexpected file in bundle: {}", path.display()))?;
+            session_ids.insert(id.to_string());
+        } else {
+            roots.insert(root);
+        }
+    }
+
+    let mut ids = session_ids.into_iter();
+    let session_id = match (ids.next(), ids.next()) {
+        (Some(id), None) => id,
+        (None, _) => return Err("Bundle does not contain a session file".to_string()),
+        (Some(_), Some(_)) => return Err("Bundle contains more than one session".to_string()),
+    };
+
+    if !BUNDLE_NAME_REGEX.is_match(&session_id) {
+        return Err(format!("Invalid session id in bundle: {session_id}"));
+    }
+    if let Some(root) = roots.iter().find(|r| **r != session_id) {
+        return Err(format!("Unexpected directory in bundle: {root}"));
+    }
+
+    Ok(session_id)
+}
+
+/// Reject anything but plain relative paths; returns the first path component
+fn validate_entry_path(path: &Path) -> Result<String, String> {
+    let mut first = None;
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => {
+                if first.is_none() {
+                    first = Some(part.to_string_lossy().to_string());
+                }
+            }
+            Component::CurDir => {}
+            _ => {
+                return Err(format!(
+                    "Bundle entry has an unsafe path: {}",
+                    path.display()
+                ))
+            }
+        }
+    }
+    first.ok_or_else(|| "Bundle entry has an empty path".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn create_session(dir: &Path) -> PathBuf {
+        let session = dir.join("abc-123.jsonl");
+        fs::write(&session, "{\"type\":\"user\"}\n").unwrap();
+        let subagents = dir.join("abc-123").join("subagents");
+        fs::create_dir_all(&subagents).unwrap();
+        fs::write(
+            subagents.join("agent-1.jsonl"),
+            "{\"type\":\"assistant\"}\n",
+        )
+        .unwrap();
+        session
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let source = TempDir::new().unwrap();
+        let session = create_session(source.path());
+        let bundle = source.path().join("bundle.tar.gz");
+
+        let exported = export_session_bundle(
+            session.to_string_lossy().to_string(),
+            bundle.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(exported.session_id, "abc-123");
+        assert_eq!(exported.files.len(), 2);
+        assert!(exported
+            .files
+            .contains(&"abc-123/subagents/agent-1.jsonl".to_string()));
+
+        let target = TempDir::new().unwrap();
+        fs::create_dir_all(target.path().join("projects")).unwrap();
+        let project = target.path().join("projects").join("-home-user-app");
+        let imported = import_session_bundle_in(
+            &bundle.to_string_lossy(),
+            &project.to_string_lossy(),
+            target.path(),
+        )
+        .unwrap();
+
+        assert_eq!(imported.session_id, "abc-123");
+        assert_eq!(imported.files_written.len(), 2);
+        assert_eq!(
+            fs::read_to_string(project.join("abc-123.jsonl")).unwrap(),
+            "{\"type\":\"user\"}\n"
+        );
+        assert!(project
+            .join("abc-123")
+            .join("subagents")
+            .join("agent-1.jsonl")
+            .is_file());
+    }
+
+    #[tokio::test]
+    async fn test_import_refuses_to_overwrite_existing_session() {
+        let source = TempDir::new().unwrap();
+        let session = create_session(source.path());
+        let bundle = source.path().join("bundle.tar.gz");
+        export_session_bundle(
+            session.to_string_lossy().to_string(),
+            bundle.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        let project = source.path().join("projects").join("-existing-project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("abc-123.jsonl"), "original").unwrap();
+
+        let result = import_session_bundle_in(
+            &bundle.to_string_lossy(),
+            &project.to_string_lossy(),
+            source.path(),
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            fs::read_to_string(project.join("abc-123.jsonl")).unwrap(),
+            "original"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_path_traversal() {
+        let temp_dir = TempDir::new().unwrap();
+        let bundle = temp_dir.path().join("evil.tar.gz");
+
+        // tar::Builder refuses to write `..` paths, so craft the header by hand
+        let file = File::create(&bundle).unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        let data = b"evil";
+        let mut header = tar::Header::new_gnu();
+        header.as_gnu_mut().unwrap().name[..13].copy_from_slice(b"../escape.txt");
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append(&header, &data[..]).unwrap();
+        builder
+            .into_inner()
+            .unwrap()
+            .finish()
+            .unwrap()
+            .flush()
+            .unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("projects")).unwrap();
+        let project = temp_dir.path().join("projects").join("-tmp-project");
+        let result = import_session_bundle_in(
+            &bundle.to_string_lossy(),
+            &project.to_string_lossy(),
+            temp_dir.path(),
+        );
+
+        assert!(result.is_err());
+        assert!(!temp_dir.path().join("projects").join("escape.txt").exists());
+        assert!(!project.exists());
+    }
+
+    #[test]
+    fn test_validate_target_project() {
+        let claude = TempDir::new().unwrap();
+        let projects = claude.path().join("projects");
+        fs::create_dir_all(projects.join("-home-user-app")).unwrap();
+        fs::create_dir_all(claude.path().join("todos")).unwrap();
+        let validate =
+            |path: &Path| validate_target_project(&path.to_string_lossy(), claude.path());
+
+        assert!(validate_target_project("relative/-project", claude.path()).is_err());
+        assert!(validate(&projects.join("..").join("todos").join("-project")).is_err());
+        assert!(validate(&projects.join("not valid!")).is_err());
+        assert!(validate(&projects.join("-home-user-app")).is_ok());
+        assert!(validate(&projects.join("-new-project")).is_ok());
+
+        // Anything that is not a direct child of the projects root is refused
+        assert!(validate(&claude.path().join("todos").join("-project")).is_err());
+        assert!(validate(&projects.join("-home-user-app").join("-nested")).is_err());
+        assert!(validate(&claude.path().join("-project")).is_err());
+    }
+}
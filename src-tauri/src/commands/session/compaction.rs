@@ -0,0 +1,302 @@
+//! Context compaction detection
+//!
+//! Locates the points where Claude compacted the conversation context so the
+//! UI can show where a long session was summarized, and splits sessions into
+//! segments at those points.
+
+use super::raw::map_session_file;
+use crate::commands::fs_utils::write_file_atomically;
+use crate::utils::find_line_ranges;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::command;
+
+/// A point in a session where the context was compacted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactionEvent {
+    /// 0-based line index of the compaction marker
+    pub line_index: usize,
+    pub uuid: Option<String>,
+    pub timestamp: Option<String>,
+    /// Marker that identified the event:
+    /// `compact_boundary`, `compact_summary` or `summary`
+    pub kind: String,
+    /// "manual" or "auto", from `compactMetadata` when present
+    pub trigger: Option<String>,
+    /// Token count before compaction, from `compactMetadata` when present
+    pub pre_tokens: Option<u64>,
+}
+
+/// Minimal view of a log line used for compaction detection
+#[derive(Debug, Deserialize)]
+struct CompactionProbe {
+    #[serde(rename = "type")]
+    message_type: String,
+    subtype: Option<String>,
+    uuid: Option<String>,
+    timestamp: Option<String>,
+    #[serde(rename = "isCompactSummary")]
+    is_compact_summary: Option<bool>,
+    #[serde(rename = "compactMetadata")]
+    compact_metadata: Option<serde_json::Value>,
+}
+
+impl CompactionProbe {
+    fn marker_kind(&self) -> Option<&'static str> {
+        if self.message_type == "system" && self.subtype.as_deref() == Some("compact_boundary") {
+            Some("compact_boundary")
+        } else if self.is_compact_summary.unwrap_or(false) {
+            Some("compact_summary")
+        } else if self.message_type == "summary" {
+            Some("summary")
+        } else {
+            None
+        }
+    }
+}
+
+/// Detect compaction events in memory-mapped session data.
+///
+/// A compaction usually writes several markers in a row (a boundary followed
+/// by the summary message); only the first marker after real conversation is
+/// reported. Markers before any conversation, such as the title summaries at
+/// the top of resumed sessions, are ignored.
+pub(crate) fn detect_compaction_events(data: &[u8]) -> Vec<CompactionEvent> {
+    let mut events = Vec::new();
+    let mut conversation_since_marker = false;
+
+    for (line_index, (start, end)) in find_line_ranges(data).into_iter().enumerate() {
+        // simd-json requires mutable slice
+        let mut line_bytes = data[start..end].to_vec();
+        let Ok(probe) = simd_json::serde::from_slice::<CompactionProbe>(&mut line_bytes) else {
+            continue;
+        };
+
+        let Some(kind) = probe.marker_kind() else {
+            if matches!(probe.message_type.as_str(), "user" | "assistant") {
+                conversation_since_marker = true;
+            }
+            continue;
+        };
+
+        if !conversation_since_marker {
+            continue;
+        }
+        conversation_since_marker = false;
+
+        let metadata = probe.compact_metadata.as_ref();
+        events.push(CompactionEvent {
+            line_index,
+            trigger: metadata
+                .and_then(|m| m.get("trigger"))
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string),
+            pre_tokens: metadata
+                .and_then(|m| m.get("preTokens"))
+                .and_then(serde_json::Value::as_u64),
+            kind: kind.to_string(),
+            uuid: probe.uuid,
+            timestamp: probe.timestamp,
+        });
+    }
+
+    events
+}
+
+/// Find context compaction events in a session.
+///
+/// Events are detected from `compact_boundary` system entries, `isCompactSummary`
+/// messages and summary entries. Returns an empty list when the session was
+/// never compacted.
+#[command]
+pub async fn find_compaction_events(file_path: String) -> Result<Vec<CompactionEvent>, String> {
+    let mmap = map_session_file(&file_path)?;
+    Ok(detect_compaction_events(&mmap))
+}
+
+/// Output filename for a segment, e.g. `abc-part001.jsonl`
+fn segment_file_name(stem: &str, index: usize) -> String {
+    format!("{stem}-part{:03}.jsonl", index + 1)
+}
+
+/// Split a session into one JSONL file per compaction segment.
+///
+/// # Arguments
+/// * `file_path` - Session JSONL file (left unmodified)
+/// * `out_dir` - Existing directory for the segment files
+///
+/// Each segment starts at a compaction marker and contains the original lines
+/// verbatim. Files are named `{session}-part001.jsonl`, `{session}-part002.jsonl`,
+/// ...; a session without compaction produces a single copy. Fails without
+/// writing anything if any output file already exists.
+#[command]
+pub async fn split_session_at_compaction(
+    file_path: String,
+    out_dir: String,
+) -> Result<Vec<String>, String> {
+    let out_dir_path = PathBuf::from(&out_dir);
+    if !out_dir_path.is_dir() {
+        return Err(format!("Output directory does not exist: {out_dir}"));
+    }
+    let stem = Path::new(&file_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .ok_or_else(|| "Invalid session filename".to_string())?;
+
+    let mmap = map_session_file(&file_path)?;
+    let line_ranges = find_line_ranges(&mmap);
+
+    let mut boundaries: Vec<usize> = detect_compaction_events(&mmap)
+        .into_iter()
+        .map(|event| event.line_index)
+        .collect();
+    boundaries.insert(0, 0);
+    boundaries.push(line_ranges.len());
+
+    let out_paths: Vec<PathBuf> = (0..boundaries.len() - 1)
+        .map(|index| out_dir_path.join(segment_file_name(&stem, index)))
+        .collect();
+    if let Some(existing) = out_paths.iter().find(|path| path.exists()) {
+        return Err(format!(
+            "Output file already exists: {}",
+            existing.display()
+        ));
+    }
+
+    for (segment, out_path) in boundaries.windows(2).zip(&out_paths) {
+        let mut output = Vec::new();
+        for &(start, end) in &line_ranges[segment[0]..segment[1]] {
+            output.extend_from_slice(&mmap[start..end]);
+            output.push(b'\n');
+        }
+        write_file_atomically(out_path, &output)?;
+    }
+
+    Ok(out_paths
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const USER: &str = r#"{"type":"user","uuid":"u1","message":{"role":"user","content":"hi"}}"#;
+    const ASSISTANT: &str =
+        r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":"hello"}}"#;
+    const BOUNDARY: &str = r#"{"type":"system","subtype":"compact_boundary","uuid":"b1","timestamp":"2025-01-01T10:00:00Z","compactMetadata":{"trigger":"auto","preTokens":155000}}"#;
+    const COMPACT_SUMMARY: &str = r#"{"type":"user","uuid":"s1","isCompactSummary":true,"message":{"role":"user","content":"This session is being continued"}}"#;
+
+    #[test]
+    fn test_detect_compaction_events() {
+        let data = [
+            r#"{"type":"summary","summary":"Title","leafUuid":"x"}"#,
+            USER,
+            ASSISTANT,
+            BOUNDARY,
+            COMPACT_SUMMARY,
+            ASSISTANT,
+            USER,
+            COMPACT_SUMMARY,
+            ASSISTANT,
+        ]
+        .join("\n");
+
+        let events = detect_compaction_events(data.as_bytes());
+        assert_eq!(events.len(), 2);
+
+        assert_eq!(events[0].line_index, 3);
+        assert_eq!(events[0].kind, "compact_boundary");
+        assert_eq!(events[0].uuid.as_deref(), Some("b1"));
+        assert_eq!(events[0].timestamp.as_deref(), Some("2025-01-01T10:00:00Z"));
+        assert_eq!(events[0].trigger.as_deref(), Some("auto"));
+        assert_eq!(events[0].pre_tokens, Some(155_000));
+
+        assert_eq!(events[1].line_index, 7);
+        assert_eq!(events[1].kind, "compact_summary");
+        assert!(events[1].trigger.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_compaction_events_none() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        fs::write(&path, [USER, ASSISTANT].join("\n")).unwrap();
+
+        let events = find_compaction_events(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_split_session_at_compaction() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("abc.jsonl");
+        let original = [USER, ASSISTANT, BOUNDARY, COMPACT_SUMMARY, ASSISTANT].join("\n");
+        fs::write(&path, &original).unwrap();
+        let out_dir = temp_dir.path().join("out");
+        fs::create_dir(&out_dir).unwrap();
+
+        let outputs = split_session_at_compaction(
+            path.to_string_lossy().to_string(),
+            out_dir.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outputs.len(), 2);
+        assert!(outputs[0].ends_with("abc-part001.jsonl"));
+        assert!(outputs[1].ends_with("abc-part002.jsonl"));
+        assert_eq!(
+            fs::read_to_string(&outputs[0]).unwrap(),
+            format!("{USER}\n{ASSISTANT}\n")
+        );
+        assert_eq!(
+            fs::read_to_string(&outputs[1]).unwrap(),
+            format!("{BOUNDARY}\n{COMPACT_SUMMARY}\n{ASSISTANT}\n")
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+
+        // Running again must not overwrite the existing segments
+        let result = split_session_at_compaction(
+            path.to_string_lossy().to_string(),
+            out_dir.to_string_lossy().to_string(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_split_session_without_compaction_copies() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("abc.jsonl");
+        fs::write(&path, format!("{USER}\n{ASSISTANT}\n")).unwrap();
+
+        let outputs = split_session_at_compaction(
+            path.to_string_lossy().to_string(),
+            temp_dir
+                .path()
+                .join("missing")
+                .to_string_lossy()
+                .to_string(),
+        )
+        .await;
+        assert!(outputs.is_err());
+
+        let outputs = split_session_at_compaction(
+            path.to_string_lossy().to_string(),
+            temp_dir.path().to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            fs::read_to_string(&outputs[0]).unwrap(),
+            format!("{USER}\n{ASSISTANT}\n")
+        );
+    }
+}
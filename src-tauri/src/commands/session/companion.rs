@@ -0,0 +1,326 @@
+//! Companion directory integrity
+//!
+//! A session's companion directory (the session path without `.jsonl`) holds
+//! artifacts such as subagent logs and tool outputs that messages refer to.
+//! This module finds references whose target has since been removed and
+//! classifies the artifacts for storage review.
+
+use super::raw::map_session_file;
+use crate::utils::find_line_ranges;
+use memchr::memmem;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tauri::command;
+use walkdir::WalkDir;
+
+/// Bytes read from the start of a file to sniff its type
+const SNIFF_BYTES: usize = 8192;
+
+/// Extensions treated as images regardless of content (SVG is text, BMP has
+/// a weak signature)
+const IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "gif", "webp", "bmp", "svg"];
+
+/// Extensions treated as JSON when the content is text
+const JSON_EXTENSIONS: [&str; 2] = ["json", "jsonl"];
+
+/// Leading bytes of common image formats
+const IMAGE_MAGIC: [&[u8]; 4] = [b"\x89PNG\r\n\x1a\n", b"\xff\xd8\xff", b"GIF87a", b"GIF89a"];
+
+/// A reference into the companion directory whose target is missing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenReference {
+    /// 0-based line index of the referencing message
+    pub line_index: usize,
+    pub missing_path: String,
+}
+
+/// Detected content type of a companion file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompanionFileKind {
+    Text,
+    Json,
+    Image,
+    Binary,
+    /// The file could not be read
+    Unknown,
+}
+
+/// A file inside a session's companion directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompanionFile {
+    pub path: String,
+    /// Path relative to the companion directory
+    pub relative_path: String,
+    pub size_bytes: u64,
+    pub kind: CompanionFileKind,
+}
+
+/// Classified contents of a companion directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompanionListing {
+    pub total_bytes: u64,
+    /// Files sorted by relative path
+    pub files: Vec<CompanionFile>,
+}
+
+/// Whether a sniffed prefix looks like text: no NUL bytes and valid UTF-8,
+/// allowing a multi-byte character cut off at the end of the buffer
+fn looks_like_text(head: &[u8]) -> bool {
+    if head.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+/// Classify a file from its extension and first bytes
+fn classify_file(path: &Path) -> CompanionFileKind {
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    let read =
+        File::open(path).and_then(|file| file.take(SNIFF_BYTES as u64).read_to_end(&mut head));
+    if read.is_err() {
+        return CompanionFileKind::Unknown;
+    }
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    let is_webp = head.len() >= 12 && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP";
+
+    if is_webp
+        || IMAGE_MAGIC.iter().any(|magic| head.starts_with(magic))
+        || IMAGE_EXTENSIONS.contains(&extension.as_str())
+    {
+        CompanionFileKind::Image
+    } else if !looks_like_text(&head) {
+        CompanionFileKind::Binary
+    } else if JSON_EXTENSIONS.contains(&extension.as_str()) {
+        CompanionFileKind::Json
+    } else {
+        CompanionFileKind::Text
+    }
+}
+
+/// List and classify the files in a session's companion directory.
+///
+/// Each file is sniffed from its first bytes (image signatures, NUL bytes,
+/// UTF-8 validity) and its extension; files that cannot be read are marked
+/// `unknown`. Symlinks are skipped. Returns an empty listing if the session
+/// has no companion directory.
+#[command]
+pub async fn classify_companion_files(file_path: String) -> Result<CompanionListing, String> {
+    let companion_dir = Path::new(&file_path).with_extension("");
+    if !companion_dir.is_dir() {
+        return Ok(CompanionListing::default());
+    }
+
+    let files: Vec<CompanionFile> = WalkDir::new(&companion_dir)
+        .follow_links(false)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .map(|entry| CompanionFile {
+            path: entry.path().to_string_lossy().to_string(),
+            relative_path: entry
+                .path()
+                .strip_prefix(&companion_dir)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .to_string(),
+            size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            kind: classify_file(entry.path()),
+        })
+        .collect();
+
+    Ok(CompanionListing {
+        total_bytes: files.iter().map(|f| f.size_bytes).sum(),
+        files,
+    })
+}
+
+/// Characters that end a path embedded in free text
+fn is_path_terminator(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '"' | '\'' | '`' | '<' | '>' | '(' | ')' | '[' | ']')
+}
+
+/// Collect every path starting with `prefix` from the strings in `value`
+fn collect_references(value: &Value, prefix: &str, out: &mut BTreeSet<String>) {
+    match value {
+        Value::String(text) => {
+            for (pos, _) in text.match_indices(prefix) {
+                let rest = &text[pos..];
+                let end = rest.find(is_path_terminator).unwrap_or(rest.len());
+                let path = rest[..end].trim_end_matches(['.', ',', ';', ':']);
+                out.insert(path.to_string());
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_references(item, prefix, out);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values() {
+                collect_references(item, prefix, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Report references into a session's companion directory that no longer
+/// exist on disk.
+///
+/// Any string in a message (tool inputs, tool results, text) containing the
+/// companion directory's absolute path counts as a reference. Returns an empty
+/// list if the session has no companion directory.
+#[command]
+pub async fn verify_companion_references(
+    file_path: String,
+) -> Result<Vec<BrokenReference>, String> {
+    let companion_dir = Path::new(&file_path).with_extension("");
+    if !companion_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mmap = map_session_file(&file_path)?;
+    let prefix = companion_dir.to_string_lossy().to_string();
+
+    // The session id is escape-free in JSON, so it is a cheap pre-filter
+    let stem = companion_dir
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let finder = memmem::Finder::new(stem.as_bytes());
+
+    let mut broken = Vec::new();
+    for (line_index, &(start, end)) in find_line_ranges(&mmap).iter().enumerate() {
+        let line = &mmap[start..end];
+        if finder.find(line).is_none() {
+            continue;
+        }
+        let Ok(value) = serde_json::from_slice::<Value>(line) else {
+            continue;
+        };
+
+        let mut references = BTreeSet::new();
+        collect_references(&value, &prefix, &mut references);
+        broken.extend(
+            references
+                .into_iter()
+                .filter(|path| !Path::new(path).exists())
+                .map(|missing_path| BrokenReference {
+                    line_index,
+                    missing_path,
+                }),
+        );
+    }
+
+    Ok(broken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_verify_companion_references_reports_missing_targets() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = temp_dir.path().join("abc-123.jsonl");
+        let companion = temp_dir.path().join("abc-123");
+        fs::create_dir_all(companion.join("tool-results")).unwrap();
+        let present = companion.join("tool-results").join("out-1.txt");
+        fs::write(&present, "ok").unwrap();
+        let missing = companion.join("tool-results").join("out-2.txt");
+
+        let lines = [
+            serde_json::json!({"type":"user","message":{"content":[{"type":"tool_result","content":format!("Saved to {}.", present.display())}]}}),
+            serde_json::json!({"type":"user","message":{"content":[{"type":"tool_result","content":format!("Output saved to {} (truncated)", missing.display())}]}}),
+            serde_json::json!({"type":"assistant","message":{"content":"no references"}}),
+        ];
+        let content: Vec<String> = lines.iter().map(Value::to_string).collect();
+        fs::write(&session, content.join("\n")).unwrap();
+
+        let broken = verify_companion_references(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            broken,
+            vec![BrokenReference {
+                line_index: 1,
+                missing_path: missing.to_string_lossy().to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_companion_references_without_companion_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = temp_dir.path().join("abc-123.jsonl");
+        fs::write(&session, r#"{"type":"user"}"#).unwrap();
+
+        let broken = verify_companion_references(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert!(broken.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_classify_companion_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = temp_dir.path().join("abc-123.jsonl");
+        fs::write(&session, "").unwrap();
+        let companion = temp_dir.path().join("abc-123");
+        fs::create_dir_all(companion.join("subagents")).unwrap();
+        fs::write(companion.join("notes.txt"), "plain text ✓").unwrap();
+        fs::write(companion.join("subagents").join("agent.jsonl"), "{}\n").unwrap();
+        fs::write(companion.join("shot.dat"), b"\x89PNG\r\n\x1a\n....").unwrap();
+        fs::write(companion.join("blob.bin"), [0u8, 1, 2, 3]).unwrap();
+
+        let listing = classify_companion_files(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        let kinds: Vec<(&str, CompanionFileKind)> = listing
+            .files
+            .iter()
+            .map(|f| (f.relative_path.as_str(), f.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("blob.bin", CompanionFileKind::Binary),
+                ("notes.txt", CompanionFileKind::Text),
+                ("shot.dat", CompanionFileKind::Image),
+                (
+                    Path::new("subagents").join("agent.jsonl").to_str().unwrap(),
+                    CompanionFileKind::Json
+                ),
+            ]
+        );
+        assert_eq!(
+            listing.total_bytes,
+            listing.files.iter().map(|f| f.size_bytes).sum::<u64>()
+        );
+        assert_eq!(listing.files[0].size_bytes, 4);
+    }
+
+    #[test]
+    fn test_looks_like_text_allows_cut_multibyte_char() {
+        let text = "ok ✓".as_bytes();
+        assert!(looks_like_text(&text[..text.len() - 1]));
+        assert!(!looks_like_text(b"\xff\xfe bad"));
+    }
+}
@@ -0,0 +1,566 @@
+//! Resumed-session continuation detection
+//!
+//! Resuming a session can make Claude start a new JSONL file that continues
+//! the old one. The new file's opening lines point back at the old session
+//! through `sessionId`, `leafUuid` (summary entries) or `parentUuid`.
+//! Following those links both ways gives a session's lineage.
+
+use super::raw::map_session_file;
+use super::resume::SESSION_ID_REGEX;
+use super::timing::{compute_session_time_bounds, TimeBounds};
+use crate::utils::find_line_ranges;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::command;
+use walkdir::WalkDir;
+
+/// Lines inspected at the top of a candidate file before giving up
+const HEADER_SCAN_LINES: usize = 50;
+
+/// Minimal view of a log line carrying only linking fields
+#[derive(Debug, Deserialize)]
+struct LinkProbe {
+    #[serde(rename = "type")]
+    message_type: Option<String>,
+    uuid: Option<String>,
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+    #[serde(rename = "leafUuid")]
+    leaf_uuid: Option<String>,
+    #[serde(rename = "parentUuid")]
+    parent_uuid: Option<String>,
+}
+
+fn read_link_probes(file_path: &Path, max_lines: Option<usize>) -> Vec<LinkProbe> {
+    let Ok(mmap) = map_session_file(&file_path.to_string_lossy()) else {
+        return Vec::new();
+    };
+
+    find_line_ranges(&mmap)
+        .into_iter()
+        .take(max_lines.unwrap_or(usize::MAX))
+        .filter_map(|(start, end)| {
+            // simd-json requires mutable slice
+            let mut line_bytes = mmap[start..end].to_vec();
+            simd_json::serde::from_slice::<LinkProbe>(&mut line_bytes).ok()
+        })
+        .collect()
+}
+
+/// Check whether a file's opening lines reference one of the given sessions.
+/// Only lines up to and including the first conversation message are considered.
+fn continues_any(file_path: &Path, session_ids: &HashSet<String>, uuids: &HashSet<String>) -> bool {
+    let own_id = file_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    for probe in read_link_probes(file_path, Some(HEADER_SCAN_LINES)) {
+        let links_session = probe
+            .session_id
+            .as_ref()
+            .is_some_and(|id| *id != own_id && session_ids.contains(id));
+        let links_message = [&probe.leaf_uuid, &probe.parent_uuid]
+            .into_iter()
+            .flatten()
+            .any(|uuid| uuids.contains(uuid));
+        if links_session || links_message {
+            return true;
+        }
+
+        if matches!(probe.message_type.as_deref(), Some("user" | "assistant")) {
+            break;
+        }
+    }
+    false
+}
+
+/// Find the session file for an id below a projects directory
+fn find_session_file(projects_path: &Path, session_id: &str) -> Option<PathBuf> {
+    let file_name = format!("{session_id}.jsonl");
+    WalkDir::new(projects_path)
+        .min_depth(2)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .find(|e| e.file_name().to_string_lossy() == file_name)
+        .map(|e| e.path().to_path_buf())
+}
+
+/// Find sessions that continue the given session after a resume.
+///
+/// Looks in the same project for files whose opening lines reference the
+/// session (or, transitively, one of its continuations) and returns their
+/// paths in chronological order of their first message.
+#[command]
+pub async fn find_session_continuations(
+    claude_path: String,
+    session_id: String,
+) -> Result<Vec<String>, String> {
+    if !SESSION_ID_REGEX.is_match(&session_id) {
+        return Err("Invalid session ID format".to_string());
+    }
+
+    let projects_path = PathBuf::from(&claude_path).join("projects");
+    let Some(origin) = find_session_file(&projects_path, &session_id) else {
+        return Err(format!("Session not found: {session_id}"));
+    };
+    let Some(project_dir) = origin.parent() else {
+        return Ok(Vec::new());
+    };
+
+    let mut candidates: Vec<PathBuf> = WalkDir::new(project_dir)
+        .min_depth(1)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .map(|e| e.path().to_path_buf())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("jsonl") && *p != origin)
+        .collect();
+
+    let mut session_ids: HashSet<String> = HashSet::from([session_id]);
+    let mut uuids: HashSet<String> = read_link_probes(&origin, None)
+        .into_iter()
+        .filter_map(|p| p.uuid)
+        .collect();
+    let mut continuations: Vec<PathBuf> = Vec::new();
+
+    // Follow chains of resumes until no new continuation is found
+    loop {
+        let (found, rest): (Vec<PathBuf>, Vec<PathBuf>) = candidates
+            .into_par_iter()
+            .partition(|path| continues_any(path, &session_ids, &uuids));
+        candidates = rest;
+        if found.is_empty() {
+            break;
+        }
+
+        for path in &found {
+            if let Some(stem) = path.file_stem() {
+                session_ids.insert(stem.to_string_lossy().to_string());
+            }
+            uuids.extend(
+                read_link_probes(path, None)
+                    .into_iter()
+                    .filter_map(|p| p.uuid),
+            );
+        }
+        continuations.extend(found);
+    }
+
+    let mut ordered: Vec<(Option<chrono::DateTime<chrono::Utc>>, PathBuf)> = continuations
+        .into_par_iter()
+        .map(|path| {
+            let first = compute_session_time_bounds(&path).ok().and_then(|b| b.0);
+            (first, path)
+        })
+        .collect();
+    ordered.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+    Ok(ordered
+        .into_iter()
+        .map(|(_, path)| path.to_string_lossy().to_string())
+        .collect())
+}
+
+/// How a session points back at the session it continues
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineageLinkKind {
+    /// A line carries the other session's `sessionId`
+    SessionId,
+    /// A summary entry's `leafUuid` names a message of the other session
+    Summary,
+    /// A message's `parentUuid` names a message of the other session
+    ParentUuid,
+}
+
+/// Where a session sits relative to the one the lineage was requested for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineageRelation {
+    Ancestor,
+    Current,
+    Descendant,
+}
+
+/// A session in a lineage chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineageNode {
+    pub session_id: String,
+    pub file_path: String,
+    /// Earliest message timestamp (RFC 3339)
+    pub first_timestamp: Option<String>,
+    pub relation: LineageRelation,
+    /// Session this one continues; `None` for the root of the chain
+    pub parent_session_id: Option<String>,
+    /// How the link to the parent was detected
+    pub link: Option<LineageLinkKind>,
+}
+
+/// Sessions related to a session through resumes and forks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lineage {
+    /// Ancestors from the root down, the session itself, then descendants
+    /// in chronological order
+    pub sessions: Vec<LineageNode>,
+    /// Links formed a loop; the walk stopped where it closed
+    pub cycle_detected: bool,
+}
+
+/// A session file of the project being walked for lineage
+struct LineageCandidate {
+    id: String,
+    path: PathBuf,
+    bounds: TimeBounds,
+    /// Message uuids written in the file
+    uuids: Vec<String>,
+}
+
+/// Find the session a file continues, from its opening lines.
+/// `uuid_owners` maps message uuids to the session that first wrote them.
+fn parent_link(
+    file_path: &Path,
+    own_id: &str,
+    session_ids: &HashSet<String>,
+    uuid_owners: &HashMap<String, String>,
+) -> Option<(String, LineageLinkKind)> {
+    let other_owner = |uuid: &Option<String>| {
+        uuid.as_ref()
+            .and_then(|uuid| uuid_owners.get(uuid))
+            .filter(|owner| *owner != own_id)
+            .cloned()
+    };
+
+    for probe in read_link_probes(file_path, Some(HEADER_SCAN_LINES)) {
+        if let Some(id) = probe
+            .session_id
+            .as_ref()
+            .filter(|id| *id != own_id && session_ids.contains(*id))
+        {
+            return Some((id.clone(), LineageLinkKind::SessionId));
+        }
+        if let Some(owner) = other_owner(&probe.leaf_uuid) {
+            return Some((owner, LineageLinkKind::Summary));
+        }
+        if let Some(owner) = other_owner(&probe.parent_uuid) {
+            return Some((owner, LineageLinkKind::ParentUuid));
+        }
+
+        if matches!(probe.message_type.as_deref(), Some("user" | "assistant")) {
+            break;
+        }
+    }
+    None
+}
+
+/// Build the chain of sessions a session continues and is continued by.
+///
+/// Every session in the project is linked to the one it continues, detected
+/// from its opening lines as in `find_session_continuations`. The walk goes
+/// up through parents to the root and down through all children; a session
+/// that copies earlier messages on resume does not claim them, because
+/// message uuids belong to the oldest session that wrote them. Revisiting a
+/// session ends the walk and sets `cycle_detected`.
+#[command]
+pub async fn session_lineage(claude_path: String, session_id: String) -> Result<Lineage, String> {
+    if !SESSION_ID_REGEX.is_match(&session_id) {
+        return Err("Invalid session ID format".to_string());
+    }
+
+    let projects_path = PathBuf::from(&claude_path).join("projects");
+    let Some(origin) = find_session_file(&projects_path, &session_id) else {
+        return Err(format!("Session not found: {session_id}"));
+    };
+    let Some(project_dir) = origin.parent() else {
+        return Err(format!("Session not found: {session_id}"));
+    };
+
+    // Sessions of the project, oldest first so originals own their uuids.
+    // A resume copying earlier messages starts at the same time as the
+    // original but ends later.
+    let mut sessions: Vec<LineageCandidate> = WalkDir::new(project_dir)
+        .min_depth(1)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .map(|e| e.path().to_path_buf())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .filter_map(|path| {
+            Some(LineageCandidate {
+                id: path.file_stem()?.to_string_lossy().to_string(),
+                bounds: compute_session_time_bounds(&path).unwrap_or((None, None)),
+                uuids: read_link_probes(&path, None)
+                    .into_iter()
+                    .filter_map(|p| p.uuid)
+                    .collect(),
+                path,
+            })
+        })
+        .collect();
+    sessions.sort_by(|a, b| a.bounds.cmp(&b.bounds).then_with(|| a.id.cmp(&b.id)));
+
+    let session_ids: HashSet<String> = sessions.iter().map(|s| s.id.clone()).collect();
+    let mut uuid_owners: HashMap<String, String> = HashMap::new();
+    for session in &sessions {
+        for uuid in &session.uuids {
+            uuid_owners
+                .entry(uuid.clone())
+                .or_insert_with(|| session.id.clone());
+        }
+    }
+
+    let parents: HashMap<String, (String, LineageLinkKind)> = sessions
+        .par_iter()
+        .filter_map(|s| {
+            parent_link(&s.path, &s.id, &session_ids, &uuid_owners).map(|link| (s.id.clone(), link))
+        })
+        .collect();
+    let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+    // `sessions` is chronological, so children are too
+    for session in &sessions {
+        if let Some((parent, _)) = parents.get(&session.id) {
+            children
+                .entry(parent.as_str())
+                .or_default()
+                .push(session.id.as_str());
+        }
+    }
+
+    let mut cycle_detected = false;
+    let mut visited: HashSet<&str> = HashSet::from([session_id.as_str()]);
+
+    let mut ancestors: Vec<&str> = Vec::new();
+    let mut current = session_id.as_str();
+    while let Some((parent, _)) = parents.get(current) {
+        if !visited.insert(parent.as_str()) {
+            cycle_detected = true;
+            break;
+        }
+        ancestors.push(parent.as_str());
+        current = parent.as_str();
+    }
+    ancestors.reverse();
+
+    let mut descendants: Vec<&str> = Vec::new();
+    let mut queue: Vec<&str> = vec![session_id.as_str()];
+    while let Some(id) = queue.pop() {
+        for &child in children.get(id).into_iter().flatten() {
+            if visited.insert(child) {
+                descendants.push(child);
+                queue.push(child);
+            } else {
+                cycle_detected = true;
+            }
+        }
+    }
+
+    let by_id: HashMap<&str, &LineageCandidate> =
+        sessions.iter().map(|s| (s.id.as_str(), s)).collect();
+    descendants.sort_by_key(|id| (by_id[id].bounds, *id));
+
+    let node = |id: &str, relation| {
+        let session = by_id[id];
+        let parent = parents.get(id);
+        LineageNode {
+            session_id: id.to_string(),
+            file_path: session.path.to_string_lossy().to_string(),
+            first_timestamp: session.bounds.0.map(|t| t.to_rfc3339()),
+            relation,
+            parent_session_id: parent.map(|(parent, _)| parent.clone()),
+            link: parent.map(|(_, link)| *link),
+        }
+    };
+
+    let sessions = ancestors
+        .iter()
+        .map(|id| node(id, LineageRelation::Ancestor))
+        .chain(std::iter::once(node(&session_id, LineageRelation::Current)))
+        .chain(
+            descendants
+                .iter()
+                .map(|id| node(id, LineageRelation::Descendant)),
+        )
+        .collect();
+
+    Ok(Lineage {
+        sessions,
+        cycle_detected,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_find_session_continuations() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("projects").join("-tmp-project");
+        fs::create_dir_all(&project_dir).unwrap();
+
+        fs::write(
+            project_dir.join("orig.jsonl"),
+            r#"{"type":"user","uuid":"o1","sessionId":"orig","timestamp":"2025-01-01T10:00:00Z"}"#,
+        )
+        .unwrap();
+        // Second continuation (continues `cont-a`), written first on purpose
+        fs::write(
+            project_dir.join("cont-b.jsonl"),
+            [
+                r#"{"type":"summary","summary":"Earlier work","leafUuid":"a1"}"#,
+                r#"{"type":"user","uuid":"b1","sessionId":"cont-b","timestamp":"2025-01-03T10:00:00Z"}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("cont-a.jsonl"),
+            r#"{"type":"user","uuid":"a1","parentUuid":"o1","sessionId":"cont-a","timestamp":"2025-01-02T10:00:00Z"}"#,
+        )
+        .unwrap();
+        // Mentions the session id only after its first message: not a continuation
+        fs::write(
+            project_dir.join("other.jsonl"),
+            [
+                r#"{"type":"user","uuid":"x1","sessionId":"other","timestamp":"2025-01-04T10:00:00Z"}"#,
+                r#"{"type":"assistant","uuid":"x2","parentUuid":"o1","sessionId":"other"}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let continuations = find_session_continuations(
+            temp_dir.path().to_string_lossy().to_string(),
+            "orig".to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(continuations.len(), 2);
+        assert!(continuations[0].ends_with("cont-a.jsonl"));
+        assert!(continuations[1].ends_with("cont-b.jsonl"));
+
+        let invalid = find_session_continuations(
+            temp_dir.path().to_string_lossy().to_string(),
+            "../orig".to_string(),
+        )
+        .await;
+        assert!(invalid.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_lineage() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("projects").join("-tmp-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let write = |name: &str, lines: &[&str]| {
+            fs::write(project_dir.join(format!("{name}.jsonl")), lines.join("\n")).unwrap();
+        };
+
+        write(
+            "root",
+            &[
+                r#"{"type":"user","uuid":"r1","sessionId":"root","timestamp":"2025-01-01T10:00:00Z"}"#,
+            ],
+        );
+        // Resume copies the parent's message before its own
+        write(
+            "mid",
+            &[
+                r#"{"type":"summary","summary":"Earlier","leafUuid":"r1"}"#,
+                r#"{"type":"user","uuid":"r1","sessionId":"mid","timestamp":"2025-01-01T10:00:00Z"}"#,
+                r#"{"type":"user","uuid":"m1","sessionId":"mid","timestamp":"2025-01-02T10:00:00Z"}"#,
+            ],
+        );
+        write(
+            "leaf",
+            &[
+                r#"{"type":"user","uuid":"l1","parentUuid":"m1","sessionId":"leaf","timestamp":"2025-01-03T10:00:00Z"}"#,
+            ],
+        );
+        write(
+            "fork",
+            &[
+                r#"{"type":"user","uuid":"f1","sessionId":"mid","timestamp":"2025-01-04T10:00:00Z"}"#,
+            ],
+        );
+        write(
+            "unrelated",
+            &[
+                r#"{"type":"user","uuid":"u1","sessionId":"unrelated","timestamp":"2025-01-05T10:00:00Z"}"#,
+            ],
+        );
+
+        let lineage = session_lineage(
+            temp_dir.path().to_string_lossy().to_string(),
+            "mid".to_string(),
+        )
+        .await
+        .unwrap();
+
+        let found: Vec<(&str, LineageRelation, Option<LineageLinkKind>)> = lineage
+            .sessions
+            .iter()
+            .map(|n| (n.session_id.as_str(), n.relation, n.link))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("root", LineageRelation::Ancestor, None),
+                (
+                    "mid",
+                    LineageRelation::Current,
+                    Some(LineageLinkKind::Summary)
+                ),
+                (
+                    "leaf",
+                    LineageRelation::Descendant,
+                    Some(LineageLinkKind::ParentUuid)
+                ),
+                (
+                    "fork",
+                    LineageRelation::Descendant,
+                    Some(LineageLinkKind::SessionId)
+                ),
+            ]
+        );
+        assert_eq!(
+            lineage.sessions[2].parent_session_id.as_deref(),
+            Some("mid")
+        );
+        assert!(!lineage.cycle_detected);
+    }
+
+    #[tokio::test]
+    async fn test_session_lineage_cycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("projects").join("-tmp-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(
+            project_dir.join("a.jsonl"),
+            r#"{"type":"user","uuid":"a1","sessionId":"b","timestamp":"2025-01-01T10:00:00Z"}"#,
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("b.jsonl"),
+            r#"{"type":"user","uuid":"b1","sessionId":"a","timestamp":"2025-01-02T10:00:00Z"}"#,
+        )
+        .unwrap();
+
+        let lineage = session_lineage(
+            temp_dir.path().to_string_lossy().to_string(),
+            "a".to_string(),
+        )
+        .await
+        .unwrap();
+        assert!(lineage.cycle_detected);
+        assert_eq!(lineage.sessions.len(), 2);
+    }
+}
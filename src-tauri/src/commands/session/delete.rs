@@ -1,12 +1,16 @@
 //! Session deletion module
 //!
 //! Provides functionality to permanently delete Claude Code sessions
-//! by removing the JSONL file and any associated companion directory.
+//! by removing the JSONL file and any associated companion directory, and
+//! lets the frontend pre-validate paths with the same safety checks.
 
+use crate::commands::error::CommandError;
+use crate::commands::fs_utils::ensure_within_root;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::path::Path;
 use tauri::command;
 
 lazy_static! {
@@ -29,7 +33,7 @@ pub struct DeleteSessionResult {
 ///
 /// # Returns
 /// * `Ok(DeleteSessionResult)` - Success with deletion details
-/// * `Err(String)` - Error description
+/// * `Err(CommandError)` - Error kind and description
 ///
 /// # Security
 /// - Path must be absolute
@@ -37,25 +41,31 @@ pub struct DeleteSessionResult {
 /// - File must be within ~/.claude directory
 /// - Filename must match safe pattern
 #[command]
-pub async fn delete_session(file_path: String) -> Result<DeleteSessionResult, String> {
+pub async fn delete_session(file_path: String) -> Result<DeleteSessionResult, CommandError> {
     let file_path_buf = std::path::PathBuf::from(&file_path);
 
     // 1. Validate file exists
     if !file_path_buf.exists() {
-        return Err(format!("Session file not found: {file_path}"));
+        return Err(CommandError::NotFound(format!(
+            "Session file not found: {file_path}"
+        )));
     }
 
     // 2. Validate path is within ~/.claude (reuse security checks from rename module)
     validate_delete_path(&file_path)?;
 
     // 3. Delete the JSONL file
-    fs::remove_file(&file_path_buf).map_err(|e| format!("Failed to delete session file: {e}"))?;
+    fs::remove_file(&file_path_buf)
+        .map_err(|e| CommandError::io("Failed to delete session file", &e))?;
 
     // 4. Delete companion directory if it exists (same name without .jsonl extension)
     let companion_dir = file_path_buf.with_extension("");
     let companion_dir_deleted = if companion_dir.is_dir() {
         fs::remove_dir_all(&companion_dir).map_err(|e| {
-            format!("Session file deleted but failed to remove companion directory: {e}")
+            CommandError::io(
+                "Session file deleted but failed to remove companion directory",
+                &e,
+            )
         })?;
         true
     } else {
@@ -69,75 +79,136 @@ pub async fn delete_session(file_path: String) -> Result<DeleteSessionResult, St
     })
 }
 
-/// Validates that the file path is safe for deletion.
-///
-/// Security checks:
-/// 1. Path must be absolute
-/// 2. No symlinks in any path component
-/// 3. Filename must match safe pattern
-/// 4. File must be within ~/.claude directory
-fn validate_delete_path(file_path: &str) -> Result<(), String> {
-    let file_path_buf = std::path::PathBuf::from(file_path);
+/// Outcome of a single path safety check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathSafetyCheck {
+    /// Check identifier: `absolute`, `no_symlinks`, `safe_filename` or `within_claude_dir`
+    pub check: String,
+    pub passed: bool,
+    /// Why the check failed (None when passed)
+    pub error: Option<CommandError>,
+}
+
+/// Result of running every path safety check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathSafety {
+    pub file_path: String,
+    /// True only if all checks passed
+    pub safe: bool,
+    pub checks: Vec<PathSafetyCheck>,
+}
+
+type PathCheckFn = fn(&Path) -> Result<(), CommandError>;
+
+/// Safety checks for destructive operations, in the order they are enforced.
+/// Shared by `delete_session` and `check_path_safe` so the two cannot drift apart.
+const PATH_SAFETY_CHECKS: [(&str, PathCheckFn); 4] = [
+    ("absolute", check_absolute),
+    ("no_symlinks", check_no_symlinks),
+    ("safe_filename", check_safe_filename),
+    ("within_claude_dir", check_within_claude_dir),
+];
 
-    // 1. Require absolute path
-    if !file_path_buf.is_absolute() {
-        return Err("File path must be absolute".to_string());
+/// 1. Path must be absolute
+fn check_absolute(path: &Path) -> Result<(), CommandError> {
+    if path.is_absolute() {
+        Ok(())
+    } else {
+        Err(CommandError::InvalidInput(
+            "File path must be absolute".to_string(),
+        ))
     }
+}
 
-    // 2. Block symlinks in path components
-    let mut current = file_path_buf.as_path();
+/// 2. No symlinks in any path component or the file itself
+fn check_no_symlinks(path: &Path) -> Result<(), CommandError> {
+    let mut current = path;
     while let Some(parent) = current.parent() {
         if parent.as_os_str().is_empty() {
             break;
         }
         if let Ok(metadata) = fs::symlink_metadata(parent) {
             if metadata.file_type().is_symlink() {
-                return Err("Symlinks are not allowed in path".to_string());
+                return Err(CommandError::PermissionDenied(
+                    "Symlinks are not allowed in path".to_string(),
+                ));
             }
         }
         current = parent;
     }
 
     // Check the file itself for symlinks
-    if let Ok(metadata) = fs::symlink_metadata(&file_path_buf) {
+    if let Ok(metadata) = fs::symlink_metadata(path) {
         if metadata.file_type().is_symlink() {
-            return Err("File path cannot be a symlink".to_string());
-        }
-    }
-
-    // 3. Validate filename pattern
-    if let Some(filename) = file_path_buf.file_stem() {
-        let filename_str = filename.to_string_lossy();
-        if !FILENAME_REGEX.is_match(&filename_str) {
-            return Err(
-                "Filename must contain only alphanumeric characters, underscores, and hyphens"
-                    .to_string(),
-            );
+            return Err(CommandError::PermissionDenied(
+                "File path cannot be a symlink".to_string(),
+            ));
         }
-    } else {
-        return Err("Invalid filename".to_string());
     }
 
-    // 4. Verify file is within ~/.claude
-    let canonical_path = file_path_buf
-        .canonicalize()
-        .map_err(|e| format!("Failed to resolve path: {e}"))?;
+    Ok(())
+}
 
-    let home_dir = dirs::home_dir().ok_or_else(|| "Cannot determine home directory".to_string())?;
+/// 3. Filename must match safe pattern
+fn check_safe_filename(path: &Path) -> Result<(), CommandError> {
+    let Some(filename) = path.file_stem() else {
+        return Err(CommandError::InvalidInput("Invalid filename".to_string()));
+    };
+    if !FILENAME_REGEX.is_match(&filename.to_string_lossy()) {
+        return Err(CommandError::InvalidInput(
+            "Filename must contain only alphanumeric characters, underscores, and hyphens"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
 
+/// 4. File must be within ~/.claude directory
+fn check_within_claude_dir(path: &Path) -> Result<(), CommandError> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| CommandError::Io("Cannot determine home directory".to_string()))?;
     let claude_dir = home_dir.join(".claude");
+    ensure_within_root(path, &claude_dir).map_err(CommandError::OutsideClaudeDir)?;
+    Ok(())
+}
 
-    // Canonicalize claude_dir too so both paths use the same format
-    // (on Windows, canonicalize adds \\?\ prefix)
-    let canonical_claude_dir = claude_dir.canonicalize().unwrap_or(claude_dir);
-
-    if !canonical_path.starts_with(&canonical_claude_dir) {
-        return Err("File path must be within ~/.claude directory".to_string());
+/// Validates that the file path is safe for deletion (or an in-place rewrite).
+/// Runs `PATH_SAFETY_CHECKS` in order and returns the first failure.
+pub(crate) fn validate_delete_path(file_path: &str) -> Result<(), CommandError> {
+    let path = Path::new(file_path);
+    for (_, check) in PATH_SAFETY_CHECKS {
+        check(path)?;
     }
-
     Ok(())
 }
 
+/// Checks whether a path is safe for destructive operations without touching it.
+///
+/// Runs the same checks as `delete_session` (absolute, no symlinks, safe
+/// filename, within `~/.claude`) and reports each one, so the frontend can
+/// explain why a destructive action is disabled.
+#[command]
+pub async fn check_path_safe(file_path: String) -> PathSafety {
+    let path = Path::new(&file_path);
+    let checks: Vec<PathSafetyCheck> = PATH_SAFETY_CHECKS
+        .iter()
+        .map(|(name, check)| {
+            let error = check(path).err();
+            PathSafetyCheck {
+                check: (*name).to_string(),
+                passed: error.is_none(),
+                error,
+            }
+        })
+        .collect();
+
+    PathSafety {
+        safe: checks.iter().all(|c| c.passed),
+        file_path,
+        checks,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,7 +217,36 @@ mod tests {
     fn test_validate_delete_path_rejects_relative_path() {
         let result = validate_delete_path("relative/path/file.jsonl");
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("must be absolute"));
+        assert!(matches!(
+            result,
+            Err(CommandError::InvalidInput(message)) if message.contains("must be absolute")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_check_path_safe_reports_each_check() {
+        let safety = check_path_safe("relative/bad.name.jsonl".to_string()).await;
+        assert!(!safety.safe);
+
+        let names: Vec<&str> = safety.checks.iter().map(|c| c.check.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "absolute",
+                "no_symlinks",
+                "safe_filename",
+                "within_claude_dir"
+            ]
+        );
+        assert!(!safety.checks[0].passed);
+        assert!(matches!(
+            safety.checks[0].error,
+            Some(CommandError::InvalidInput(_))
+        ));
+        assert!(safety.checks[1].passed);
+        assert!(safety.checks[1].error.is_none());
+        assert!(!safety.checks[2].passed);
+        assert!(!safety.checks[3].passed);
     }
 
     #[test]
@@ -1,12 +1,18 @@
 //! File edit and restore functions
 
-use crate::models::{RawLogEntry, RecentFileEdit};
+use super::raw::map_session_file;
+use super::tools::pair_tool_calls;
+use crate::models::{
+    EditSummary, FailedEdit, FileEditStats, RawLogEntry, RecentFileEdit, ReplayResult,
+    ReplayedFile, SingleEdit,
+};
 use crate::utils::find_line_ranges;
 use memmap2::Mmap;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use walkdir::WalkDir;
 
 /// Intermediate result from processing a single session file (for parallel processing)
@@ -328,6 +334,452 @@ pub async fn restore_file(file_path: String, content: String) -> Result<(), Stri
     Ok(())
 }
 
+/// Split the `MultiEdit` tool call of a message into its individual edits.
+///
+/// # Arguments
+/// * `file_path` - Session JSONL file
+/// * `message_index` - 0-based line index of the assistant message
+///
+/// Edits are returned in the order they are applied. Fails if the message
+/// does not contain a `MultiEdit` `tool_use` block.
+#[tauri::command]
+pub async fn expand_multiedit(
+    file_path: String,
+    message_index: usize,
+) -> Result<Vec<SingleEdit>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let &(start, end) = find_line_ranges(&mmap)
+        .get(message_index)
+        .ok_or_else(|| format!("Line index {message_index} out of range"))?;
+    let entry: Value = serde_json::from_slice(&mmap[start..end])
+        .map_err(|e| format!("Invalid JSON at line {message_index}: {e}"))?;
+
+    let input = entry
+        .pointer("/message/content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .find(|block| {
+            block.get("type").and_then(Value::as_str) == Some("tool_use")
+                && block.get("name").and_then(Value::as_str) == Some("MultiEdit")
+        })
+        .and_then(|block| block.get("input"))
+        .ok_or_else(|| format!("Message at line {message_index} is not a MultiEdit tool call"))?;
+
+    let target = input
+        .get("file_path")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let edits = input
+        .get("edits")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "MultiEdit input has no edits".to_string())?;
+
+    Ok(edits
+        .iter()
+        .enumerate()
+        .map(|(index, edit)| {
+            let field = |name: &str| {
+                edit.get(name)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            SingleEdit {
+                index,
+                file_path: target.to_string(),
+                old_string: field("old_string"),
+                new_string: field("new_string"),
+                replace_all: edit.get("replace_all").and_then(Value::as_bool) == Some(true),
+            }
+        })
+        .collect())
+}
+
+/// Largest `old × new` line matrix diffed exactly; bigger changes count
+/// every differing line as removed and added
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Approximate `(added, removed)` line counts between two texts.
+///
+/// Common leading and trailing lines are skipped, then the longest common
+/// subsequence of the remaining lines is kept as unchanged.
+fn line_diff_counts(old: &str, new: &str) -> (usize, usize) {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    if old_mid.is_empty() || new_mid.is_empty() || old_mid.len() * new_mid.len() > MAX_DIFF_CELLS {
+        return (new_mid.len(), old_mid.len());
+    }
+
+    // Single-row LCS table
+    let mut row = vec![0usize; new_mid.len() + 1];
+    for old_line in old_mid {
+        let mut diagonal = 0;
+        for (j, new_line) in new_mid.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if old_line == new_line {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    let common = row[new_mid.len()];
+
+    (new_mid.len() - common, old_mid.len() - common)
+}
+
+/// `(file_path, added, removed)` for an `Edit`, `Write` or `MultiEdit` `tool_use` block
+fn tool_use_line_changes(block: &Value) -> Option<(String, usize, usize)> {
+    let input = block.get("input")?;
+    let file_path = input.get("file_path").and_then(Value::as_str)?.to_string();
+    let text = |value: &Value, field: &str| {
+        value
+            .get(field)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let (added, removed) = match block.get("name").and_then(Value::as_str)? {
+        "Write" => (text(input, "content").lines().count(), 0),
+        "Edit" => line_diff_counts(&text(input, "old_string"), &text(input, "new_string")),
+        "MultiEdit" => input
+            .get("edits")
+            .and_then(Value::as_array)?
+            .iter()
+            .map(|edit| line_diff_counts(&text(edit, "old_string"), &text(edit, "new_string")))
+            .fold((0, 0), |(a, r), (added, removed)| (a + added, r + removed)),
+        _ => return None,
+    };
+    Some((file_path, added, removed))
+}
+
+/// Summarize the lines a session added and removed, per file.
+///
+/// `Edit` and `MultiEdit` calls are diffed line by line (old vs new string);
+/// `Write` calls count every line as added. Counts are approximate: a
+/// `replace_all` edit is counted once and failed tool calls are included.
+#[tauri::command]
+pub async fn session_edit_summary(file_path: String) -> Result<EditSummary, String> {
+    let mmap = map_session_file(&file_path)?;
+    let mut files: BTreeMap<String, FileEditStats> = BTreeMap::new();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let Ok(entry) = serde_json::from_slice::<Value>(&mmap[start..end]) else {
+            continue;
+        };
+        let blocks = entry
+            .pointer("/message/content")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("tool_use"));
+
+        for (target, added, removed) in blocks.filter_map(tool_use_line_changes) {
+            let stats = files
+                .entry(target.clone())
+                .or_insert_with(|| FileEditStats {
+                    file_path: target,
+                    ..FileEditStats::default()
+                });
+            stats.operations += 1;
+            stats.lines_added += added;
+            stats.lines_removed += removed;
+        }
+    }
+
+    let files: Vec<FileEditStats> = files.into_values().collect();
+    Ok(EditSummary {
+        lines_added: files.iter().map(|f| f.lines_added).sum(),
+        lines_removed: files.iter().map(|f| f.lines_removed).sum(),
+        files,
+    })
+}
+
+/// Check that `scratch_dir` is a writable directory without visible entries,
+/// creating it when missing. Dotfiles such as `.DS_Store` are tolerated.
+fn prepare_scratch_dir(scratch_dir: &Path) -> Result<(), String> {
+    if !scratch_dir.is_absolute() {
+        return Err("Scratch directory must be an absolute path".to_string());
+    }
+    if scratch_dir.exists() {
+        if !scratch_dir.is_dir() {
+            return Err(format!(
+                "Scratch path is not a directory: {}",
+                scratch_dir.display()
+            ));
+        }
+        let has_visible_entries = fs::read_dir(scratch_dir)
+            .map_err(|e| format!("Failed to read scratch directory: {e}"))?
+            .filter_map(Result::ok)
+            .any(|entry| !entry.file_name().to_string_lossy().starts_with('.'));
+        if has_visible_entries {
+            return Err(format!(
+                "Scratch directory is not empty: {}",
+                scratch_dir.display()
+            ));
+        }
+    } else {
+        fs::create_dir_all(scratch_dir)
+            .map_err(|e| format!("Failed to create scratch directory: {e}"))?;
+    }
+
+    // Probe with a real file: permission bits alone miss ACLs, read-only
+    // mounts and directories owned by another user
+    tempfile::tempfile_in(scratch_dir).map_err(|e| {
+        format!(
+            "Scratch directory is not writable: {}: {e}",
+            scratch_dir.display()
+        )
+    })?;
+    Ok(())
+}
+
+/// Error naming the first replayed files that cannot coexist under the
+/// scratch directory: two paths mirroring to the same location, or a file
+/// whose mirrored path is also needed as a directory (`/app/x` and
+/// `/app/x/y`). None when every file can be written.
+fn scratch_path_conflict(scratch_paths: &[(&str, PathBuf)]) -> Option<String> {
+    let mut by_scratch: BTreeMap<&Path, &str> = BTreeMap::new();
+    for (file_path, scratch_path) in scratch_paths {
+        if let Some(other) = by_scratch.insert(scratch_path, file_path) {
+            return Some(format!(
+                "Edited paths {other} and {file_path} map to the same scratch file"
+            ));
+        }
+    }
+    // Paths order component-wise, so a path's descendants directly follow it
+    by_scratch
+        .iter()
+        .zip(by_scratch.iter().skip(1))
+        .find(|((parent, _), (child, _))| child.starts_with(parent))
+        .map(|((_, file), (_, nested))| {
+            format!("Edited path {file} is a file but {nested} needs it as a directory")
+        })
+}
+
+/// Location under `scratch_dir` mirroring an edited path with its root
+/// removed (`/app/src/main.rs` -> `<scratch>/app/src/main.rs`).
+///
+/// None for paths with `..` segments or nothing left to mirror.
+fn scratch_path_for(scratch_dir: &Path, file_path: &str) -> Option<PathBuf> {
+    let mut target = scratch_dir.to_path_buf();
+    let mut depth = 0;
+    for component in Path::new(file_path).components() {
+        match component {
+            Component::Normal(part) => target.push(part),
+            Component::Prefix(prefix) => {
+                target.push(
+                    prefix
+                        .as_os_str()
+                        .to_string_lossy()
+                        .replace([':', '\\', '?'], ""),
+                );
+            }
+            Component::ParentDir => return None,
+            Component::RootDir | Component::CurDir => continue,
+        }
+        depth += 1;
+    }
+    (depth > 0).then_some(target)
+}
+
+/// Apply one `old_string` -> `new_string` replacement the way the `Edit`
+/// tool does: the old string must occur, and exactly once unless
+/// `replace_all` is set. An empty old string fills an empty file.
+fn apply_edit(content: &str, old: &str, new: &str, replace_all: bool) -> Result<String, String> {
+    if old.is_empty() {
+        return if content.is_empty() {
+            Ok(new.to_string())
+        } else {
+            Err("old_string is empty but the file has content".to_string())
+        };
+    }
+    match content.matches(old).count() {
+        0 => Err("old_string not found".to_string()),
+        1 => Ok(content.replacen(old, new, 1)),
+        _ if replace_all => Ok(content.replace(old, new)),
+        count => Err(format!(
+            "old_string found {count} times but replace_all is not set"
+        )),
+    }
+}
+
+/// File content rebuilt so far during a replay
+#[derive(Default)]
+struct ReplayedContent {
+    content: String,
+    operations: usize,
+    fully_written: bool,
+}
+
+/// Rebuild the files a session produced inside a scratch directory.
+///
+/// # Arguments
+/// * `file_path` - Session JSONL file
+/// * `scratch_dir` - Absolute directory to write into; created when missing
+///   and rejected when it already has visible entries
+///
+/// `Write`, `Edit` and `MultiEdit` calls are applied in session order to an
+/// in-memory copy of each file, starting from empty, then every file is
+/// written under `scratch_dir` at its original path without the root. Calls
+/// whose `tool_result` reported an error are skipped, as they changed
+/// nothing. An edit that does not apply is reported and skipped; a
+/// `MultiEdit` applies all of its edits or none. The real project is never
+/// read or touched, so a file the session only edited (never wrote) starts
+/// empty: edits with an empty `old_string` fill it, and any other edit to it
+/// fails and is reported.
+///
+/// Nothing is created or written when the session cannot be read or when
+/// two replayed paths conflict (one file also needed as a directory).
+#[tauri::command]
+pub async fn replay_edits_to(
+    file_path: String,
+    scratch_dir: String,
+) -> Result<ReplayResult, String> {
+    let scratch_dir = PathBuf::from(&scratch_dir);
+    let mmap = map_session_file(&file_path)?;
+    let mut files: BTreeMap<String, ReplayedContent> = BTreeMap::new();
+    let mut failed_edits = Vec::new();
+
+    let text = |value: &Value, field: &str| {
+        value
+            .get(field)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    for call in pair_tool_calls(&mmap).calls {
+        if call.result.as_ref().is_some_and(|r| r.is_error) {
+            continue;
+        }
+        if !matches!(call.name.as_str(), "Write" | "Edit" | "MultiEdit") {
+            continue;
+        }
+        let Some(target) = call.input.get("file_path").and_then(Value::as_str) else {
+            continue;
+        };
+        let failure = |edit_index: Option<usize>, reason: String| FailedEdit {
+            file_path: target.to_string(),
+            line_index: call.line_index,
+            tool: call.name.clone(),
+            edit_index,
+            reason,
+        };
+        if scratch_path_for(&scratch_dir, target).is_none() {
+            failed_edits.push(failure(None, "Path cannot be mirrored".to_string()));
+            continue;
+        }
+
+        let current = files.get(target).map_or("", |f| f.content.as_str());
+        let (updated, fully_written) = match call.name.as_str() {
+            "Write" => (text(&call.input, "content"), true),
+            "Edit" => {
+                let edited = apply_edit(
+                    current,
+                    &text(&call.input, "old_string"),
+                    &text(&call.input, "new_string"),
+                    call.input.get("replace_all").and_then(Value::as_bool) == Some(true),
+                );
+                match edited {
+                    Ok(content) => (content, false),
+                    Err(reason) => {
+                        failed_edits.push(failure(None, reason));
+                        continue;
+                    }
+                }
+            }
+            _ => {
+                let edits = call
+                    .input
+                    .get("edits")
+                    .and_then(Value::as_array)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let mut content = current.to_string();
+                let mut failed = None;
+                for (index, edit) in edits.iter().enumerate() {
+                    match apply_edit(
+                        &content,
+                        &text(edit, "old_string"),
+                        &text(edit, "new_string"),
+                        edit.get("replace_all").and_then(Value::as_bool) == Some(true),
+                    ) {
+                        Ok(next) => content = next,
+                        Err(reason) => {
+                            failed = Some(failure(Some(index), reason));
+                            break;
+                        }
+                    }
+                }
+                if let Some(failed) = failed {
+                    failed_edits.push(failed);
+                    continue;
+                }
+                (content, false)
+            }
+        };
+
+        let file = files.entry(target.to_string()).or_default();
+        file.content = updated;
+        file.operations += 1;
+        file.fully_written |= fully_written;
+    }
+
+    let scratch_paths: Vec<(&str, PathBuf)> = files
+        .keys()
+        .filter_map(|path| Some((path.as_str(), scratch_path_for(&scratch_dir, path)?)))
+        .collect();
+    if let Some(conflict) = scratch_path_conflict(&scratch_paths) {
+        return Err(conflict);
+    }
+
+    // Only touch the scratch directory once the replay is known to fit
+    prepare_scratch_dir(&scratch_dir)?;
+
+    let mut replayed = Vec::with_capacity(files.len());
+    for (path, file) in files {
+        let Some(scratch_path) = scratch_path_for(&scratch_dir, &path) else {
+            continue;
+        };
+        if let Some(parent) = scratch_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directories: {e}"))?;
+        }
+        fs::write(&scratch_path, &file.content)
+            .map_err(|e| format!("Failed to write {}: {e}", scratch_path.display()))?;
+        replayed.push(ReplayedFile {
+            file_path: path,
+            scratch_path: scratch_path.to_string_lossy().to_string(),
+            operations: file.operations,
+            size_bytes: file.content.len() as u64,
+            fully_written: file.fully_written,
+        });
+    }
+
+    Ok(ReplayResult {
+        files: replayed,
+        failed_edits,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -569,4 +1021,186 @@ mod tests {
         assert_eq!(edits_result.unique_files_count, 2);
         assert_eq!(edits_result.project_cwd, Some("/test/project".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_expand_multiedit() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = [
+            r#"{"type":"user","message":{"role":"user","content":"rename things"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Editing"},{"type":"tool_use","id":"t1","name":"MultiEdit","input":{"file_path":"/p/lib.rs","edits":[{"old_string":"foo","new_string":"bar"},{"old_string":"a","new_string":"b","replace_all":true}]}}]}}"#,
+        ]
+        .join("\n");
+        let path = create_test_jsonl_file(&temp_dir, "session.jsonl", &content)
+            .to_string_lossy()
+            .to_string();
+
+        let edits = expand_multiedit(path.clone(), 1).await.unwrap();
+        assert_eq!(edits.len(), 2);
+        assert_eq!(edits[0].index, 0);
+        assert_eq!(edits[0].file_path, "/p/lib.rs");
+        assert_eq!(
+            (edits[0].old_string.as_str(), edits[0].new_string.as_str()),
+            ("foo", "bar")
+        );
+        assert!(!edits[0].replace_all);
+        assert!(edits[1].replace_all);
+
+        // Not a MultiEdit message, and out of range
+        assert!(expand_multiedit(path.clone(), 0).await.is_err());
+        assert!(expand_multiedit(path, 5).await.is_err());
+    }
+
+    #[test]
+    fn test_line_diff_counts() {
+        assert_eq!(line_diff_counts("a\nb\nc", "a\nB\nc"), (1, 1));
+        assert_eq!(line_diff_counts("a\nc", "a\nb\nc"), (1, 0));
+        assert_eq!(line_diff_counts("x\na\ny\nb", "a\nz\nb"), (1, 2));
+        assert_eq!(line_diff_counts("", "one\ntwo"), (2, 0));
+        assert_eq!(line_diff_counts("same", "same"), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_session_edit_summary_per_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let lines = [
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Write","input":{"file_path":"/app/new.rs","content":"fn a() {}\nfn b() {}\n"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Edit","input":{"file_path":"/app/lib.rs","old_string":"let x = 1;\nlet y = 2;","new_string":"let x = 10;\nlet y = 2;\nlet z = 3;"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"MultiEdit","input":{"file_path":"/app/lib.rs","edits":[{"old_string":"a","new_string":"b"},{"old_string":"gone","new_string":""}]}},{"type":"tool_use","id":"t4","name":"Read","input":{"file_path":"/app/other.rs"}}]}}"#,
+        ];
+        let path = create_test_jsonl_file(&temp_dir, "s.jsonl", &lines.join("\n"));
+
+        let summary = session_edit_summary(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            summary.files,
+            vec![
+                FileEditStats {
+                    file_path: "/app/lib.rs".to_string(),
+                    operations: 2,
+                    lines_added: 3,
+                    lines_removed: 3,
+                },
+                FileEditStats {
+                    file_path: "/app/new.rs".to_string(),
+                    operations: 1,
+                    lines_added: 2,
+                    lines_removed: 0,
+                },
+            ]
+        );
+        assert_eq!(summary.lines_added, 5);
+        assert_eq!(summary.lines_removed, 3);
+    }
+
+    #[tokio::test]
+    async fn test_replay_edits_to_rebuilds_files_and_reports_failures() {
+        let temp_dir = TempDir::new().unwrap();
+        let lines = [
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Write","input":{"file_path":"/app/src/main.rs","content":"fn main() {\n    run();\n}\n"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Edit","input":{"file_path":"/app/src/main.rs","old_string":"run();","new_string":"start();"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Edit","input":{"file_path":"/app/src/main.rs","old_string":"missing","new_string":"x"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t4","name":"MultiEdit","input":{"file_path":"/app/src/main.rs","edits":[{"old_string":"start","new_string":"go"},{"old_string":"nope","new_string":"y"}]}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t5","name":"Edit","input":{"file_path":"/app/src/main.rs","old_string":"fn main","new_string":"fn broken"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t5","content":"denied","is_error":true}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t6","name":"Edit","input":{"file_path":"/app/notes.md","old_string":"","new_string":"todo"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t7","name":"Edit","input":{"file_path":"/app/src/lib.rs","old_string":"mod a;","new_string":"mod b;"}}]}}"#,
+        ];
+        let path = create_test_jsonl_file(&temp_dir, "s.jsonl", &lines.join("\n"));
+        let scratch = temp_dir.path().join("scratch");
+
+        let result = replay_edits_to(
+            path.to_string_lossy().to_string(),
+            scratch.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        let main = scratch.join("app").join("src").join("main.rs");
+        assert_eq!(
+            fs::read_to_string(&main).unwrap(),
+            "fn main() {\n    start();\n}\n"
+        );
+        assert_eq!(
+            fs::read_to_string(scratch.join("app").join("notes.md")).unwrap(),
+            "todo"
+        );
+        assert_eq!(result.files.len(), 2);
+        assert_eq!(result.files[1].file_path, "/app/src/main.rs");
+        assert_eq!(result.files[1].scratch_path, main.to_string_lossy());
+        assert_eq!(result.files[1].operations, 2);
+        assert!(result.files[1].fully_written);
+        assert!(!result.files[0].fully_written);
+
+        let failed: Vec<(usize, &str, Option<usize>)> = result
+            .failed_edits
+            .iter()
+            .map(|f| (f.line_index, f.tool.as_str(), f.edit_index))
+            .collect();
+        assert_eq!(
+            failed,
+            vec![
+                (2, "Edit", None),
+                (3, "MultiEdit", Some(1)),
+                (7, "Edit", None)
+            ]
+        );
+        // A file the session only edited starts empty, so its edit cannot apply
+        assert!(!scratch.join("app").join("src").join("lib.rs").exists());
+
+        // A second replay into the now populated directory is refused
+        let err = replay_edits_to(
+            path.to_string_lossy().to_string(),
+            scratch.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("not empty"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_edits_to_leaves_no_scratch_dir_on_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let scratch = temp_dir.path().join("scratch");
+
+        let missing = temp_dir.path().join("missing.jsonl");
+        assert!(replay_edits_to(
+            missing.to_string_lossy().to_string(),
+            scratch.to_string_lossy().to_string(),
+        )
+        .await
+        .is_err());
+        assert!(!scratch.exists());
+
+        // `/app/x` is written as a file, then used as a directory
+        let lines = [
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Write","input":{"file_path":"/app/x","content":"a"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Write","input":{"file_path":"/app/x/y","content":"b"}}]}}"#,
+        ];
+        let path = create_test_jsonl_file(&temp_dir, "s.jsonl", &lines.join("\n"));
+        let err = replay_edits_to(
+            path.to_string_lossy().to_string(),
+            scratch.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("/app/x/y"));
+        assert!(!scratch.exists());
+    }
+
+    #[test]
+    fn test_scratch_path_conflict() {
+        let scratch = Path::new("/scratch");
+        let paths = |files: &[&'static str]| -> Vec<(&'static str, PathBuf)> {
+            files
+                .iter()
+                .map(|f| (*f, scratch_path_for(scratch, f).unwrap()))
+                .collect()
+        };
+
+        assert!(scratch_path_conflict(&paths(&["/app/x", "/app/x.rs", "/app/xy/z"])).is_none());
+        assert!(scratch_path_conflict(&paths(&["/app/x", "/app/x.rs", "/app/x/y"])).is_some());
+        assert!(scratch_path_conflict(&paths(&["/app/x", "/app/./x"])).is_some());
+    }
 }
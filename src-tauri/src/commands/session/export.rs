@@ -0,0 +1,375 @@
+//! Normalized session export
+//!
+//! Converts the raw per-line JSONL format into a single JSON document with a
+//! stable, versioned schema for external tooling, either as one document or
+//! streamed message by message over a Tauri channel. Also extracts just the
+//! user's prompts, for building a prompt library.
+
+use super::load::is_genuine_user_text;
+use super::probe::{read_session_metadata, SessionMetadata};
+use super::raw::map_session_file;
+use super::summary::{derive_title_from_file, parse_conversation_entry};
+use crate::models::{RawLogEntry, TokenUsage};
+use crate::utils::find_line_ranges;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use tauri::command;
+use tauri::ipc::Channel;
+
+/// Version of the `export_session_json` schema; bump on breaking changes
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// A normalized session document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedSession {
+    pub schema_version: u32,
+    pub session_id: Option<String>,
+    pub title: String,
+    pub metadata: SessionMetadata,
+    pub first_timestamp: Option<String>,
+    pub last_timestamp: Option<String>,
+    pub messages: Vec<ExportedMessage>,
+}
+
+/// A user or assistant message with consistent fields
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedMessage {
+    /// 0-based line index in the source file
+    pub line_index: usize,
+    pub uuid: Option<String>,
+    pub parent_uuid: Option<String>,
+    pub role: String,
+    /// Original UTC timestamp
+    pub timestamp: Option<String>,
+    /// Content blocks; plain string content becomes a single `text` block
+    pub content: Vec<Value>,
+    pub model: Option<String>,
+    pub stop_reason: Option<String>,
+    pub usage: Option<TokenUsage>,
+    pub is_sidechain: bool,
+}
+
+/// Event sent over the channel by `export_session_ndjson_channel`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum NdjsonExportEvent {
+    /// One normalized message; write it as one NDJSON line
+    Message(Box<ExportedMessage>),
+    /// The export finished after `count` messages
+    Finished { count: usize },
+    /// The export stopped early
+    Error { message: String },
+}
+
+/// Normalize message content into a list of content blocks
+fn content_blocks(content: Value) -> Vec<Value> {
+    match content {
+        Value::Array(blocks) => blocks,
+        Value::String(text) => vec![json!({ "type": "text", "text": text })],
+        Value::Null => Vec::new(),
+        other => vec![other],
+    }
+}
+
+fn exported_message(line_index: usize, entry: RawLogEntry) -> Option<ExportedMessage> {
+    let message = entry.message?;
+    Some(ExportedMessage {
+        line_index,
+        uuid: entry.uuid,
+        parent_uuid: entry.parent_uuid,
+        role: message.role,
+        timestamp: entry.timestamp,
+        content: content_blocks(message.content),
+        model: message.model,
+        stop_reason: message.stop_reason,
+        usage: message.usage,
+        is_sidechain: entry.is_sidechain.unwrap_or(false),
+    })
+}
+
+/// Export a session as one normalized JSON document.
+///
+/// Unlike the raw JSONL, every message has the same shape: role, timestamp,
+/// content blocks and usage. Meta and bookkeeping lines (snapshots,
+/// progress, summaries) are left out. `schema_version` identifies the
+/// format. The source file is only read.
+#[command]
+pub async fn export_session_json(file_path: String) -> Result<Value, String> {
+    let mmap = map_session_file(&file_path)?;
+
+    let mut session_id = None;
+    let messages: Vec<ExportedMessage> = find_line_ranges(&mmap)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(line_index, (start, end))| {
+            let entry = parse_conversation_entry(&mmap[start..end])?;
+            if session_id.is_none() {
+                session_id.clone_from(&entry.session_id);
+            }
+            exported_message(line_index, entry)
+        })
+        .collect();
+
+    let session_id = session_id.or_else(|| {
+        Path::new(&file_path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+    });
+
+    let session = ExportedSession {
+        schema_version: EXPORT_SCHEMA_VERSION,
+        session_id,
+        title: derive_title_from_file(&file_path)?,
+        metadata: read_session_metadata(&file_path)?,
+        first_timestamp: messages.iter().find_map(|m| m.timestamp.clone()),
+        last_timestamp: messages.iter().rev().find_map(|m| m.timestamp.clone()),
+        messages,
+    };
+
+    serde_json::to_value(session).map_err(|e| format!("Failed to serialize session: {e}"))
+}
+
+/// Stream a session's normalized messages over a channel, one event per
+/// message, so huge sessions can be written to disk without buffering them.
+///
+/// Messages have the same shape as in `export_session_json`. A `finished`
+/// event with the message count ends a successful export; on failure an
+/// `error` event is sent and the command returns the same error.
+#[command]
+pub async fn export_session_ndjson_channel(
+    file_path: String,
+    channel: Channel<NdjsonExportEvent>,
+) -> Result<usize, String> {
+    let fail = |message: String| {
+        let _ = channel.send(NdjsonExportEvent::Error {
+            message: message.clone(),
+        });
+        message
+    };
+
+    let mmap = map_session_file(&file_path).map_err(fail)?;
+
+    let mut count = 0;
+    for (line_index, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        let Some(message) = parse_conversation_entry(&mmap[start..end])
+            .and_then(|entry| exported_message(line_index, entry))
+        else {
+            continue;
+        };
+        channel
+            .send(NdjsonExportEvent::Message(Box::new(message)))
+            .map_err(|e| fail(format!("Failed to send message {count}: {e}")))?;
+        count += 1;
+    }
+
+    channel
+        .send(NdjsonExportEvent::Finished { count })
+        .map_err(|e| format!("Failed to send completion event: {e}"))?;
+    Ok(count)
+}
+
+/// Collapse runs of spaces within lines and of blank lines, keeping single
+/// line breaks and paragraph breaks
+fn normalize_prompt_text(text: &str) -> String {
+    let mut normalized = String::new();
+    let mut blank_run = false;
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank_run = !normalized.is_empty();
+            continue;
+        }
+        if !normalized.is_empty() {
+            normalized.push_str(if blank_run { "\n\n" } else { "\n" });
+        }
+        normalized.push_str(&line);
+        blank_run = false;
+    }
+    normalized
+}
+
+/// Text the user typed in a message, without tool results or attachments
+fn prompt_text(content: &Value) -> Option<String> {
+    let texts: Vec<&str> = match content {
+        Value::String(text) => vec![text.as_str()],
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let prompt = texts
+        .into_iter()
+        .filter(|text| is_genuine_user_text(text))
+        .map(normalize_prompt_text)
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (!prompt.is_empty()).then_some(prompt)
+}
+
+/// Extract the prompts the user typed, in order.
+///
+/// Tool results, meta and system messages (slash command echoes, caveats),
+/// and subagent prompts are skipped, as are user turns that only carry tool
+/// results. Spaces are collapsed within lines; line breaks are kept.
+#[command]
+pub async fn export_user_prompts(file_path: String) -> Result<Vec<String>, String> {
+    let mmap = map_session_file(&file_path)?;
+    Ok(user_prompts(&mmap))
+}
+
+/// Prompts the user typed in a session's contents, in order
+pub(crate) fn user_prompts(data: &[u8]) -> Vec<String> {
+    find_line_ranges(data)
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let entry = parse_conversation_entry(&data[start..end])?;
+            if entry.message_type != "user" || entry.is_sidechain.unwrap_or(false) {
+                return None;
+            }
+            prompt_text(&entry.message?.content)
+        })
+        .collect()
+}
+
+/// Export the user's prompts as a Markdown document with one numbered
+/// section per prompt, as returned by `export_user_prompts`.
+#[command]
+pub async fn export_user_prompts_markdown(file_path: String) -> Result<String, String> {
+    let title = derive_title_from_file(&file_path)?;
+    let prompts = export_user_prompts(file_path).await?;
+
+    let mut markdown = format!("# Prompts: {title}\n");
+    for (number, prompt) in prompts.iter().enumerate() {
+        markdown.push_str(&format!("\n## {}\n\n{prompt}\n", number + 1));
+    }
+    Ok(markdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+    use tauri::ipc::InvokeResponseBody;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_export_session_json_normalizes_messages() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("abc.jsonl");
+        let lines = [
+            r#"{"type":"summary","summary":"Title","leafUuid":"a1"}"#,
+            r#"{"type":"user","uuid":"u1","sessionId":"s1","timestamp":"2025-01-01T10:00:00Z","version":"1.0.0","cwd":"/tmp/app","message":{"role":"user","content":"Fix the build"}}"#,
+            r#"{"type":"assistant","uuid":"a1","parentUuid":"u1","sessionId":"s1","timestamp":"2025-01-01T10:00:05Z","message":{"role":"assistant","model":"claude-sonnet-4","content":[{"type":"text","text":"Done"}],"usage":{"input_tokens":10,"output_tokens":2}}}"#,
+            r#"{"type":"file-history-snapshot","messageId":"m1"}"#,
+        ];
+        let original = lines.join("\n");
+        fs::write(&path, &original).unwrap();
+
+        let exported = export_session_json(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(exported["schema_version"], EXPORT_SCHEMA_VERSION);
+        assert_eq!(exported["session_id"], "s1");
+        assert_eq!(exported["title"], "Fix the build");
+        assert_eq!(exported["metadata"]["cwd"], "/tmp/app");
+        assert_eq!(exported["last_timestamp"], "2025-01-01T10:00:05Z");
+
+        let messages = exported["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["line_index"], 1);
+        assert_eq!(
+            messages[0]["content"],
+            json!([{ "type": "text", "text": "Fix the build" }])
+        );
+        assert_eq!(messages[1]["role"], "assistant");
+        assert_eq!(messages[1]["usage"]["output_tokens"], 2);
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    fn collecting_channel() -> (Channel<NdjsonExportEvent>, Arc<Mutex<Vec<Value>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let channel = Channel::new(move |body| {
+            if let InvokeResponseBody::Json(json) = body {
+                sink.lock()
+                    .unwrap()
+                    .push(serde_json::from_str(&json).unwrap());
+            }
+            Ok(())
+        });
+        (channel, events)
+    }
+
+    #[tokio::test]
+    async fn test_export_session_ndjson_channel_streams_messages() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("abc.jsonl");
+        let lines = [
+            r#"{"type":"user","uuid":"u1","sessionId":"s1","timestamp":"2025-01-01T10:00:00Z","message":{"role":"user","content":"Fix the build"}}"#,
+            r#"{"type":"file-history-snapshot","messageId":"m1"}"#,
+            r#"{"type":"assistant","uuid":"a1","parentUuid":"u1","sessionId":"s1","timestamp":"2025-01-01T10:00:05Z","message":{"role":"assistant","content":[{"type":"text","text":"Done"}]}}"#,
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let (channel, events) = collecting_channel();
+        let count = export_session_ndjson_channel(path.to_string_lossy().to_string(), channel)
+            .await
+            .unwrap();
+
+        assert_eq!(count, 2);
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["event"], "message");
+        assert_eq!(events[0]["data"]["content"][0]["text"], "Fix the build");
+        assert_eq!(events[1]["data"]["line_index"], 2);
+        assert_eq!(
+            events[2],
+            json!({ "event": "finished", "data": { "count": 2 } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_session_ndjson_channel_reports_errors() {
+        let (channel, events) = collecting_channel();
+        let result =
+            export_session_ndjson_channel("/nonexistent/session.jsonl".to_string(), channel).await;
+
+        assert!(result.is_err());
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["event"], "error");
+    }
+
+    #[tokio::test]
+    async fn test_export_user_prompts() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = temp_dir.path().join("s.jsonl");
+        let lines = [
+            r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"Caveat: meta"}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"Fix   the bug\n  in login.rs\n\n\n\nThanks"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Read","input":{}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"file"}]}}"#,
+            r#"{"type":"user","isSidechain":true,"message":{"role":"user","content":"Subagent task"}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"text","text":"Now add tests"},{"type":"image","source":{}}]}}"#,
+        ];
+        fs::write(&session, lines.join("\n")).unwrap();
+        let file_path = session.to_string_lossy().to_string();
+
+        let prompts = export_user_prompts(file_path.clone()).await.unwrap();
+        assert_eq!(
+            prompts,
+            vec!["Fix the bug\nin login.rs\n\nThanks", "Now add tests"]
+        );
+
+        let markdown = export_user_prompts_markdown(file_path).await.unwrap();
+        assert!(markdown.contains("## 1\n\nFix the bug\nin login.rs"));
+        assert!(markdown.contains("## 2\n\nNow add tests\n"));
+    }
+}
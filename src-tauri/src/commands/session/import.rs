@@ -0,0 +1,208 @@
+//! External conversation import
+//!
+//! Writes conversations from other tools as Claude Code JSONL sessions so
+//! they can be browsed alongside native sessions.
+
+use super::bundle::{claude_dir, validate_target_project};
+use crate::commands::fs_utils::write_file_atomically;
+use crate::utils::{encode_project_path, parse_message_timestamp};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use tauri::command;
+use uuid::Uuid;
+
+/// A message of an external conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimpleMessage {
+    /// `user` or `assistant`
+    pub role: String,
+    pub text: String,
+    /// RFC 3339 or epoch seconds/milliseconds; defaults to the previous
+    /// message's timestamp (or the import time for the first message)
+    pub timestamp: Option<String>,
+}
+
+/// Build the JSONL lines of an imported session
+fn conversation_lines(
+    messages: &[SimpleMessage],
+    session_id: &str,
+    cwd: &str,
+    now: DateTime<Utc>,
+) -> Result<Vec<Value>, String> {
+    let mut lines = Vec::with_capacity(messages.len());
+    let mut parent_uuid: Option<String> = None;
+    let mut last_timestamp = now;
+
+    for (index, message) in messages.iter().enumerate() {
+        let content = match message.role.as_str() {
+            "user" => Value::String(message.text.clone()),
+            "assistant" => json!([{ "type": "text", "text": message.text }]),
+            other => {
+                return Err(format!(
+                    "Message {index}: unsupported role '{other}' (expected user or assistant)"
+                ))
+            }
+        };
+        if let Some(raw) = &message.timestamp {
+            last_timestamp = parse_message_timestamp(raw)
+                .ok_or_else(|| format!("Message {index}: invalid timestamp '{raw}'"))?;
+        }
+
+        let uuid = Uuid::new_v4().to_string();
+        lines.push(json!({
+            "parentUuid": parent_uuid,
+            "isSidechain": false,
+            "userType": "external",
+            "cwd": cwd,
+            "sessionId": session_id,
+            "type": message.role,
+            "message": { "role": message.role, "content": content },
+            "uuid": uuid,
+            "timestamp": last_timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+        }));
+        parent_uuid = Some(uuid);
+    }
+
+    Ok(lines)
+}
+
+/// Import an external conversation as a new Claude Code session.
+///
+/// # Arguments
+/// * `messages` - Conversation in order, as role/text/timestamp entries
+/// * `project_path` - Absolute path of the real project the conversation
+///   belongs to; recorded as each message's `cwd`
+///
+/// The session is stored in the project's encoded directory inside
+/// `~/.claude/projects`, created if it does not exist. Each message gets a
+/// fresh UUID and is linked to the previous one via `parentUuid`, forming a
+/// single thread. Returns the new session file path.
+#[command]
+pub async fn import_conversation(
+    messages: Vec<SimpleMessage>,
+    project_path: String,
+) -> Result<String, String> {
+    import_conversation_in(&messages, &project_path, &claude_dir()?)
+}
+
+fn import_conversation_in(
+    messages: &[SimpleMessage],
+    project_path: &str,
+    claude_dir: &Path,
+) -> Result<String, String> {
+    if messages.is_empty() {
+        return Err("Conversation has no messages".to_string());
+    }
+    if !Path::new(project_path).is_absolute() {
+        return Err("Project path must be absolute".to_string());
+    }
+    let encoded_dir = claude_dir
+        .join("projects")
+        .join(encode_project_path(project_path));
+    let project_dir = validate_target_project(&encoded_dir.to_string_lossy(), claude_dir)?;
+
+    let session_id = Uuid::new_v4().to_string();
+    let lines = conversation_lines(messages, &session_id, project_path, Utc::now())?;
+
+    let mut data = Vec::new();
+    for line in &lines {
+        serde_json::to_writer(&mut data, line)
+            .map_err(|e| format!("Failed to serialize message: {e}"))?;
+        data.push(b'\n');
+    }
+
+    fs::create_dir_all(&project_dir)
+        .map_err(|e| format!("Failed to create project directory: {e}"))?;
+    let session_file = project_dir.join(format!("{session_id}.jsonl"));
+    write_file_atomically(&session_file, &data)?;
+
+    Ok(session_file.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::session::load_session_messages;
+    use tempfile::TempDir;
+
+    fn message(role: &str, text: &str, timestamp: Option<&str>) -> SimpleMessage {
+        SimpleMessage {
+            role: role.to_string(),
+            text: text.to_string(),
+            timestamp: timestamp.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_conversation_loads_cleanly() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("projects").join("-tmp-my-app");
+        fs::create_dir_all(temp_dir.path().join("projects")).unwrap();
+
+        let file_path = import_conversation_in(
+            &[
+                message("user", "What is a monad?", Some("2024-03-01T09:00:00Z")),
+                message(
+                    "assistant",
+                    "A monoid in the category of endofunctors.",
+                    None,
+                ),
+                message("user", "Thanks", Some("1709283720")),
+            ],
+            "/tmp/my_app",
+            temp_dir.path(),
+        )
+        .unwrap();
+        assert!(file_path.starts_with(&*project.to_string_lossy()));
+
+        let messages = load_session_messages(file_path.clone(), Some(0), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].parent_uuid, None);
+        assert_eq!(
+            messages[1].parent_uuid.as_deref(),
+            Some(messages[0].uuid.as_str())
+        );
+        assert_eq!(messages[1].message_type, "assistant");
+        assert_eq!(messages[1].timestamp, "2024-03-01T09:00:00.000Z");
+        assert_eq!(messages[2].timestamp, "2024-03-01T09:02:00.000Z");
+        assert_eq!(messages[0].session_id, messages[2].session_id);
+
+        // The real path is recorded as-is, not decoded from the directory name
+        let raw = fs::read_to_string(&file_path).unwrap();
+        let first: Value = serde_json::from_str(raw.lines().next().unwrap()).unwrap();
+        assert_eq!(first["cwd"], "/tmp/my_app");
+    }
+
+    #[tokio::test]
+    async fn test_import_conversation_validates_input() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("projects")).unwrap();
+        let project = temp_dir.path().join("projects").join("-tmp-imported");
+        let import = |messages: &[SimpleMessage], path: &str| {
+            import_conversation_in(messages, path, temp_dir.path())
+        };
+
+        assert!(import(&[], "/tmp/imported").is_err());
+        assert!(import(&[message("system", "x", None)], "/tmp/imported").is_err());
+        assert!(import(&[message("user", "x", Some("yesterday"))], "/tmp/imported").is_err());
+        assert!(import(&[message("user", "x", None)], "relative/dir").is_err());
+        assert!(!project.exists());
+    }
+
+    #[test]
+    fn test_import_conversation_requires_projects_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = import_conversation_in(
+            &[message("user", "x", None)],
+            "/tmp/imported",
+            temp_dir.path(),
+        );
+        assert!(result.is_err());
+        assert!(!temp_dir.path().join("projects").exists());
+    }
+}
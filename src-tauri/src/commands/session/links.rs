@@ -0,0 +1,173 @@
+//! URL extraction
+//!
+//! Collects the http(s) links mentioned in message text, tool inputs and
+//! tool outputs, for a "links from this conversation" panel.
+
+use super::raw::map_session_file;
+use crate::utils::find_line_ranges;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::command;
+
+/// Content block types carrying binary payloads rather than text
+const BINARY_BLOCK_TYPES: [&str; 2] = ["image", "document"];
+
+/// Trailing characters that end a sentence rather than a URL
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', '\'', '"', '*', '_'];
+
+lazy_static! {
+    /// Scheme and host, then an optional path, query or fragment up to whitespace
+    static ref URL_REGEX: Regex = Regex::new(
+        r#"\bhttps?://[A-Za-z0-9][A-Za-z0-9\-._~%]*(?::\d+)?(?:[/?#][^\s<>"`{}|\\^]*)?"#
+    )
+    .unwrap();
+}
+
+/// A URL mentioned in a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlMention {
+    pub url: String,
+    /// 0-based line index of the first message mentioning it
+    pub first_line_index: usize,
+    /// Lines mentioning it, in file order
+    pub line_indices: Vec<usize>,
+    /// Total mentions across the session
+    pub count: usize,
+}
+
+/// Strip sentence punctuation and unbalanced closing brackets from the end
+/// of a matched URL, so `(see https://a.io/x).` yields `https://a.io/x`
+fn trim_url(candidate: &str) -> &str {
+    let mut url = candidate;
+    loop {
+        let trimmed = url.trim_end_matches(TRAILING_PUNCTUATION);
+        let trimmed = [(')', '('), (']', '[')]
+            .iter()
+            .find(|(close, open)| {
+                trimmed.ends_with(*close)
+                    && trimmed.matches(*close).count() > trimmed.matches(*open).count()
+            })
+            .map_or(trimmed, |_| &trimmed[..trimmed.len() - 1]);
+        if trimmed.len() == url.len() {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+/// Collect URLs from every string of a JSON value, skipping binary blocks
+/// and thinking signatures
+fn scan_value<'a>(value: &'a Value, urls: &mut Vec<&'a str>) {
+    match value {
+        Value::String(text) => {
+            urls.extend(URL_REGEX.find_iter(text).map(|m| trim_url(m.as_str())));
+        }
+        Value::Array(items) => items.iter().for_each(|item| scan_value(item, urls)),
+        Value::Object(map) => {
+            let block_type = map.get("type").and_then(Value::as_str);
+            if block_type.is_some_and(|t| BINARY_BLOCK_TYPES.contains(&t)) {
+                return;
+            }
+            for (key, item) in map {
+                if key != "signature" {
+                    scan_value(item, urls);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// List the http(s) URLs mentioned in a session.
+///
+/// Scans message content: text, tool inputs and tool results. Trailing
+/// sentence punctuation and unbalanced closing brackets are not part of a
+/// URL. Each URL is listed once, in first-seen order, with its mention count
+/// and the lines mentioning it. Image and document payloads are skipped.
+#[command]
+pub async fn extract_urls(file_path: String) -> Result<Vec<UrlMention>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let mut mentions: Vec<UrlMention> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (line_index, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        let Ok(entry) = serde_json::from_slice::<Value>(&mmap[start..end]) else {
+            continue;
+        };
+        let Some(content) = entry.pointer("/message/content") else {
+            continue;
+        };
+
+        let mut urls = Vec::new();
+        scan_value(content, &mut urls);
+        for url in urls {
+            let position = *positions.entry(url.to_string()).or_insert_with(|| {
+                mentions.push(UrlMention {
+                    url: url.to_string(),
+                    first_line_index: line_index,
+                    line_indices: Vec::new(),
+                    count: 0,
+                });
+                mentions.len() - 1
+            });
+            let mention = &mut mentions[position];
+            mention.count += 1;
+            if mention.line_indices.last() != Some(&line_index) {
+                mention.line_indices.push(line_index);
+            }
+        }
+    }
+
+    Ok(mentions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_trim_url() {
+        assert_eq!(trim_url("https://a.io/x."), "https://a.io/x");
+        assert_eq!(trim_url("https://a.io/x)."), "https://a.io/x");
+        assert_eq!(
+            trim_url("https://en.wikipedia.org/wiki/Rust_(language)"),
+            "https://en.wikipedia.org/wiki/Rust_(language)"
+        );
+        assert_eq!(trim_url("https://a.io/?q=1&b=2"), "https://a.io/?q=1&b=2");
+    }
+
+    #[tokio::test]
+    async fn test_extract_urls_dedupes_in_first_seen_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = temp_dir.path().join("s.jsonl");
+        let lines = [
+            r#"{"type":"user","message":{"role":"user","content":"See https://docs.rs/regex. Also (https://github.com/a/b)"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"","signature":"https://not.a.link"},{"type":"tool_use","id":"t1","name":"WebFetch","input":{"url":"https://docs.rs/regex"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"moved to https://docs.rs/regex/latest, https://docs.rs/regex"},{"type":"image","source":{"type":"url","url":"https://img.example.com/a.png"}}]}}"#,
+        ];
+        fs::write(&session, lines.join("\n")).unwrap();
+
+        let urls = extract_urls(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        let found: Vec<(&str, usize, usize)> = urls
+            .iter()
+            .map(|u| (u.url.as_str(), u.first_line_index, u.count))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("https://docs.rs/regex", 0, 3),
+                ("https://github.com/a/b", 0, 1),
+                ("https://docs.rs/regex/latest", 2, 1),
+            ]
+        );
+        assert_eq!(urls[0].line_indices, vec![0, 1, 2]);
+    }
+}
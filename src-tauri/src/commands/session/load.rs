@@ -1,14 +1,17 @@
 //! Session loading functions
 
-use crate::models::{ClaudeMessage, ClaudeSession, MessagePage, RawLogEntry};
-use crate::utils::{extract_project_name, find_line_ranges, find_line_starts};
-use chrono::{DateTime, Utc};
-use memmap2::Mmap;
+use super::raw::map_session_file;
+use crate::commands::fs_utils::{ensure_within_root, validate_root_dir};
+use crate::models::{ClaudeMessage, ClaudeSession, LoadChunk, MessagePage, RawLogEntry};
+use crate::utils::{
+    extract_project_name, find_line_ranges, format_local_timestamp, timezone_offset,
+};
+use chrono::{DateTime, FixedOffset, Utc};
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::fs;
 use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use uuid::Uuid;
 use walkdir::WalkDir;
@@ -518,7 +521,7 @@ const SYSTEM_MESSAGE_TYPES: [&str; 4] = [
 
 /// Check if a message type is a system type (should be excluded)
 #[inline]
-fn is_system_message_type(message_type: &str) -> bool {
+pub(crate) fn is_system_message_type(message_type: &str) -> bool {
     SYSTEM_MESSAGE_TYPES.contains(&message_type)
 }
 
@@ -554,7 +557,7 @@ fn classify_line(line: &str, exclude_sidechain: bool) -> bool {
 }
 
 // Helper to check if text is a genuine user message (not system-generated)
-fn is_genuine_user_text(text: &str) -> bool {
+pub(crate) fn is_genuine_user_text(text: &str) -> bool {
     let trimmed = text.trim();
     if trimmed.is_empty() {
         return false;
@@ -578,7 +581,7 @@ fn is_genuine_user_text(text: &str) -> bool {
     true
 }
 
-fn truncate_text(text: &str, max_chars: usize) -> String {
+pub(crate) fn truncate_text(text: &str, max_chars: usize) -> String {
     if text.chars().count() > max_chars {
         let truncated: String = text.chars().take(max_chars).collect();
         format!("{truncated}...")
@@ -931,12 +934,14 @@ pub async fn load_project_sessions(
     Ok(sessions)
 }
 
-/// Parse a single line into `ClaudeMessage` (with line number)
+/// Parse a single line into `ClaudeMessage` (with line number); `cwd` is
+/// only kept when `track_cwd` is set
 #[allow(dead_code)] // Keep for fallback and tests
 fn parse_line_to_message(
     line_num: usize,
     line: &str,
     include_summary: bool,
+    track_cwd: bool,
 ) -> Option<ClaudeMessage> {
     if line.trim().is_empty() {
         return None;
@@ -992,6 +997,9 @@ fn parse_line_to_message(
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: Some(line_num),
+            cwd: None,
         });
     }
 
@@ -1052,15 +1060,21 @@ fn parse_line_to_message(
         prevented_continuation: log_entry.prevented_continuation,
         compact_metadata: log_entry.compact_metadata,
         microcompact_metadata: log_entry.microcompact_metadata,
+        local_timestamp: None,
+        line_index: Some(line_num),
+        cwd: log_entry.cwd.filter(|_| track_cwd),
     })
 }
 
 /// Parse a single line using simd-json for faster parsing
-/// Returns None if the line is empty or fails to parse
+/// Returns None if the line is empty or fails to parse; `cwd` is only kept
+/// when `track_cwd` is set
 fn parse_line_simd(
     line_num: usize,
     line: &mut [u8],
     include_summary: bool,
+    hide_meta: bool,
+    track_cwd: bool,
 ) -> Option<ClaudeMessage> {
     if line
         .iter()
@@ -1072,8 +1086,8 @@ fn parse_line_simd(
     // Use simd_json for faster parsing
     let log_entry: RawLogEntry = simd_json::serde::from_slice(line).ok()?;
 
-    // Skip meta messages
-    if log_entry.is_meta.unwrap_or(false) {
+    // Skip meta messages unless requested for debugging
+    if hide_meta && log_entry.is_meta.unwrap_or(false) {
         return None;
     }
 
@@ -1120,6 +1134,9 @@ fn parse_line_simd(
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: Some(line_num),
+            cwd: None,
         });
     }
 
@@ -1193,50 +1210,168 @@ fn parse_line_simd(
         prevented_continuation: log_entry.prevented_continuation,
         compact_metadata: log_entry.compact_metadata,
         microcompact_metadata: log_entry.microcompact_metadata,
+        local_timestamp: None,
+        line_index: Some(line_num),
+        cwd: log_entry.cwd.filter(|_| track_cwd),
     })
 }
 
+/// Resolve the optional timezone offset load option
+fn resolve_timezone_offset(
+    timezone_offset_minutes: Option<i32>,
+) -> Result<Option<FixedOffset>, String> {
+    timezone_offset_minutes
+        .map(|minutes| {
+            timezone_offset(minutes).ok_or_else(|| format!("Invalid timezone offset: {minutes}"))
+        })
+        .transpose()
+}
+
+/// Fill `local_timestamp` for each message; the raw `timestamp` is left untouched
+fn apply_local_timestamps(messages: &mut [ClaudeMessage], offset: Option<FixedOffset>) {
+    if let Some(offset) = offset {
+        for msg in messages {
+            msg.local_timestamp = format_local_timestamp(&msg.timestamp, offset);
+        }
+    }
+}
+
+/// Working directory recorded on a raw line, if any
+fn line_cwd(line: &[u8]) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct CwdProbe {
+        cwd: Option<String>,
+    }
+    serde_json::from_slice::<CwdProbe>(line).ok()?.cwd
+}
+
+/// Carry the last recorded `cwd` forward onto messages without one, so each
+/// message holds the working directory active at that point.
+///
+/// Takes every parsed line in file order as the message it produced (if not
+/// hidden) and the `cwd` of a hidden line, so a directory change recorded on
+/// a hidden line still applies to the messages after it.
+fn carry_cwd_forward(lines: Vec<(Option<ClaudeMessage>, Option<String>)>) -> Vec<ClaudeMessage> {
+    let mut active: Option<String> = None;
+    lines
+        .into_iter()
+        .filter_map(|(message, hidden_cwd)| {
+            if hidden_cwd.is_some() {
+                active = hidden_cwd;
+            }
+            let mut msg = message?;
+            if msg.cwd.is_some() {
+                active.clone_from(&msg.cwd);
+            } else {
+                msg.cwd.clone_from(&active);
+            }
+            Some(msg)
+        })
+        .collect()
+}
+
+/// Carry `cwd` onto one page of parsed messages (sorted by line index).
+///
+/// Seeds the active directory from the last line before the page that
+/// records one and includes hidden lines inside the page, so every message
+/// gets the same `cwd` as from `load_session_messages`.
+fn carry_cwd_into_page(
+    mmap: &[u8],
+    line_ranges: &[(usize, usize)],
+    parsed: Vec<(usize, ClaudeMessage)>,
+) -> Vec<ClaudeMessage> {
+    let (Some(&(first, _)), Some(&(last, _))) = (parsed.first(), parsed.last()) else {
+        return Vec::new();
+    };
+    let line = |idx: usize| {
+        let (start, end) = line_ranges[idx];
+        &mmap[start..end]
+    };
+
+    let seed = (0..first).rev().find_map(|idx| line_cwd(line(idx)));
+    let mut lines = vec![(None, seed)];
+    let mut page = parsed.into_iter().peekable();
+    for idx in first..=last {
+        let message = page
+            .next_if(|(line_num, _)| *line_num == idx)
+            .map(|(_, msg)| msg);
+        let hidden_cwd = if message.is_none() {
+            line_cwd(line(idx))
+        } else {
+            None
+        };
+        lines.push((message, hidden_cwd));
+    }
+    carry_cwd_forward(lines)
+}
+
+/// Load all messages of a session.
+///
+/// # Arguments
+/// * `session_path` - Session JSONL file
+/// * `timezone_offset_minutes` - Fill `localTimestamp` for this UTC offset
+/// * `hide_meta` - Hide system and `isMeta` bookkeeping lines (default true);
+///   pass false to show them for debugging
+/// * `reverse` - Return messages newest first (default false)
+/// * `track_cwd` - Fill `cwd` on every message with the working directory
+///   active at that point, carried forward from the last line recording one
+///   (default false)
+///
+/// Every message carries its original `lineIndex`, so deep links stay valid
+/// regardless of which lines are hidden or the order they are returned in.
 #[tauri::command]
-#[allow(unsafe_code)] // Required for mmap performance optimization
-pub async fn load_session_messages(session_path: String) -> Result<Vec<ClaudeMessage>, String> {
+pub async fn load_session_messages(
+    session_path: String,
+    timezone_offset_minutes: Option<i32>,
+    hide_meta: Option<bool>,
+    reverse: Option<bool>,
+    track_cwd: Option<bool>,
+) -> Result<Vec<ClaudeMessage>, String> {
     #[cfg(debug_assertions)]
     let start_time = std::time::Instant::now();
 
-    // Use memory-mapped file for faster I/O
-    let file =
-        fs::File::open(&session_path).map_err(|e| format!("Failed to open session file: {e}"))?;
+    let tz_offset = resolve_timezone_offset(timezone_offset_minutes)?;
+
+    // Use memory-mapped file for faster I/O (empty files yield no lines)
+    let mmap = map_session_file(&session_path)?;
 
-    // SAFETY: We're only reading the file, and the file handle is kept open
-    // for the duration of the mmap's lifetime. No concurrent modifications expected
-    // as session files are append-only by Claude.
-    let mmap = unsafe { Mmap::map(&file) }
-        .map_err(|e| format!("Failed to memory-map session file: {e}"))?;
+    let hide_meta = hide_meta.unwrap_or(true);
+    let track_cwd = track_cwd.unwrap_or(false);
 
     // Find line boundaries efficiently using SIMD-accelerated memchr
-    let line_starts = find_line_starts(&mmap);
+    let line_ranges = find_line_ranges(&mmap);
 
-    // Parse lines in parallel using simd-json
-    let mut messages: Vec<(usize, ClaudeMessage)> = line_starts
+    // Parse lines in parallel using simd-json; when tracking cwd, hidden
+    // lines still report theirs so it can be carried forward
+    let mut lines: Vec<(usize, Option<ClaudeMessage>, Option<String>)> = line_ranges
         .par_iter()
         .enumerate()
-        .filter_map(|(line_num, &start)| {
-            let end = line_starts.get(line_num + 1).map_or(mmap.len(), |&e| e - 1);
-            if start >= end {
-                return None;
-            }
-
+        .filter_map(|(line_num, &(start, end))| {
             // Create a mutable copy for simd-json (it requires mutable slice)
             let mut line_bytes = mmap[start..end].to_vec();
 
-            parse_line_simd(line_num, &mut line_bytes, false)
-                .filter(|msg| !is_system_message_type(&msg.message_type))
-                .map(|msg| (line_num, msg))
+            let message = parse_line_simd(line_num, &mut line_bytes, false, hide_meta, track_cwd)
+                .filter(|msg| !hide_meta || !is_system_message_type(&msg.message_type));
+            let hidden_cwd = if track_cwd && message.is_none() {
+                line_cwd(&mmap[start..end])
+            } else {
+                None
+            };
+            (message.is_some() || hidden_cwd.is_some()).then_some((line_num, message, hidden_cwd))
         })
         .collect();
 
     // Sort by line number to maintain original order
-    messages.sort_by_key(|(line_num, _)| *line_num);
-    let messages: Vec<ClaudeMessage> = messages.into_iter().map(|(_, msg)| msg).collect();
+    lines.sort_by_key(|(line_num, _, _)| *line_num);
+    let lines = lines
+        .into_iter()
+        .map(|(_, message, hidden_cwd)| (message, hidden_cwd))
+        .collect();
+    let mut messages = carry_cwd_forward(lines);
+    apply_local_timestamps(&mut messages, tz_offset);
+    if reverse.unwrap_or(false) {
+        messages.reverse();
+    }
 
     #[cfg(debug_assertions)]
     {
@@ -1251,8 +1386,46 @@ pub async fn load_session_messages(session_path: String) -> Result<Vec<ClaudeMes
     Ok(messages)
 }
 
+/// Load project sessions under an explicit data root.
+///
+/// `project_path` must resolve to a location inside `root`.
+#[tauri::command]
+pub async fn load_project_sessions_in(
+    root: String,
+    project_path: String,
+    exclude_sidechain: Option<bool>,
+) -> Result<Vec<ClaudeSession>, String> {
+    let root_path = validate_root_dir(&root)?;
+    ensure_within_root(Path::new(&project_path), &root_path)?;
+    load_project_sessions(project_path, exclude_sidechain).await
+}
+
+/// Load session messages under an explicit data root.
+///
+/// `session_path` must resolve to a location inside `root`.
+#[tauri::command]
+pub async fn load_session_messages_in(
+    root: String,
+    session_path: String,
+    timezone_offset_minutes: Option<i32>,
+    hide_meta: Option<bool>,
+    reverse: Option<bool>,
+    track_cwd: Option<bool>,
+) -> Result<Vec<ClaudeMessage>, String> {
+    let root_path = validate_root_dir(&root)?;
+    ensure_within_root(Path::new(&session_path), &root_path)?;
+    load_session_messages(
+        session_path,
+        timezone_offset_minutes,
+        hide_meta,
+        reverse,
+        track_cwd,
+    )
+    .await
+}
+
 /// Fast line classifier for simd-json (mutable slice)
-fn classify_line_fast(line: &[u8], exclude_sidechain: bool) -> bool {
+fn classify_line_fast(line: &[u8], exclude_sidechain: bool, hide_meta: bool) -> bool {
     if line
         .iter()
         .all(|&b| b == b' ' || b == b'\t' || b == b'\n' || b == b'\r')
@@ -1266,10 +1439,10 @@ fn classify_line_fast(line: &[u8], exclude_sidechain: bool) -> bool {
         if classifier.message_type == "summary" {
             return false;
         }
-        if is_system_message_type(&classifier.message_type) {
-            return false;
-        }
-        if classifier.is_meta.unwrap_or(false) {
+        if hide_meta
+            && (is_system_message_type(&classifier.message_type)
+                || classifier.is_meta.unwrap_or(false))
+        {
             return false;
         }
         if exclude_sidechain && classifier.is_sidechain.unwrap_or(false) {
@@ -1280,28 +1453,38 @@ fn classify_line_fast(line: &[u8], exclude_sidechain: bool) -> bool {
     false
 }
 
+/// Load a page of session messages, newest first (chat-style pagination).
+///
+/// `offset` counts messages from the end of the session, so the first page
+/// holds the latest messages and only the lines of the requested page are
+/// parsed. `hide_meta` behaves as in `load_session_messages` and also applies
+/// to `total_count`. Messages within a page are in file order unless
+/// `reverse` is set, which orders them newest first; `lineIndex` is
+/// preserved either way. `track_cwd` fills `cwd` as in
+/// `load_session_messages`, including directories recorded before the page.
 #[tauri::command]
-#[allow(unsafe_code)] // Required for mmap performance optimization
+#[allow(clippy::too_many_arguments)] // Flat arguments map to the frontend's invoke payload
 pub async fn load_session_messages_paginated(
     session_path: String,
     offset: usize,
     limit: usize,
     exclude_sidechain: Option<bool>,
+    timezone_offset_minutes: Option<i32>,
+    hide_meta: Option<bool>,
+    reverse: Option<bool>,
+    track_cwd: Option<bool>,
 ) -> Result<MessagePage, String> {
     #[cfg(debug_assertions)]
     let start_time = std::time::Instant::now();
 
-    // Use memory-mapped file for faster I/O
-    let file =
-        fs::File::open(&session_path).map_err(|e| format!("Failed to open session file: {e}"))?;
+    let tz_offset = resolve_timezone_offset(timezone_offset_minutes)?;
 
-    // SAFETY: We're only reading the file, and the file handle is kept open
-    // for the duration of the mmap's lifetime. No concurrent modifications expected
-    // as session files are append-only by Claude.
-    let mmap = unsafe { Mmap::map(&file) }
-        .map_err(|e| format!("Failed to memory-map session file: {e}"))?;
+    // Use memory-mapped file for faster I/O (empty files yield no lines)
+    let mmap = map_session_file(&session_path)?;
 
     let exclude = exclude_sidechain.unwrap_or(false);
+    let hide_meta = hide_meta.unwrap_or(true);
+    let track_cwd = track_cwd.unwrap_or(false);
 
     // Find line boundaries efficiently using SIMD-accelerated memchr
     let line_ranges = find_line_ranges(&mmap);
@@ -1312,7 +1495,7 @@ pub async fn load_session_messages_paginated(
         .enumerate()
         .filter(|(_, &(start, end))| {
             let line = &mmap[start..end];
-            classify_line_fast(line, exclude)
+            classify_line_fast(line, exclude, hide_meta)
         })
         .map(|(idx, _)| idx)
         .collect();
@@ -1348,14 +1531,22 @@ pub async fn load_session_messages_paginated(
         .filter_map(|&range_idx| {
             let (start, end) = line_ranges[range_idx];
             let mut line_bytes = mmap[start..end].to_vec();
-            let msg = parse_line_simd(range_idx, &mut line_bytes, false)?;
+            let msg = parse_line_simd(range_idx, &mut line_bytes, false, hide_meta, track_cwd)?;
             Some((range_idx, msg))
         })
         .collect();
 
     // Sort by line number to maintain original order
     parsed.sort_by_key(|(line_num, _)| *line_num);
-    let messages: Vec<ClaudeMessage> = parsed.into_iter().map(|(_, msg)| msg).collect();
+    let mut messages: Vec<ClaudeMessage> = if track_cwd {
+        carry_cwd_into_page(&mmap, &line_ranges, parsed)
+    } else {
+        parsed.into_iter().map(|(_, msg)| msg).collect()
+    };
+    apply_local_timestamps(&mut messages, tz_offset);
+    if reverse.unwrap_or(false) {
+        messages.reverse();
+    }
 
     let has_more = start_idx > 0;
     let next_offset = offset + messages.len();
@@ -1375,23 +1566,98 @@ pub async fn load_session_messages_paginated(
     })
 }
 
+/// Load messages forward from a byte offset (cursor-based infinite scroll).
+///
+/// An offset landing mid-line snaps to the next line start. Up to `max_lines`
+/// messages are returned along with the offset to resume from, which stays
+/// valid while a live session keeps appending. A trailing line that is not
+/// yet complete JSON (still being written) is left for the next call.
+#[tauri::command]
+pub async fn load_from_offset(
+    file_path: String,
+    byte_offset: usize,
+    max_lines: usize,
+) -> Result<LoadChunk, String> {
+    let mmap = map_session_file(&file_path)?;
+    let line_ranges = find_line_ranges(&mmap);
+
+    // First line starting at or after the offset; a partial line is skipped
+    let first = line_ranges.partition_point(|&(start, _)| start < byte_offset);
+
+    let mut messages = Vec::new();
+    let mut next_offset = byte_offset.min(mmap.len());
+    let mut eof = true;
+
+    for (line_num, &(start, end)) in line_ranges.iter().enumerate().skip(first) {
+        if messages.len() >= max_lines {
+            eof = false;
+            break;
+        }
+
+        let terminated = end < mmap.len();
+        if !terminated
+            && serde_json::from_slice::<serde::de::IgnoredAny>(&mmap[start..end]).is_err()
+        {
+            // Incomplete last line of a growing file: resume at its start
+            next_offset = start;
+            break;
+        }
+
+        let mut line_bytes = mmap[start..end].to_vec();
+        if let Some(msg) = parse_line_simd(line_num, &mut line_bytes, false, true, false)
+            .filter(|msg| !is_system_message_type(&msg.message_type))
+        {
+            messages.push(msg);
+        }
+        next_offset = if terminated { end + 1 } else { end };
+    }
+
+    Ok(LoadChunk {
+        messages,
+        next_offset,
+        eof,
+    })
+}
+
+/// Load the last `n` messages of a session, oldest first.
+///
+/// Lines are walked backwards from the end of the file and only parsed until
+/// `n` visible messages are collected, so the conclusion of a huge session is
+/// cheap to show. Messages keep their `lineIndex` relative to the full file;
+/// a file with fewer messages returns them all.
+#[tauri::command]
+pub async fn load_session_tail(file_path: String, n: usize) -> Result<Vec<ClaudeMessage>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let line_ranges = find_line_ranges(&mmap);
+
+    let mut messages = Vec::with_capacity(n.min(line_ranges.len()));
+    for (line_num, &(start, end)) in line_ranges.iter().enumerate().rev() {
+        if messages.len() >= n {
+            break;
+        }
+        let mut line_bytes = mmap[start..end].to_vec();
+        if let Some(msg) = parse_line_simd(line_num, &mut line_bytes, false, true, false)
+            .filter(|msg| !is_system_message_type(&msg.message_type))
+        {
+            messages.push(msg);
+        }
+    }
+
+    messages.reverse();
+    Ok(messages)
+}
+
 #[tauri::command]
-#[allow(unsafe_code)] // Required for mmap performance optimization
 pub async fn get_session_message_count(
     session_path: String,
     exclude_sidechain: Option<bool>,
+    hide_meta: Option<bool>,
 ) -> Result<usize, String> {
-    // Use memory-mapped file for faster I/O
-    let file =
-        fs::File::open(&session_path).map_err(|e| format!("Failed to open session file: {e}"))?;
-
-    // SAFETY: We're only reading the file, and the file handle is kept open
-    // for the duration of the mmap's lifetime. No concurrent modifications expected
-    // as session files are append-only by Claude.
-    let mmap = unsafe { Mmap::map(&file) }
-        .map_err(|e| format!("Failed to memory-map session file: {e}"))?;
+    // Use memory-mapped file for faster I/O (empty files yield no lines)
+    let mmap = map_session_file(&session_path)?;
 
     let exclude = exclude_sidechain.unwrap_or(false);
+    let hide_meta = hide_meta.unwrap_or(true);
 
     // Find line boundaries and count valid lines using SIMD-accelerated memchr
     let line_ranges = find_line_ranges(&mmap);
@@ -1401,7 +1667,7 @@ pub async fn get_session_message_count(
         .par_iter()
         .filter(|&&(start, end)| {
             let line = &mmap[start..end];
-            classify_line_fast(line, exclude)
+            classify_line_fast(line, exclude, hide_meta)
         })
         .count();
 
@@ -1451,7 +1717,14 @@ mod tests {
 
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(
+            file_path.to_string_lossy().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         assert!(result.is_ok());
         let messages = result.unwrap();
@@ -1473,7 +1746,14 @@ mod tests {
 
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(
+            file_path.to_string_lossy().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         assert!(result.is_ok());
         let messages = result.unwrap();
@@ -1490,7 +1770,14 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = create_test_jsonl_file(&temp_dir, "empty.jsonl", "");
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(
+            file_path.to_string_lossy().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
@@ -1508,7 +1795,14 @@ mod tests {
 
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(
+            file_path.to_string_lossy().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap().len(), 2);
@@ -1516,12 +1810,44 @@ mod tests {
 
     #[tokio::test]
     async fn test_load_session_messages_file_not_found() {
-        let result = load_session_messages("/nonexistent/path/file.jsonl".to_string()).await;
+        let result = load_session_messages(
+            "/nonexistent/path/file.jsonl".to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Failed to open session file"));
     }
 
+    #[tokio::test]
+    async fn test_loaders_handle_empty_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_jsonl_file(&temp_dir, "empty.jsonl", "")
+            .to_string_lossy()
+            .to_string();
+
+        let messages = load_session_messages(path.clone(), None, None, None, None)
+            .await
+            .unwrap();
+        assert!(messages.is_empty());
+
+        let page =
+            load_session_messages_paginated(path.clone(), 0, 20, None, None, None, None, None)
+                .await
+                .unwrap();
+        assert_eq!(page.total_count, 0);
+        assert!(!page.has_more);
+
+        assert_eq!(
+            get_session_message_count(path, None, None).await.unwrap(),
+            0
+        );
+    }
+
     #[tokio::test]
     async fn test_load_session_messages_with_malformed_json() {
         let temp_dir = TempDir::new().unwrap();
@@ -1535,7 +1861,14 @@ mod tests {
 
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(
+            file_path.to_string_lossy().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         // Should still succeed with valid messages
         assert!(result.is_ok());
@@ -1562,9 +1895,17 @@ mod tests {
 
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
-        let result =
-            load_session_messages_paginated(file_path.to_string_lossy().to_string(), 0, 3, None)
-                .await;
+        let result = load_session_messages_paginated(
+            file_path.to_string_lossy().to_string(),
+            0,
+            3,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         assert!(result.is_ok());
         let page = result.unwrap();
@@ -1592,9 +1933,17 @@ mod tests {
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
         // Get second page
-        let result =
-            load_session_messages_paginated(file_path.to_string_lossy().to_string(), 3, 3, None)
-                .await;
+        let result = load_session_messages_paginated(
+            file_path.to_string_lossy().to_string(),
+            3,
+            3,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         assert!(result.is_ok());
         let page = result.unwrap();
@@ -1603,6 +1952,121 @@ mod tests {
         assert!(!page.has_more);
     }
 
+    #[tokio::test]
+    async fn test_load_session_messages_in_enforces_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(&root).unwrap();
+
+        let content = format!(
+            "{}\n",
+            create_sample_user_message("uuid-1", "session-1", "Hello")
+        );
+        let inside = root.join("inside.jsonl");
+        fs::write(&inside, &content).unwrap();
+        let outside = create_test_jsonl_file(&temp_dir, "outside.jsonl", &content);
+
+        let root_str = root.to_string_lossy().to_string();
+        let messages = load_session_messages_in(
+            root_str.clone(),
+            inside.to_string_lossy().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(messages.len(), 1);
+
+        let result = load_session_messages_in(
+            root_str,
+            outside.to_string_lossy().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_session_messages_with_timezone_offset() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let content = r#"{"uuid":"uuid-1","sessionId":"session-1","timestamp":"2025-06-26T20:30:00Z","type":"user","message":{"role":"user","content":"Hello"}}
+{"uuid":"uuid-2","sessionId":"session-1","timestamp":"","type":"user","message":{"role":"user","content":"No time"}}
+"#;
+        let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", content);
+        let path = file_path.to_string_lossy().to_string();
+
+        let messages = load_session_messages(path.clone(), Some(540), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(messages[0].timestamp, "2025-06-26T20:30:00Z");
+        assert_eq!(
+            messages[0].local_timestamp.as_deref(),
+            Some("2025-06-27 05:30:00")
+        );
+        assert!(messages[1].local_timestamp.is_none());
+
+        let messages = load_session_messages(path.clone(), None, None, None, None)
+            .await
+            .unwrap();
+        assert!(messages[0].local_timestamp.is_none());
+
+        assert!(load_session_messages(path, Some(100_000), None, None, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_session_messages_hide_meta_keeps_line_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = [
+            create_sample_user_message("uuid-1", "session-1", "Hello"),
+            r#"{"uuid":"meta-1","sessionId":"session-1","timestamp":"2025-06-26T10:00:30Z","type":"user","isMeta":true,"message":{"role":"user","content":"Caveat"}}"#.to_string(),
+            r#"{"uuid":"sys-1","sessionId":"session-1","timestamp":"2025-06-26T10:00:40Z","type":"system","content":"hook ran"}"#.to_string(),
+            create_sample_assistant_message("uuid-2", "session-1", "Hi"),
+        ]
+        .join("\n\n");
+        let path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content)
+            .to_string_lossy()
+            .to_string();
+
+        let hidden = load_session_messages(path.clone(), None, None, None, None)
+            .await
+            .unwrap();
+        let indices: Vec<Option<usize>> = hidden.iter().map(|m| m.line_index).collect();
+        assert_eq!(indices, vec![Some(0), Some(3)]);
+
+        let shown = load_session_messages(path.clone(), None, Some(false), None, None)
+            .await
+            .unwrap();
+        let uuids: Vec<&str> = shown.iter().map(|m| m.uuid.as_str()).collect();
+        assert_eq!(uuids, vec!["uuid-1", "meta-1", "sys-1", "uuid-2"]);
+
+        let page = load_session_messages_paginated(
+            path.clone(),
+            0,
+            10,
+            None,
+            None,
+            Some(false),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.total_count, 4);
+        assert_eq!(page.messages[1].line_index, Some(1));
+        assert_eq!(
+            get_session_message_count(path, None, None).await.unwrap(),
+            2
+        );
+    }
+
     #[tokio::test]
     async fn test_load_session_messages_paginated_exclude_sidechain() {
         let temp_dir = TempDir::new().unwrap();
@@ -1620,6 +2084,10 @@ mod tests {
             0,
             10,
             Some(true),
+            None,
+            None,
+            None,
+            None,
         )
         .await;
 
@@ -1648,7 +2116,8 @@ mod tests {
 
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
 
-        let result = get_session_message_count(file_path.to_string_lossy().to_string(), None).await;
+        let result =
+            get_session_message_count(file_path.to_string_lossy().to_string(), None, None).await;
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 10); // Summary not counted
@@ -1666,14 +2135,15 @@ mod tests {
         let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", content);
 
         // Without exclude
-        let count_all = get_session_message_count(file_path.to_string_lossy().to_string(), None)
-            .await
-            .unwrap();
+        let count_all =
+            get_session_message_count(file_path.to_string_lossy().to_string(), None, None)
+                .await
+                .unwrap();
         assert_eq!(count_all, 3);
 
         // With exclude
         let count_filtered =
-            get_session_message_count(file_path.to_string_lossy().to_string(), Some(true))
+            get_session_message_count(file_path.to_string_lossy().to_string(), Some(true), None)
                 .await
                 .unwrap();
         assert_eq!(count_filtered, 2);
@@ -1868,7 +2338,14 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
         file.write_all(content.as_bytes()).unwrap();
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(
+            file_path.to_string_lossy().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         assert!(result.is_ok());
         let messages = result.unwrap();
@@ -1890,7 +2367,14 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
         file.write_all(content.as_bytes()).unwrap();
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(
+            file_path.to_string_lossy().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         assert!(result.is_ok());
         let messages = result.unwrap();
@@ -1909,7 +2393,14 @@ mod tests {
         let mut file = File::create(&file_path).unwrap();
         file.write_all(content.as_bytes()).unwrap();
 
-        let result = load_session_messages(file_path.to_string_lossy().to_string()).await;
+        let result = load_session_messages(
+            file_path.to_string_lossy().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
 
         assert!(result.is_ok());
         let messages = result.unwrap();
@@ -2084,4 +2575,251 @@ mod tests {
         // Should fall back to user message since assistant text is too short
         assert_eq!(result[0].summary, Some("User fallback message".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_load_from_offset_resumes_and_snaps_mid_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = create_sample_user_message("uuid-1", "session-1", "One");
+        let content = format!(
+            "{first}\n{}\n{}\n",
+            create_sample_assistant_message("uuid-2", "session-1", "Two"),
+            create_sample_user_message("uuid-3", "session-1", "Three")
+        );
+        let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
+        let path = file_path.to_string_lossy().to_string();
+
+        let chunk = load_from_offset(path.clone(), 0, 2).await.unwrap();
+        assert_eq!(chunk.messages.len(), 2);
+        assert_eq!(chunk.messages[1].uuid, "uuid-2");
+        assert!(!chunk.eof);
+
+        let chunk = load_from_offset(path.clone(), chunk.next_offset, 2)
+            .await
+            .unwrap();
+        assert_eq!(chunk.messages.len(), 1);
+        assert_eq!(chunk.messages[0].uuid, "uuid-3");
+        assert_eq!(chunk.messages[0].line_index, Some(2));
+        assert!(chunk.eof);
+        assert_eq!(chunk.next_offset, content.len());
+
+        // Mid-line offset skips to the next line
+        let chunk = load_from_offset(path, 3, 10).await.unwrap();
+        assert_eq!(chunk.messages[0].uuid, "uuid-2");
+    }
+
+    #[tokio::test]
+    async fn test_load_from_offset_leaves_incomplete_trailing_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = create_sample_user_message("uuid-1", "session-1", "One");
+        let content = format!("{first}\n{{\"type\":\"assistant\",\"mess");
+        let file_path = create_test_jsonl_file(&temp_dir, "live.jsonl", &content);
+
+        let chunk = load_from_offset(file_path.to_string_lossy().to_string(), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(chunk.messages.len(), 1);
+        assert_eq!(chunk.next_offset, first.len() + 1);
+        assert!(chunk.eof);
+    }
+
+    #[tokio::test]
+    async fn test_load_session_messages_tracks_cwd_forward() {
+        let temp_dir = TempDir::new().unwrap();
+        let with_cwd = |uuid: &str, cwd: &str| {
+            create_sample_user_message(uuid, "session-1", uuid).replacen(
+                '{',
+                &format!(r#"{{"cwd":"{cwd}","#),
+                1,
+            )
+        };
+        let lines = [
+            create_sample_user_message("uuid-0", "session-1", "before"),
+            with_cwd("uuid-1", "/repo"),
+            create_sample_assistant_message("uuid-2", "session-1", "no cwd"),
+            with_cwd("uuid-3", "/repo/web"),
+            create_sample_assistant_message("uuid-4", "session-1", "no cwd"),
+            // A hidden meta line still moves the working directory
+            with_cwd("uuid-5", "/srv").replacen('{', r#"{"isMeta":true,"#, 1),
+            create_sample_assistant_message("uuid-6", "session-1", "no cwd"),
+        ];
+        let path = create_test_jsonl_file(&temp_dir, "test.jsonl", &lines.join("\n"))
+            .to_string_lossy()
+            .to_string();
+
+        let messages = load_session_messages(path.clone(), None, None, None, Some(true))
+            .await
+            .unwrap();
+        let cwds: Vec<Option<&str>> = messages.iter().map(|m| m.cwd.as_deref()).collect();
+        assert_eq!(
+            cwds,
+            vec![
+                None,
+                Some("/repo"),
+                Some("/repo"),
+                Some("/repo/web"),
+                Some("/repo/web"),
+                Some("/srv")
+            ]
+        );
+
+        // Newest first still reflects the directory active at each message
+        let reversed = load_session_messages(path.clone(), None, None, Some(true), Some(true))
+            .await
+            .unwrap();
+        assert_eq!(reversed[0].cwd.as_deref(), Some("/srv"));
+        assert_eq!(reversed[5].cwd, None);
+
+        // Pages pick up directories recorded before them and on hidden lines
+        let page_cwds = |page: &MessagePage| -> Vec<Option<String>> {
+            page.messages.iter().map(|m| m.cwd.clone()).collect()
+        };
+        let latest =
+            load_session_messages_paginated(path.clone(), 0, 2, None, None, None, None, Some(true))
+                .await
+                .unwrap();
+        assert_eq!(
+            page_cwds(&latest),
+            vec![Some("/repo/web".to_string()), Some("/srv".to_string())]
+        );
+        let older = load_session_messages_paginated(
+            path.clone(),
+            latest.next_offset,
+            2,
+            None,
+            None,
+            None,
+            None,
+            Some(true),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            page_cwds(&older),
+            vec![Some("/repo".to_string()), Some("/repo/web".to_string())]
+        );
+
+        // Without the flag no cwd is reported
+        let untracked_page =
+            load_session_messages_paginated(path.clone(), 0, 2, None, None, None, None, None)
+                .await
+                .unwrap();
+        assert!(untracked_page.messages.iter().all(|m| m.cwd.is_none()));
+        let untracked = load_session_messages(path, None, None, None, None)
+            .await
+            .unwrap();
+        assert!(untracked.iter().all(|m| m.cwd.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_load_session_tail_keeps_full_file_indices() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = format!(
+            "{}\n\n{}\n{}\n",
+            create_sample_user_message("uuid-1", "session-1", "One"),
+            create_sample_assistant_message("uuid-2", "session-1", "Two"),
+            create_sample_user_message("uuid-3", "session-1", "Three")
+        );
+        let file_path = create_test_jsonl_file(&temp_dir, "test.jsonl", &content);
+        let path = file_path.to_string_lossy().to_string();
+
+        let full = load_session_messages(path.clone(), None, None, None, None)
+            .await
+            .unwrap();
+        let tail = load_session_tail(path.clone(), 2).await.unwrap();
+        assert_eq!(tail.len(), 2);
+        assert_eq!(tail[0].uuid, "uuid-2");
+        assert_eq!(tail[0].line_index, full[1].line_index);
+        assert_eq!(tail[1].line_index, Some(2));
+
+        // Fewer lines than requested returns everything in order
+        let tail = load_session_tail(path, 10).await.unwrap();
+        assert_eq!(tail.len(), 3);
+        assert_eq!(tail[0].uuid, "uuid-1");
+        assert_eq!(tail[0].line_index, Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_content_block_order_and_types_preserved() {
+        let temp_dir = TempDir::new().unwrap();
+        let blocks = serde_json::json!([
+            {"type": "thinking", "thinking": "Plan first", "signature": "sig"},
+            {"type": "text", "text": "Here is the screenshot"},
+            {"type": "tool_use", "id": "toolu_1", "name": "Read", "input": {"file_path": "/tmp/a.png"}},
+            {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo="}},
+            {"type": "redacted_thinking", "data": "opaque"},
+            {"type": "some_future_block", "payload": [1, 2, 3]},
+            {"type": "text", "text": "Done"}
+        ]);
+        let line = serde_json::json!({
+            "uuid": "uuid-1",
+            "sessionId": "session-1",
+            "timestamp": "2025-06-26T10:00:00Z",
+            "type": "assistant",
+            "message": {"role": "assistant", "content": blocks}
+        });
+        let file_path = create_test_jsonl_file(&temp_dir, "blocks.jsonl", &format!("{line}\n"));
+        let path = file_path.to_string_lossy().to_string();
+
+        let messages = load_session_messages(path.clone(), None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(messages[0].content.as_ref(), Some(&blocks));
+
+        let page = load_session_messages_paginated(path, 0, 20, None, None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(page.messages[0].content.as_ref(), Some(&blocks));
+    }
+
+    #[tokio::test]
+    async fn test_load_session_messages_reverse_keeps_line_indices() {
+        let temp_dir = TempDir::new().unwrap();
+        let lines: Vec<String> = (0..5)
+            .map(|i| {
+                create_sample_user_message(&format!("uuid-{i}"), "session-1", &format!("m{i}"))
+            })
+            .collect();
+        let path = create_test_jsonl_file(&temp_dir, "test.jsonl", &lines.join("\n"))
+            .to_string_lossy()
+            .to_string();
+
+        let forward = load_session_messages(path.clone(), None, None, None, None)
+            .await
+            .unwrap();
+        let reversed = load_session_messages(path.clone(), None, None, Some(true), None)
+            .await
+            .unwrap();
+        let indices = |messages: &[ClaudeMessage]| -> Vec<Option<usize>> {
+            messages.iter().map(|m| m.line_index).collect()
+        };
+        assert_eq!(
+            indices(&reversed),
+            vec![Some(4), Some(3), Some(2), Some(1), Some(0)]
+        );
+        let mut forward_indices = indices(&forward);
+        forward_indices.reverse();
+        assert_eq!(indices(&reversed), forward_indices);
+        assert_eq!(reversed[0].uuid, "uuid-4");
+
+        // Latest page first, newest message first within it
+        let page =
+            load_session_messages_paginated(path.clone(), 0, 2, None, None, None, Some(true), None)
+                .await
+                .unwrap();
+        assert_eq!(indices(&page.messages), vec![Some(4), Some(3)]);
+        assert!(page.has_more);
+        let page = load_session_messages_paginated(
+            path,
+            page.next_offset,
+            2,
+            None,
+            None,
+            None,
+            Some(true),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(indices(&page.messages), vec![Some(2), Some(1)]);
+    }
 }
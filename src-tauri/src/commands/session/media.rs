@@ -0,0 +1,165 @@
+//! Image statistics
+//!
+//! Finds the sessions of a project that carry inline images (pasted
+//! screenshots, tool screenshots), for a media view.
+
+use super::raw::map_session_file;
+use crate::commands::file_limit::with_file_permit;
+use crate::utils::find_line_ranges;
+use memchr::memmem;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tauri::command;
+use walkdir::WalkDir;
+
+/// Image content in one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionImageInfo {
+    pub session_id: String,
+    pub file_path: String,
+    /// `image` content blocks, including those nested in tool results
+    pub image_count: usize,
+    /// Decoded size of the inline base64 image payloads
+    pub image_bytes: u64,
+}
+
+/// Decoded size of a base64 payload, computed from its length
+fn base64_decoded_len(data: &str) -> u64 {
+    let padding = data.bytes().rev().take_while(|&b| b == b'=').count();
+    ((data.len() / 4 * 3) as u64).saturating_sub(padding as u64)
+}
+
+/// Count image blocks and their inline bytes anywhere in a content value
+fn scan_images(value: &Value, count: &mut usize, bytes: &mut u64) {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| scan_images(item, count, bytes)),
+        Value::Object(map) => {
+            if map.get("type").and_then(Value::as_str) == Some("image") {
+                *count += 1;
+                if let Some(source) = map.get("source") {
+                    if source.get("type").and_then(Value::as_str) == Some("base64") {
+                        if let Some(data) = source.get("data").and_then(Value::as_str) {
+                            *bytes += base64_decoded_len(data);
+                        }
+                    }
+                }
+                return;
+            }
+            map.values()
+                .for_each(|item| scan_images(item, count, bytes));
+        }
+        _ => {}
+    }
+}
+
+/// Count the images of one session file.
+///
+/// Only lines containing an image block marker are parsed.
+fn scan_session_images(path: &Path) -> Option<SessionImageInfo> {
+    let mmap = map_session_file(&path.to_string_lossy()).ok()?;
+    let image_finder = memmem::Finder::new(b"\"image\"");
+
+    let mut image_count = 0;
+    let mut image_bytes = 0;
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let line = &mmap[start..end];
+        if image_finder.find(line).is_none() {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_slice::<Value>(line) else {
+            continue;
+        };
+        if let Some(content) = entry.pointer("/message/content") {
+            scan_images(content, &mut image_count, &mut image_bytes);
+        }
+    }
+
+    Some(SessionImageInfo {
+        session_id: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        file_path: path.to_string_lossy().to_string(),
+        image_count,
+        image_bytes,
+    })
+}
+
+/// List the sessions of a project that contain images.
+///
+/// Sessions are scanned in parallel; image payloads are sized from their
+/// base64 length, never decoded. Sessions without images are left out.
+/// Sorted by image count (descending), then file path.
+#[command]
+pub async fn image_session_stats(project_path: String) -> Result<Vec<SessionImageInfo>, String> {
+    let project_dir = PathBuf::from(&project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Project directory not found: {project_path}"));
+    }
+
+    let session_files: Vec<PathBuf> = WalkDir::new(&project_dir)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let mut sessions: Vec<SessionImageInfo> = session_files
+        .par_iter()
+        .filter_map(|path| with_file_permit(|| scan_session_images(path)))
+        .filter(|info| info.image_count > 0)
+        .collect();
+    sessions.sort_by(|a, b| {
+        b.image_count
+            .cmp(&a.image_count)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const PASTED: &str = r#"{"type":"user","message":{"role":"user","content":[{"type":"text","text":"look"},{"type":"image","source":{"type":"base64","media_type":"image/png","data":"AAAAAA=="}}]}}"#;
+    const TOOL_SCREENSHOT: &str = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":[{"type":"image","source":{"type":"base64","media_type":"image/png","data":"AAAA"}}]}]}}"#;
+    const TEXT: &str =
+        r#"{"type":"user","message":{"role":"user","content":"an \"image\" in words"}}"#;
+
+    #[test]
+    fn test_base64_decoded_len() {
+        assert_eq!(base64_decoded_len("AAAA"), 3);
+        assert_eq!(base64_decoded_len("AAAAAA=="), 4);
+        assert_eq!(base64_decoded_len("AAAAAAA="), 5);
+    }
+
+    #[tokio::test]
+    async fn test_image_session_stats_counts_nested_and_sorts() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("one.jsonl"), PASTED).unwrap();
+        fs::write(
+            temp_dir.path().join("two.jsonl"),
+            [PASTED, TEXT, TOOL_SCREENSHOT].join("\n"),
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("none.jsonl"), TEXT).unwrap();
+
+        let sessions = image_session_stats(temp_dir.path().to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        let summary: Vec<(&str, usize, u64)> = sessions
+            .iter()
+            .map(|s| (s.session_id.as_str(), s.image_count, s.image_bytes))
+            .collect();
+        assert_eq!(summary, vec![("two", 2, 7), ("one", 1, 4)]);
+    }
+}
@@ -0,0 +1,303 @@
+//! Session merge module
+//!
+//! Combines several session files into a single new JSONL timeline.
+//! The original files are never modified.
+
+use super::raw::map_session_file;
+use crate::commands::fs_utils::atomic_rename;
+use crate::utils::find_line_ranges;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::command;
+
+/// Field added to every merged message recording the session it came from
+pub const SOURCE_SESSION_ID_FIELD: &str = "sourceSessionId";
+
+/// Result structure for merge operations
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeResult {
+    pub out_path: String,
+    pub session_id: String,
+    pub source_count: usize,
+    pub message_count: usize,
+    /// Messages without a usable timestamp (appended after timestamped ones)
+    pub untimestamped_count: usize,
+    /// Messages whose parentUuid was rewritten to keep the timeline connected
+    pub rethreaded_count: usize,
+    /// Lines that could not be parsed as JSON
+    pub skipped_lines: usize,
+}
+
+struct MergeEntry {
+    timestamp: Option<DateTime<FixedOffset>>,
+    value: Value,
+}
+
+/// Read all JSON lines of a session file, tagging each with its source session id
+fn read_session_entries(file_path: &Path) -> Result<(Vec<MergeEntry>, usize), String> {
+    let mmap = map_session_file(&file_path.to_string_lossy())?;
+
+    let fallback_session_id = file_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut entries = Vec::new();
+    let mut skipped = 0;
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let Ok(mut value) = serde_json::from_slice::<Value>(&mmap[start..end]) else {
+            skipped += 1;
+            continue;
+        };
+        let Some(object) = value.as_object_mut() else {
+            skipped += 1;
+            continue;
+        };
+
+        let source_session_id = object
+            .get("sessionId")
+            .and_then(Value::as_str)
+            .map_or_else(|| fallback_session_id.clone(), str::to_string);
+        object.insert(
+            SOURCE_SESSION_ID_FIELD.to_string(),
+            Value::String(source_session_id),
+        );
+
+        let timestamp = object
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok());
+
+        entries.push(MergeEntry { timestamp, value });
+    }
+
+    Ok((entries, skipped))
+}
+
+/// Order entries by timestamp; entries without one keep file order at the end
+fn order_entries(entries: Vec<MergeEntry>) -> (Vec<Value>, usize) {
+    let (mut timestamped, untimestamped): (Vec<_>, Vec<_>) =
+        entries.into_iter().partition(|e| e.timestamp.is_some());
+
+    // Stable sort keeps file order for identical timestamps
+    timestamped.sort_by_key(|e| e.timestamp);
+
+    let untimestamped_count = untimestamped.len();
+    let ordered = timestamped
+        .into_iter()
+        .chain(untimestamped)
+        .map(|e| e.value)
+        .collect();
+
+    (ordered, untimestamped_count)
+}
+
+/// Point dangling or missing parentUuids at the previous message in the merged timeline.
+/// Parents that still exist in the merged output are left untouched.
+fn rethread_parents(messages: &mut [Value]) -> usize {
+    let known_uuids: HashSet<String> = messages
+        .iter()
+        .filter_map(|m| m.get("uuid").and_then(Value::as_str).map(str::to_string))
+        .collect();
+
+    let mut previous_uuid: Option<String> = None;
+    let mut rethreaded = 0;
+
+    for message in messages.iter_mut() {
+        let Some(object) = message.as_object_mut() else {
+            continue;
+        };
+        let Some(uuid) = object
+            .get("uuid")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+        else {
+            continue;
+        };
+
+        let parent_known = object
+            .get("parentUuid")
+            .and_then(Value::as_str)
+            .is_some_and(|p| known_uuids.contains(p));
+
+        if !parent_known {
+            if let Some(prev) = &previous_uuid {
+                object.insert("parentUuid".to_string(), Value::String(prev.clone()));
+                rethreaded += 1;
+            }
+        }
+
+        previous_uuid = Some(uuid);
+    }
+
+    rethreaded
+}
+
+/// Merges several sessions into one new JSONL file ordered by timestamp.
+///
+/// # Arguments
+/// * `file_paths` - Session JSONL files to merge
+/// * `out_path` - Path of the new JSONL file (must not exist yet)
+///
+/// Every message gets a `sourceSessionId` field with its original session id,
+/// and its `sessionId` is set to the output file's stem.
+#[command]
+pub async fn merge_sessions(
+    file_paths: Vec<String>,
+    out_path: String,
+) -> Result<MergeResult, String> {
+    if file_paths.len() < 2 {
+        return Err("At least two sessions are required to merge".to_string());
+    }
+
+    let out = PathBuf::from(&out_path);
+    if out.exists() {
+        return Err(format!("Output file already exists: {out_path}"));
+    }
+    if out.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+        return Err("Output file must have a .jsonl extension".to_string());
+    }
+    let session_id = out
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .ok_or_else(|| "Invalid output filename".to_string())?;
+
+    let mut all_entries = Vec::new();
+    let mut skipped_lines = 0;
+    for file_path in &file_paths {
+        let (entries, skipped) = read_session_entries(Path::new(file_path))?;
+        all_entries.extend(entries);
+        skipped_lines += skipped;
+    }
+
+    let (mut messages, untimestamped_count) = order_entries(all_entries);
+    let rethreaded_count = rethread_parents(&mut messages);
+
+    let mut output = Vec::new();
+    for message in &mut messages {
+        if let Some(object) = message.as_object_mut() {
+            object.insert("sessionId".to_string(), Value::String(session_id.clone()));
+        }
+        serde_json::to_writer(&mut output, message)
+            .map_err(|e| format!("Failed to serialize message: {e}"))?;
+        output.push(b'\n');
+    }
+
+    let temp_path = PathBuf::from(format!("{out_path}.tmp"));
+    let mut temp_file =
+        fs::File::create(&temp_path).map_err(|e| format!("Failed to create temp file: {e}"))?;
+    if let Err(e) = temp_file
+        .write_all(&output)
+        .and_then(|()| temp_file.sync_all())
+    {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Failed to write merged session: {e}"));
+    }
+    drop(temp_file);
+    atomic_rename(&temp_path, &out)?;
+
+    Ok(MergeResult {
+        out_path,
+        session_id,
+        source_count: file_paths.len(),
+        message_count: messages.len(),
+        untimestamped_count,
+        rethreaded_count,
+        skipped_lines,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_lines(path: &Path, lines: &[&str]) {
+        fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    fn read_output(path: &Path) -> Vec<Value> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_merge_sessions_orders_and_rethreads() {
+        let temp_dir = TempDir::new().unwrap();
+        let a = temp_dir.path().join("a.jsonl");
+        let b = temp_dir.path().join("b.jsonl");
+        write_lines(
+            &a,
+            &[
+                r#"{"uuid":"a1","parentUuid":null,"sessionId":"a","timestamp":"2025-01-01T10:00:00Z","type":"user"}"#,
+                r#"{"uuid":"a2","parentUuid":"a1","sessionId":"a","timestamp":"2025-01-01T10:02:00Z","type":"assistant"}"#,
+            ],
+        );
+        write_lines(
+            &b,
+            &[
+                r#"{"uuid":"b1","parentUuid":null,"sessionId":"b","timestamp":"2025-01-01T10:01:00Z","type":"user"}"#,
+                r#"{"uuid":"b2","parentUuid":"b1","sessionId":"b","type":"assistant"}"#,
+                "not json",
+            ],
+        );
+
+        let out = temp_dir.path().join("merged.jsonl");
+        let result = merge_sessions(
+            vec![
+                a.to_string_lossy().to_string(),
+                b.to_string_lossy().to_string(),
+            ],
+            out.to_string_lossy().to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.message_count, 4);
+        assert_eq!(result.untimestamped_count, 1);
+        assert_eq!(result.skipped_lines, 1);
+        // b1 had no parent and gets attached to a1
+        assert_eq!(result.rethreaded_count, 1);
+
+        let messages = read_output(&out);
+        let uuids: Vec<&str> = messages
+            .iter()
+            .map(|m| m["uuid"].as_str().unwrap())
+            .collect();
+        assert_eq!(uuids, vec!["a1", "b1", "a2", "b2"]);
+        assert_eq!(messages[1]["parentUuid"], "a1");
+        assert_eq!(messages[2]["parentUuid"], "a1");
+        assert_eq!(messages[1][SOURCE_SESSION_ID_FIELD], "b");
+        assert_eq!(messages[0]["sessionId"], "merged");
+    }
+
+    #[tokio::test]
+    async fn test_merge_sessions_refuses_existing_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let a = temp_dir.path().join("a.jsonl");
+        let b = temp_dir.path().join("b.jsonl");
+        write_lines(&a, &[r#"{"uuid":"a1"}"#]);
+        write_lines(&b, &[r#"{"uuid":"b1"}"#]);
+
+        let result = merge_sessions(
+            vec![
+                a.to_string_lossy().to_string(),
+                b.to_string_lossy().to_string(),
+            ],
+            a.to_string_lossy().to_string(),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&a).unwrap(), "{\"uuid\":\"a1\"}\n");
+    }
+}
@@ -1,22 +1,88 @@
 //! Session commands module
 //!
 //! This module contains all session-related Tauri commands organized into submodules:
+//! - `links`: URLs mentioned in a session
 //! - `load`: Session and message loading functions
-//! - `search`: Message search functions
-//! - `edits`: File edit tracking and restore functions
+//! - `search`: Message search and sessions touching a file
+//! - `edits`: File edit tracking, restore and replay functions
 //! - `rename`: Native session renaming functions
+//! - `anonymize`: Scrubbed copies of sessions for sharing
+//! - `bundle`: Session export/import as `.tar.gz` bundles
+//! - `companion`: Companion directory reference checks and file classification
+//! - `export`: Normalized JSON export with a stable schema and user prompt export
+//! - `import`: External conversations imported as sessions
+//! - `continuation`: Resumed-session continuation detection and session lineage
+//! - `compaction`: Context compaction detection and splitting
+//! - `modes`: Permission mode transitions
+//! - `media`: Sessions with inline images
+//! - `merge`: Merging several sessions into one timeline
+//! - `raw`: Raw JSONL line access, UUID prefix lookup, whitespace compaction and output truncation
+//! - `query`: JSON path queries over session lines
+//! - `probe`: Cheap existence checks, live status, CLI metadata, environment, working directories and users of session files
+//! - `secrets`: Leaked credential detection
+//! - `slash`: Slash command invocations
+//! - `summary`: Session titles, batch title listing, previews and largest messages
+//! - `threading`: parentUuid threading integrity checks
+//! - `timing`: Session time bounds, response latency and interruption analysis
+//! - `triage`: Sessions with tool errors or interruptions
+//! - `tools`: Tool call, tool error, error streak and MCP call analysis, agentic ratio and declared tools
+//! - `web_link`: Deep links to sessions in Claude's web UI
 
+mod anonymize;
+mod bundle;
+mod compaction;
+mod companion;
+mod continuation;
 mod delete;
 mod edits;
+mod export;
+mod import;
+mod links;
 mod load;
+mod media;
+mod merge;
+mod modes;
+mod probe;
+mod query;
+mod raw;
 mod rename;
 mod resume;
 mod search;
+mod secrets;
+mod slash;
+mod summary;
+mod threading;
+mod timing;
+mod tools;
+mod triage;
+mod web_link;
 
 // Re-export all commands
+pub use anonymize::*;
+pub use bundle::*;
+pub use compaction::*;
+pub use companion::*;
+pub use continuation::*;
 pub use delete::*;
 pub use edits::*;
+pub use export::*;
+pub use import::*;
+pub use links::*;
 pub use load::*;
+pub use media::*;
+pub use merge::*;
+pub use modes::*;
+pub use probe::*;
+pub use query::*;
+pub use raw::*;
 pub use rename::*;
 pub use resume::*;
 pub use search::*;
+pub use secrets::*;
+pub use slash::*;
+pub use summary::*;
+pub use threading::*;
+pub use timing::*;
+pub use tools::*;
+pub use triage::*;
+pub use web_link::*;
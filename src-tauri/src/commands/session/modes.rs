@@ -0,0 +1,118 @@
+//! Permission mode transitions
+//!
+//! Claude stamps each user message with the `permissionMode` active when it
+//! was sent (`default`, `plan`, `acceptEdits`, `bypassPermissions`). A change
+//! between consecutive stamps marks where the user switched modes.
+
+use super::raw::map_session_file;
+use crate::utils::find_line_ranges;
+use serde::{Deserialize, Serialize};
+use tauri::command;
+
+/// Mode a session is in before any message records one
+const INITIAL_MODE: &str = "default";
+
+/// Minimal view of a log line carrying only mode fields
+#[derive(Debug, Deserialize)]
+struct ModeProbe {
+    uuid: Option<String>,
+    timestamp: Option<String>,
+    #[serde(rename = "permissionMode")]
+    permission_mode: Option<String>,
+}
+
+/// A switch from one permission mode to another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeTransition {
+    /// 0-based line index of the first message sent in the new mode
+    pub line_index: usize,
+    pub uuid: Option<String>,
+    pub timestamp: Option<String>,
+    pub from_mode: String,
+    pub to_mode: String,
+}
+
+/// Find where the permission mode changes in a session.
+///
+/// A session starts in `default` mode, so one opened directly in plan mode
+/// reports a `default` → `plan` transition at its first message. Lines
+/// without a `permissionMode` (assistant messages, older CLI versions) keep
+/// the current mode. Returns an empty list when the mode never changes.
+#[command]
+pub async fn mode_transitions(file_path: String) -> Result<Vec<ModeTransition>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let mut current = INITIAL_MODE.to_string();
+    let mut transitions = Vec::new();
+
+    for (line_index, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        // simd-json requires mutable slice
+        let mut line_bytes = mmap[start..end].to_vec();
+        let Ok(probe) = simd_json::serde::from_slice::<ModeProbe>(&mut line_bytes) else {
+            continue;
+        };
+        let Some(mode) = probe.permission_mode.filter(|m| *m != current) else {
+            continue;
+        };
+
+        transitions.push(ModeTransition {
+            line_index,
+            uuid: probe.uuid,
+            timestamp: probe.timestamp,
+            from_mode: std::mem::replace(&mut current, mode.clone()),
+            to_mode: mode,
+        });
+    }
+
+    Ok(transitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_mode_transitions() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = temp_dir.path().join("session.jsonl");
+        let lines = [
+            r#"{"type":"user","uuid":"a","timestamp":"2025-01-01T00:00:00Z","permissionMode":"default"}"#,
+            r#"{"type":"assistant","uuid":"b"}"#,
+            r#"{"type":"user","uuid":"c","timestamp":"2025-01-01T00:01:00Z","permissionMode":"plan"}"#,
+            r#"{"type":"user","uuid":"d","permissionMode":"plan"}"#,
+            r#"{"type":"user","uuid":"e","timestamp":"2025-01-01T00:02:00Z","permissionMode":"acceptEdits"}"#,
+        ];
+        fs::write(&session, lines.join("\n")).unwrap();
+
+        let transitions = mode_transitions(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        let found: Vec<(usize, &str, &str)> = transitions
+            .iter()
+            .map(|t| (t.line_index, t.from_mode.as_str(), t.to_mode.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(2, "default", "plan"), (4, "plan", "acceptEdits")]
+        );
+        assert_eq!(transitions[0].uuid.as_deref(), Some("c"));
+        assert_eq!(
+            transitions[1].timestamp.as_deref(),
+            Some("2025-01-01T00:02:00Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mode_transitions_empty_without_modes() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = temp_dir.path().join("session.jsonl");
+        fs::write(&session, "{\"type\":\"user\",\"uuid\":\"a\"}\n").unwrap();
+
+        let transitions = mode_transitions(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert!(transitions.is_empty());
+    }
+}
@@ -0,0 +1,578 @@
+//! Session file probe
+//!
+//! Cheap existence/metadata checks so the frontend can reconcile its session
+//! list after files were deleted elsewhere, without loading any content.
+//! Also reads the CLI metadata recorded at the start of a session and the
+//! environment (platform, shell) and working directories it ran in, tells
+//! whether a session is still being written and lists the accounts sessions
+//! were recorded by.
+
+use super::raw::map_session_file;
+use crate::commands::file_limit::with_file_permit;
+use crate::utils::find_line_ranges;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use memchr::memmem;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tauri::command;
+use walkdir::WalkDir;
+
+/// Existence and metadata of a single session file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionProbe {
+    pub file_path: String,
+    /// False if the path no longer exists (or is not a regular file)
+    pub exists: bool,
+    pub size: Option<u64>,
+    /// RFC 3339 modification time
+    pub last_modified: Option<String>,
+}
+
+fn probe_session(file_path: String) -> SessionProbe {
+    // symlink_metadata avoids following links and is cheaper than canonicalizing
+    match fs::symlink_metadata(&file_path) {
+        Ok(metadata) if metadata.is_file() => SessionProbe {
+            size: Some(metadata.len()),
+            last_modified: metadata.modified().ok().map(|t| {
+                let dt: DateTime<Utc> = t.into();
+                dt.to_rfc3339()
+            }),
+            exists: true,
+            file_path,
+        },
+        _ => SessionProbe {
+            file_path,
+            exists: false,
+            size: None,
+            last_modified: None,
+        },
+    }
+}
+
+/// Probe a list of session files for existence, size and mtime in one call.
+/// Results are returned in the same order as `file_paths`.
+#[command]
+pub async fn probe_sessions(file_paths: Vec<String>) -> Result<Vec<SessionProbe>, String> {
+    Ok(file_paths.into_par_iter().map(probe_session).collect())
+}
+
+/// Default window in which a modified session counts as live
+const ACTIVE_THRESHOLD_SECS: u64 = 10;
+
+/// Whether a session file is still being written to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionActivity {
+    pub is_active: bool,
+    /// RFC 3339 modification time
+    pub last_modified: String,
+    /// Seconds between the last modification and now
+    pub seconds_since_modified: u64,
+}
+
+/// Check whether a session is live, i.e. modified within `threshold_secs`
+/// seconds (default 10).
+///
+/// Claude appends to a session after every message and tool call, so a
+/// recent mtime means a CLI process is most likely still writing to it.
+/// Re-check on file watcher events to keep a live badge current. A
+/// modification time in the future (clock skew) counts as just modified.
+#[command]
+pub async fn is_session_active(
+    file_path: String,
+    threshold_secs: Option<u64>,
+) -> Result<SessionActivity, String> {
+    let metadata =
+        fs::metadata(&file_path).map_err(|e| format!("Failed to read {file_path}: {e}"))?;
+    let modified = metadata
+        .modified()
+        .map_err(|e| format!("Failed to read modification time: {e}"))?;
+
+    let seconds_since_modified = SystemTime::now()
+        .duration_since(modified)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let last_modified: DateTime<Utc> = modified.into();
+
+    Ok(SessionActivity {
+        is_active: seconds_since_modified <= threshold_secs.unwrap_or(ACTIVE_THRESHOLD_SECS),
+        last_modified: last_modified.to_rfc3339(),
+        seconds_since_modified,
+    })
+}
+
+/// CLI metadata recorded on session lines
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionMetadata {
+    /// Claude Code version that wrote the session
+    pub version: Option<String>,
+    pub cwd: Option<String>,
+    pub git_branch: Option<String>,
+    pub user_type: Option<String>,
+    /// Account identifier, when the CLI records one
+    pub user_id: Option<String>,
+}
+
+/// Metadata fields of a raw session line; everything else is ignored
+#[derive(Deserialize)]
+struct MetadataProbe {
+    version: Option<String>,
+    cwd: Option<String>,
+    #[serde(rename = "gitBranch")]
+    git_branch: Option<String>,
+    #[serde(rename = "userType")]
+    user_type: Option<String>,
+    #[serde(rename = "userID")]
+    user_id: Option<String>,
+}
+
+impl SessionMetadata {
+    fn is_complete(&self) -> bool {
+        self.version.is_some()
+            && self.cwd.is_some()
+            && self.git_branch.is_some()
+            && self.user_type.is_some()
+    }
+}
+
+/// Read session metadata, parsing lines only until it is known.
+///
+/// Leading lines (summaries, snapshots) usually lack metadata, so fields are
+/// filled from successive lines. Parsing stops once all fields are found or
+/// after the first line carrying `version`: conversation lines record all
+/// fields together, so anything missing there is absent from the session.
+pub(crate) fn read_session_metadata(file_path: &str) -> Result<SessionMetadata, String> {
+    let mmap = map_session_file(file_path)?;
+    let mut metadata = SessionMetadata::default();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        // simd-json requires mutable slice
+        let mut line = mmap[start..end].to_vec();
+        let Ok(probe) = simd_json::serde::from_slice::<MetadataProbe>(&mut line) else {
+            continue;
+        };
+
+        let has_version = probe.version.is_some();
+        metadata.version = metadata.version.or(probe.version);
+        metadata.cwd = metadata.cwd.or(probe.cwd);
+        metadata.git_branch = metadata.git_branch.or(probe.git_branch);
+        metadata.user_type = metadata.user_type.or(probe.user_type);
+        metadata.user_id = metadata.user_id.or(probe.user_id);
+
+        if has_version || metadata.is_complete() {
+            break;
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Get the CLI version, working directory, git branch, user type and user id
+/// a session was recorded with. Absent fields are `None`.
+#[command]
+pub async fn session_metadata(file_path: String) -> Result<SessionMetadata, String> {
+    read_session_metadata(&file_path)
+}
+
+/// `cwd` field of a raw session line; everything else is ignored
+#[derive(Deserialize)]
+struct CwdProbe {
+    cwd: Option<String>,
+}
+
+/// List the distinct working directories a session ran in, in first-seen
+/// order.
+///
+/// Only lines containing a `cwd` key are parsed, and only for that field.
+/// More than one entry means Claude moved into a subdirectory or another
+/// project partway through, so paths in later messages resolve against a
+/// different base.
+#[command]
+pub async fn session_working_dirs(file_path: String) -> Result<Vec<String>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let cwd_finder = memmem::Finder::new(b"\"cwd\"");
+    let mut dirs: Vec<String> = Vec::new();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        if cwd_finder.find(&mmap[start..end]).is_none() {
+            continue;
+        }
+        // simd-json requires mutable slice
+        let mut line = mmap[start..end].to_vec();
+        let Ok(CwdProbe { cwd: Some(cwd) }) = simd_json::serde::from_slice::<CwdProbe>(&mut line)
+        else {
+            continue;
+        };
+        if !dirs.contains(&cwd) {
+            dirs.push(cwd);
+        }
+    }
+
+    Ok(dirs)
+}
+
+/// Bucket for sessions that record neither a user id nor a user type
+const UNKNOWN_USER: &str = "(unknown)";
+
+/// List the distinct users sessions were recorded by, with session counts.
+///
+/// A session's user is its `userID`, or its `userType` when no id is
+/// recorded; sessions with neither go into `(unknown)`. Only the leading
+/// metadata lines of each session are read, in parallel across all
+/// projects below `claude_path`. Most sessions first.
+#[command]
+pub async fn list_session_users(claude_path: String) -> Result<Vec<(String, usize)>, String> {
+    let projects_path = PathBuf::from(&claude_path).join("projects");
+    if !projects_path.is_dir() {
+        return Err(format!(
+            "Projects directory not found: {}",
+            projects_path.display()
+        ));
+    }
+
+    let session_files: Vec<PathBuf> = WalkDir::new(&projects_path)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .filter(|e| !e.path().components().any(|c| c.as_os_str() == "subagents"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let users: Vec<String> = session_files
+        .par_iter()
+        .filter_map(|path| with_file_permit(|| read_session_metadata(&path.to_string_lossy())).ok())
+        .map(|metadata| {
+            metadata
+                .user_id
+                .or(metadata.user_type)
+                .unwrap_or_else(|| UNKNOWN_USER.to_string())
+        })
+        .collect();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for user in users {
+        *counts.entry(user).or_insert(0) += 1;
+    }
+
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(counts)
+}
+
+lazy_static! {
+    /// Environment lines Claude writes in `<env>` blocks and `/doctor`-style
+    /// output, matched on the raw JSON line (newlines appear as `\n` escapes)
+    static ref PLATFORM_REGEX: Regex = Regex::new(r"Platform: ([A-Za-z0-9_]+)").unwrap();
+    static ref OS_VERSION_REGEX: Regex = Regex::new(r#"OS Version: ([^\\"]+)"#).unwrap();
+    static ref SHELL_REGEX: Regex = Regex::new(r"Shell: ([A-Za-z0-9_./\-]+)").unwrap();
+    static ref WINDOWS_PATH_REGEX: Regex = Regex::new(r"^[A-Za-z]:[\\/]").unwrap();
+}
+
+/// Operating system and shell a session ran in
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EnvironmentInfo {
+    /// `darwin`, `linux`, `win32`, ...
+    pub platform: Option<String>,
+    pub os_version: Option<String>,
+    pub shell: Option<String>,
+    pub cwd: Option<String>,
+}
+
+impl EnvironmentInfo {
+    fn is_complete(&self) -> bool {
+        self.platform.is_some()
+            && self.os_version.is_some()
+            && self.shell.is_some()
+            && self.cwd.is_some()
+    }
+}
+
+/// Guess the platform from the shape of a working directory path
+fn platform_from_cwd(cwd: &str) -> Option<&'static str> {
+    if WINDOWS_PATH_REGEX.is_match(cwd) {
+        Some("win32")
+    } else if cwd.starts_with("/Users/") {
+        Some("darwin")
+    } else if cwd.starts_with("/home/") || cwd == "/root" || cwd.starts_with("/root/") {
+        Some("linux")
+    } else {
+        None
+    }
+}
+
+/// Detect the operating system, shell and working directory of a session.
+///
+/// Reads the `Platform:`, `OS Version:` and `Shell:` lines Claude records in
+/// environment blocks and tool output, plus the `cwd` field. Scanning stops
+/// once everything is found. Without an explicit platform line the platform
+/// is inferred from the working directory (`/Users/...` is macOS, drive
+/// letters are Windows). Missing fields are `None`.
+#[command]
+pub async fn session_environment(file_path: String) -> Result<EnvironmentInfo, String> {
+    let mmap = map_session_file(&file_path)?;
+    let mut info = EnvironmentInfo::default();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let line = String::from_utf8_lossy(&mmap[start..end]);
+        let capture = |regex: &Regex| regex.captures(&line).map(|caps| caps[1].trim().to_string());
+
+        if info.platform.is_none() {
+            info.platform = capture(&PLATFORM_REGEX);
+        }
+        if info.os_version.is_none() {
+            info.os_version = capture(&OS_VERSION_REGEX);
+        }
+        if info.shell.is_none() {
+            info.shell = capture(&SHELL_REGEX);
+        }
+        if info.cwd.is_none() {
+            info.cwd = serde_json::from_str::<serde_json::Value>(&line)
+                .ok()
+                .and_then(|value| value.get("cwd")?.as_str().map(str::to_string));
+        }
+
+        if info.is_complete() {
+            break;
+        }
+    }
+
+    if info.platform.is_none() {
+        info.platform = info
+            .cwd
+            .as_deref()
+            .and_then(platform_from_cwd)
+            .map(str::to_string);
+    }
+
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_probe_sessions_reports_existing_and_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        let existing = temp_dir.path().join("existing.jsonl");
+        fs::write(&existing, "{}\n").unwrap();
+        let missing = temp_dir.path().join("missing.jsonl");
+
+        let probes = probe_sessions(vec![
+            existing.to_string_lossy().to_string(),
+            missing.to_string_lossy().to_string(),
+            temp_dir.path().to_string_lossy().to_string(),
+        ])
+        .await
+        .unwrap();
+
+        assert_eq!(probes.len(), 3);
+        assert!(probes[0].exists);
+        assert_eq!(probes[0].size, Some(3));
+        assert!(probes[0].last_modified.is_some());
+        assert!(!probes[1].exists);
+        assert_eq!(probes[1].size, None);
+        // Directories are not session files
+        assert!(!probes[2].exists);
+    }
+
+    #[tokio::test]
+    async fn test_session_metadata_skips_leading_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        fs::write(
+            &path,
+            [
+                r#"{"type":"summary","summary":"Fix bug","leafUuid":"u9"}"#,
+                r#"{"type":"user","version":"1.0.98","cwd":"/repo","gitBranch":"main","userType":"external","message":{"role":"user","content":"hi"}}"#,
+                r#"{"type":"user","version":"2.0.0","cwd":"/other","gitBranch":"dev","userType":"internal"}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let metadata = session_metadata(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            metadata,
+            SessionMetadata {
+                version: Some("1.0.98".to_string()),
+                cwd: Some("/repo".to_string()),
+                git_branch: Some("main".to_string()),
+                user_type: Some("external".to_string()),
+                user_id: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_metadata_absent_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        fs::write(
+            &path,
+            r#"{"type":"user","version":"1.0.0","cwd":"/tmp","message":{"role":"user","content":"hi"}}"#,
+        )
+        .unwrap();
+
+        let metadata = session_metadata(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(metadata.version.as_deref(), Some("1.0.0"));
+        assert!(metadata.git_branch.is_none());
+        assert!(metadata.user_type.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_working_dirs_first_seen_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        fs::write(
+            &path,
+            [
+                r#"{"type":"summary","summary":"Fix bug","leafUuid":"u9"}"#,
+                r#"{"type":"user","cwd":"/repo","message":{"role":"user","content":"hi"}}"#,
+                r#"{"type":"assistant","cwd":"/repo/crates/core"}"#,
+                r#"{"type":"user","cwd":"/repo"}"#,
+                r#"{"type":"user","message":{"role":"user","content":"the \"cwd\" key"}}"#,
+                r#"{"type":"user","cwd":"/other"}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let dirs = session_working_dirs(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(dirs, vec!["/repo", "/repo/crates/core", "/other"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_session_users() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_a = temp_dir.path().join("projects/-a");
+        let project_b = temp_dir.path().join("projects/-b");
+        fs::create_dir_all(project_a.join("subagents")).unwrap();
+        fs::create_dir_all(&project_b).unwrap();
+        let line = |user: &str| {
+            format!(
+                r#"{{"type":"user","version":"1.0.0",{user}"message":{{"role":"user","content":"hi"}}}}"#
+            )
+        };
+        fs::write(project_a.join("1.jsonl"), line(r#""userType":"external","#)).unwrap();
+        fs::write(
+            project_a.join("2.jsonl"),
+            line(r#""userID":"abc","userType":"external","#),
+        )
+        .unwrap();
+        fs::write(
+            project_a.join("subagents/3.jsonl"),
+            line(r#""userType":"external","#),
+        )
+        .unwrap();
+        fs::write(project_b.join("4.jsonl"), line(r#""userType":"external","#)).unwrap();
+        fs::write(project_b.join("5.jsonl"), line("")).unwrap();
+
+        let users = list_session_users(temp_dir.path().to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            users,
+            vec![
+                ("external".to_string(), 2),
+                ("(unknown)".to_string(), 1),
+                ("abc".to_string(), 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_environment_reads_env_block() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        fs::write(
+            &path,
+            [
+                r#"{"type":"summary","summary":"Setup"}"#,
+                r#"{"type":"user","cwd":"/Users/dev/app","message":{"role":"user","content":"hi"}}"#,
+                r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"<env>\nPlatform: darwin\nOS Version: Darwin 24.1.0\nShell: /bin/zsh\n</env>"}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let info = session_environment(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            info,
+            EnvironmentInfo {
+                platform: Some("darwin".to_string()),
+                os_version: Some("Darwin 24.1.0".to_string()),
+                shell: Some("/bin/zsh".to_string()),
+                cwd: Some("/Users/dev/app".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_environment_infers_platform_from_cwd() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        fs::write(
+            &path,
+            r#"{"type":"user","cwd":"C:\\Users\\dev\\app","message":{"role":"user","content":"hi"}}"#,
+        )
+        .unwrap();
+
+        let info = session_environment(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(info.platform.as_deref(), Some("win32"));
+        assert_eq!(info.cwd.as_deref(), Some("C:\\Users\\dev\\app"));
+        assert!(info.shell.is_none());
+        assert!(info.os_version.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_is_session_active() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("live.jsonl");
+        fs::write(&path, "{}\n").unwrap();
+        let file_path = path.to_string_lossy().to_string();
+
+        let activity = is_session_active(file_path.clone(), None).await.unwrap();
+        assert!(activity.is_active);
+
+        let hour_ago = SystemTime::now() - std::time::Duration::from_secs(3600);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(hour_ago)
+            .unwrap();
+
+        let activity = is_session_active(file_path.clone(), None).await.unwrap();
+        assert!(!activity.is_active);
+        assert!(activity.seconds_since_modified >= 3600);
+        assert!(
+            is_session_active(file_path, Some(7200))
+                .await
+                .unwrap()
+                .is_active
+        );
+
+        let missing = temp_dir.path().join("missing.jsonl");
+        assert!(
+            is_session_active(missing.to_string_lossy().to_string(), None)
+                .await
+                .is_err()
+        );
+    }
+}
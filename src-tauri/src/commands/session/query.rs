@@ -0,0 +1,98 @@
+//! JSON path queries over session files
+//!
+//! Applies a JSON path expression to every line of a session and collects the
+//! matching values, so analysts can run ad-hoc aggregations.
+
+use super::raw::map_session_file;
+use crate::utils::find_line_ranges;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use tauri::command;
+
+/// A single JSON path match and the line it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryMatch {
+    /// 0-based index of the source line (same indexing as `get_raw_line`)
+    pub line_index: usize,
+    pub value: Value,
+}
+
+/// Run a JSON path expression against each line of a session.
+///
+/// Example: `$.message.usage.output_tokens` returns every output token count.
+/// Lines that are not valid JSON are skipped.
+#[command]
+pub async fn query_session(
+    file_path: String,
+    json_path: String,
+) -> Result<Vec<QueryMatch>, String> {
+    let path =
+        JsonPath::parse(&json_path).map_err(|e| format!("Invalid JSONPath '{json_path}': {e}"))?;
+
+    let mmap = map_session_file(&file_path)?;
+    let mut matches = Vec::new();
+
+    for (line_index, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        let Ok(value) = serde_json::from_slice::<Value>(&mmap[start..end]) else {
+            continue;
+        };
+
+        matches.extend(path.query(&value).all().into_iter().map(|v| QueryMatch {
+            line_index,
+            value: v.clone(),
+        }));
+    }
+
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_query_session_collects_matches_with_line_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        fs::write(
+            &path,
+            concat!(
+                r#"{"type":"user","message":{"role":"user","content":"hi"}}"#,
+                "\n",
+                r#"{"type":"assistant","message":{"usage":{"output_tokens":42}}}"#,
+                "\n",
+                "garbage\n",
+                r#"{"type":"assistant","message":{"usage":{"output_tokens":7}}}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let matches = query_session(
+            path.to_string_lossy().to_string(),
+            "$.message.usage.output_tokens".to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].line_index, 1);
+        assert_eq!(matches[0].value, serde_json::json!(42));
+        assert_eq!(matches[1].line_index, 3);
+        assert_eq!(matches[1].value, serde_json::json!(7));
+    }
+
+    #[tokio::test]
+    async fn test_query_session_rejects_invalid_path() {
+        let result = query_session(
+            "/nonexistent/session.jsonl".to_string(),
+            "message.usage[".to_string(),
+        )
+        .await;
+
+        assert!(result.unwrap_err().starts_with("Invalid JSONPath"));
+    }
+}
@@ -0,0 +1,759 @@
+//! Raw JSONL line access
+//!
+//! Returns unparsed line text for debugging and custom tooling, finds lines
+//! by UUID prefix, compacts whitespace-heavy lines in place and truncates
+//! oversized tool outputs.
+//! Line indices are 0-based and count non-empty lines, matching `find_line_ranges`.
+
+use super::delete::validate_delete_path;
+use crate::commands::error::CommandError;
+pub(crate) use crate::commands::fs_utils::map_session_file;
+use crate::commands::fs_utils::write_file_atomically_checked;
+use crate::utils::find_line_ranges;
+use chrono::Utc;
+use memchr::memmem;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::time::SystemTime;
+use tauri::command;
+
+/// Outcome of compacting a session file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactSessionResult {
+    /// Copy of the original file; `None` if nothing needed compacting
+    pub backup_path: Option<String>,
+    pub original_bytes: u64,
+    pub compacted_bytes: u64,
+    pub bytes_saved: u64,
+    /// Lines that were rewritten to a shorter form
+    pub lines_compacted: usize,
+    /// Lines that are not valid JSON and were copied verbatim
+    pub lines_skipped: usize,
+}
+
+/// Outcome of truncating oversized tool outputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TruncateOutputsResult {
+    /// Copy of the original file; `None` if nothing exceeded the limit
+    pub backup_path: Option<String>,
+    /// Lines with at least one truncated output
+    pub messages_truncated: usize,
+    pub original_bytes: u64,
+    pub truncated_bytes: u64,
+    pub bytes_saved: u64,
+}
+
+/// Get the exact raw JSON text of a line without parsing it.
+///
+/// Returns `None` if `line_index` is out of range.
+#[command]
+pub async fn get_raw_line(file_path: String, line_index: usize) -> Result<Option<String>, String> {
+    let mmap = map_session_file(&file_path)?;
+
+    Ok(find_line_ranges(&mmap)
+        .get(line_index)
+        .map(|&(start, end)| String::from_utf8_lossy(&mmap[start..end]).into_owned()))
+}
+
+/// Minimal view of a log line carrying only its UUID
+#[derive(Deserialize)]
+struct UuidProbe {
+    uuid: Option<String>,
+}
+
+/// Find the lines whose message `uuid` starts with `prefix`.
+///
+/// The prefix may only contain hex digits and hyphens and is matched
+/// case-insensitively. Lines not containing the prefix at all are skipped
+/// without parsing. Returns 0-based line indices in file order.
+#[command]
+pub async fn find_messages_by_uuid_prefix(
+    file_path: String,
+    prefix: String,
+) -> Result<Vec<usize>, String> {
+    let prefix = prefix.trim().to_ascii_lowercase();
+    if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        return Err(format!(
+            "Invalid UUID prefix '{prefix}': only hex digits and hyphens are allowed"
+        ));
+    }
+
+    let mmap = map_session_file(&file_path)?;
+    let finder = memmem::Finder::new(prefix.as_bytes());
+
+    Ok(find_line_ranges(&mmap)
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, (start, end))| {
+            let line = &mmap[start..end];
+            if finder.find(&line.to_ascii_lowercase()).is_none() {
+                return false;
+            }
+            // simd-json requires mutable slice
+            let mut line_bytes = line.to_vec();
+            simd_json::serde::from_slice::<UuidProbe>(&mut line_bytes)
+                .ok()
+                .and_then(|probe| probe.uuid)
+                .is_some_and(|uuid| uuid.to_ascii_lowercase().starts_with(&prefix))
+        })
+        .map(|(line_index, _)| line_index)
+        .collect())
+}
+
+/// Count the non-empty lines of a session file without parsing them
+#[command]
+pub async fn count_lines(file_path: String) -> Result<usize, String> {
+    let mmap = map_session_file(&file_path)?;
+    Ok(find_line_ranges(&mmap).len())
+}
+
+/// Strip insignificant whitespace from a JSON line.
+///
+/// Returns `None` if the line is not valid JSON. Only whitespace outside
+/// strings is removed, so keys, key order and number formatting are kept
+/// byte-for-byte.
+fn minify_json_line(line: &[u8]) -> Option<Vec<u8>> {
+    serde_json::from_slice::<serde::de::IgnoredAny>(line).ok()?;
+
+    let mut out = Vec::with_capacity(line.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for &b in line {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+        } else if matches!(b, b' ' | b'\t' | b'\n' | b'\r') {
+            continue;
+        } else if b == b'"' {
+            in_string = true;
+        }
+        out.push(b);
+    }
+    Some(out)
+}
+
+/// Size and modification time of a file, to notice writes during a rewrite
+fn file_stamp(path: &Path) -> Result<(u64, Option<SystemTime>), String> {
+    let metadata =
+        fs::metadata(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok((metadata.len(), metadata.modified().ok()))
+}
+
+/// Back up a session as `<file>.<timestamp>.bak` and replace it with `data`.
+///
+/// `stamp` is the session's `file_stamp` from before it was read. If the
+/// session changed since (Claude appended to a live session), nothing is
+/// replaced and the backup is removed, so no appended line is lost.
+fn replace_session(
+    path: &Path,
+    data: &[u8],
+    stamp: (u64, Option<SystemTime>),
+) -> Result<String, CommandError> {
+    let unchanged = || {
+        if file_stamp(path)? == stamp {
+            Ok(())
+        } else {
+            Err(
+                "Session was modified while it was being rewritten; try again once it is idle"
+                    .to_string(),
+            )
+        }
+    };
+    unchanged().map_err(CommandError::Io)?;
+
+    let backup = format!(
+        "{}.{}.bak",
+        path.display(),
+        Utc::now().format("%Y%m%d%H%M%S")
+    );
+    fs::copy(path, &backup).map_err(|e| CommandError::io("Failed to back up session", &e))?;
+    if let Err(e) = write_file_atomically_checked(path, data, unchanged) {
+        let _ = fs::remove_file(&backup);
+        return Err(CommandError::Io(e));
+    }
+    Ok(backup)
+}
+
+/// Compact every line of a session file without path validation
+fn compact_session_file(path: &Path) -> Result<CompactSessionResult, CommandError> {
+    let file_path = path.to_string_lossy();
+    let stamp = file_stamp(path).map_err(CommandError::Io)?;
+    let original = map_session_file(&file_path).map_err(CommandError::Io)?;
+
+    let mut compacted = Vec::with_capacity(original.len());
+    let mut lines_compacted = 0;
+    let mut lines_skipped = 0;
+
+    // Split on every newline so empty lines and the trailing newline survive
+    for (i, line) in original.split(|&b| b == b'\n').enumerate() {
+        if i > 0 {
+            compacted.push(b'\n');
+        }
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(minified) = minify_json_line(line) {
+            if minified.len() < line.len() {
+                lines_compacted += 1;
+            }
+            compacted.extend_from_slice(&minified);
+        } else {
+            lines_skipped += 1;
+            compacted.extend_from_slice(line);
+        }
+    }
+
+    let original_bytes = original.len() as u64;
+    let compacted_bytes = compacted.len() as u64;
+    drop(original);
+
+    let backup_path = if lines_compacted > 0 {
+        Some(replace_session(path, &compacted, stamp)?)
+    } else {
+        None
+    };
+
+    Ok(CompactSessionResult {
+        backup_path,
+        original_bytes,
+        compacted_bytes,
+        bytes_saved: original_bytes - compacted_bytes,
+        lines_compacted,
+        lines_skipped,
+    })
+}
+
+/// Rewrite a session with insignificant JSON whitespace removed.
+///
+/// This is a lossless cleanup: only whitespace outside JSON strings is
+/// dropped, and lines that do not parse are copied verbatim. The original is
+/// backed up next to the session as `<file>.<timestamp>.bak` before the
+/// rewrite; nothing is written if no line gets shorter, or if the session is
+/// appended to while it is being compacted.
+///
+/// # Security
+/// Uses the same path checks as `delete_session` (absolute, no symlinks,
+/// safe filename, within `~/.claude`).
+#[command]
+pub async fn compact_session(file_path: String) -> Result<CompactSessionResult, CommandError> {
+    let path = Path::new(&file_path);
+    if !path.is_file() {
+        return Err(CommandError::NotFound(format!(
+            "Session file not found: {file_path}"
+        )));
+    }
+    validate_delete_path(&file_path)?;
+    compact_session_file(path)
+}
+
+/// Start and byte count of a trailing `[truncated N bytes]` marker left by
+/// an earlier truncation
+fn truncation_marker(text: &str) -> Option<(usize, usize)> {
+    let start = text.rfind("\n[truncated ")?;
+    let count = text[start..]
+        .strip_prefix("\n[truncated ")
+        .and_then(|rest| rest.strip_suffix(" bytes]"))
+        .filter(|count| count.bytes().all(|b| b.is_ascii_digit()))?;
+    Some((start, count.parse().ok()?))
+}
+
+/// Cut `text` to at most `max_bytes` (on a char boundary) and append a
+/// `[truncated N bytes]` marker, where N counts every byte removed from the
+/// original output, including by earlier truncations. Returns false, leaving
+/// `text` unchanged, unless the result is strictly shorter.
+fn truncate_output_text(text: &mut String, max_bytes: usize) -> bool {
+    let (body_len, already_removed) = truncation_marker(text).unwrap_or((text.len(), 0));
+    if body_len <= max_bytes {
+        return false;
+    }
+    let mut cut = max_bytes;
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    let marker = format!("\n[truncated {} bytes]", body_len - cut + already_removed);
+    if cut + marker.len() >= text.len() {
+        return false;
+    }
+    text.truncate(cut);
+    text.push_str(&marker);
+    true
+}
+
+/// Truncate a tool result's `content`, either a string or a list of text blocks
+fn truncate_tool_result_content(content: &mut Value, max_bytes: usize) -> bool {
+    match content {
+        Value::String(text) => truncate_output_text(text, max_bytes),
+        Value::Array(blocks) => blocks
+            .iter_mut()
+            .filter_map(|block| match block.get_mut("text") {
+                Some(Value::String(text)) => Some(truncate_output_text(text, max_bytes)),
+                _ => None,
+            })
+            .fold(false, |changed, truncated| changed | truncated),
+        _ => false,
+    }
+}
+
+/// Truncate the oversized tool outputs of one log entry: `tool_result`
+/// blocks in the message content and string fields of `toolUseResult`.
+fn truncate_entry_outputs(entry: &mut Value, max_bytes: usize) -> bool {
+    let mut changed = false;
+
+    if let Some(Value::Array(items)) = entry.pointer_mut("/message/content") {
+        for item in items {
+            if item.get("type").and_then(Value::as_str) != Some("tool_result") {
+                continue;
+            }
+            if let Some(content) = item.get_mut("content") {
+                changed |= truncate_tool_result_content(content, max_bytes);
+            }
+        }
+    }
+
+    if let Some(Value::Object(result)) = entry.get_mut("toolUseResult") {
+        for value in result.values_mut() {
+            if let Value::String(text) = value {
+                changed |= truncate_output_text(text, max_bytes);
+            }
+        }
+    }
+
+    changed
+}
+
+/// Byte ranges of the string values in a valid JSON document, each keyed by
+/// its JSON pointer and including the surrounding quotes
+struct StringSpans<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    spans: Vec<(String, Range<usize>)>,
+}
+
+impl StringSpans<'_> {
+    fn of(bytes: &[u8]) -> Vec<(String, Range<usize>)> {
+        let mut scanner = StringSpans {
+            bytes,
+            pos: 0,
+            spans: Vec::new(),
+        };
+        scanner.value(&mut String::new());
+        scanner.spans
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip the string starting at the current quote and return its range
+    fn string(&mut self) -> Range<usize> {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(&b) = self.bytes.get(self.pos) {
+            self.pos += if b == b'\\' { 2 } else { 1 };
+            if b == b'"' {
+                break;
+            }
+        }
+        start..self.pos.min(self.bytes.len())
+    }
+
+    fn value(&mut self, pointer: &mut String) {
+        self.skip_whitespace();
+        match self.bytes.get(self.pos) {
+            Some(b'"') => {
+                let span = self.string();
+                self.spans.push((pointer.clone(), span));
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    match self.bytes.get(self.pos) {
+                        Some(b'"') => {}
+                        Some(b',') => {
+                            self.pos += 1;
+                            continue;
+                        }
+                        _ => break,
+                    }
+                    let key_span = self.string();
+                    let key: String =
+                        serde_json::from_slice(&self.bytes[key_span]).unwrap_or_default();
+                    self.skip_whitespace();
+                    self.pos += 1; // `:`
+                    let len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                    self.value(pointer);
+                    pointer.truncate(len);
+                }
+                self.pos += 1;
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut index = 0;
+                loop {
+                    self.skip_whitespace();
+                    match self.bytes.get(self.pos) {
+                        None | Some(b']') => break,
+                        Some(b',') => {
+                            self.pos += 1;
+                            continue;
+                        }
+                        Some(_) => {}
+                    }
+                    let len = pointer.len();
+                    pointer.push_str(&format!("/{index}"));
+                    self.value(pointer);
+                    pointer.truncate(len);
+                    index += 1;
+                }
+                self.pos += 1;
+            }
+            Some(_) => {
+                while self.bytes.get(self.pos).is_some_and(|b| {
+                    !matches!(b, b',' | b']' | b'}' | b' ' | b'\t' | b'\n' | b'\r')
+                }) {
+                    self.pos += 1;
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+/// Copy `line` into `out`, replacing only the string values that differ in
+/// `entry`, so key order, escaping and number formatting elsewhere are kept
+/// byte for byte
+fn splice_changed_strings(line: &[u8], entry: &Value, out: &mut Vec<u8>) {
+    let mut copied = 0;
+    for (pointer, span) in StringSpans::of(line) {
+        let Some(Value::String(text)) = entry.pointer(&pointer) else {
+            continue;
+        };
+        if serde_json::from_slice::<String>(&line[span.clone()]).is_ok_and(|old| old == *text) {
+            continue;
+        }
+        out.extend_from_slice(&line[copied..span.start]);
+        let _ = serde_json::to_writer(&mut *out, text);
+        copied = span.end;
+    }
+    out.extend_from_slice(&line[copied..]);
+}
+
+/// Truncate tool outputs of a session file without path validation
+fn truncate_session_outputs(
+    path: &Path,
+    max_bytes: usize,
+) -> Result<TruncateOutputsResult, CommandError> {
+    let file_path = path.to_string_lossy();
+    let stamp = file_stamp(path).map_err(CommandError::Io)?;
+    let original = map_session_file(&file_path).map_err(CommandError::Io)?;
+
+    let mut rewritten = Vec::with_capacity(original.len());
+    let mut messages_truncated = 0;
+
+    // Split on every newline so empty lines and the trailing newline survive
+    for (i, line) in original.split(|&b| b == b'\n').enumerate() {
+        if i > 0 {
+            rewritten.push(b'\n');
+        }
+        let truncated = serde_json::from_slice::<Value>(line)
+            .ok()
+            .and_then(|mut entry| truncate_entry_outputs(&mut entry, max_bytes).then_some(entry));
+        if let Some(entry) = truncated {
+            messages_truncated += 1;
+            splice_changed_strings(line, &entry, &mut rewritten);
+        } else {
+            rewritten.extend_from_slice(line);
+        }
+    }
+
+    let original_bytes = original.len() as u64;
+    let truncated_bytes = rewritten.len() as u64;
+    drop(original);
+
+    let backup_path = if messages_truncated > 0 {
+        Some(replace_session(path, &rewritten, stamp)?)
+    } else {
+        None
+    };
+
+    Ok(TruncateOutputsResult {
+        backup_path,
+        messages_truncated,
+        original_bytes,
+        truncated_bytes,
+        bytes_saved: original_bytes.saturating_sub(truncated_bytes),
+    })
+}
+
+/// Shrink a bloated session by truncating tool outputs larger than `max_bytes`.
+///
+/// Each oversized `tool_result` text (and string field of `toolUseResult`)
+/// keeps its first `max_bytes` bytes followed by a `[truncated N bytes]`
+/// marker. Only the truncated strings change, so rewritten lines keep their
+/// key order and formatting; other lines are copied verbatim.
+/// The original is backed up next to the session as `<file>.<timestamp>.bak`
+/// before the rewrite; nothing is written if no output exceeds the limit, or
+/// if the session is appended to while it is being rewritten.
+///
+/// # Security
+/// Uses the same path checks as `delete_session` (absolute, no symlinks,
+/// safe filename, within `~/.claude`).
+#[command]
+pub async fn truncate_large_outputs(
+    file_path: String,
+    max_bytes: usize,
+) -> Result<TruncateOutputsResult, CommandError> {
+    let path = Path::new(&file_path);
+    if !path.is_file() {
+        return Err(CommandError::NotFound(format!(
+            "Session file not found: {file_path}"
+        )));
+    }
+    validate_delete_path(&file_path)?;
+    truncate_session_outputs(path, max_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_find_messages_by_uuid_prefix() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let lines = [
+            r#"{"type":"user","uuid":"3f2a9c10-0000-4000-8000-000000000001","parentUuid":null}"#,
+            r#"{"type":"assistant","uuid":"7b1e0000-0000-4000-8000-000000000002","parentUuid":"3f2a9c10-0000-4000-8000-000000000001"}"#,
+            r#"{"type":"user","uuid":"3F2A0000-0000-4000-8000-000000000003"}"#,
+            r#"{"type":"summary","leafUuid":"3f2a9c10-0000-4000-8000-000000000001"}"#,
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        assert_eq!(
+            find_messages_by_uuid_prefix(path.clone(), "3f2a".to_string())
+                .await
+                .unwrap(),
+            vec![0, 2]
+        );
+        assert_eq!(
+            find_messages_by_uuid_prefix(path.clone(), "3F2A9C10-".to_string())
+                .await
+                .unwrap(),
+            vec![0]
+        );
+        assert!(
+            find_messages_by_uuid_prefix(path.clone(), "ffff".to_string())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(find_messages_by_uuid_prefix(path, "3f2a\"".to_string())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_raw_line_and_count_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        fs::write(&path, "{\"a\":1}\n\n{\"b\": 2}\n{\"c\":3}").unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        assert_eq!(count_lines(path.clone()).await.unwrap(), 3);
+        assert_eq!(
+            get_raw_line(path.clone(), 1).await.unwrap(),
+            Some("{\"b\": 2}".to_string())
+        );
+        assert_eq!(
+            get_raw_line(path.clone(), 2).await.unwrap(),
+            Some("{\"c\":3}".to_string())
+        );
+        assert_eq!(get_raw_line(path, 3).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_raw_access_missing_file() {
+        assert!(count_lines("/nonexistent/session.jsonl".to_string())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_minify_json_line_preserves_strings() {
+        assert_eq!(
+            minify_json_line(br#"{ "b" : 1.50, "a" : "x  \" y" }"#).unwrap(),
+            br#"{"b":1.50,"a":"x  \" y"}"#.to_vec()
+        );
+        assert!(minify_json_line(b"{not json").is_none());
+    }
+
+    #[test]
+    fn test_compact_session_file_backs_up_and_keeps_unparsable_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let original = "{ \"type\": \"user\" }\n\n{broken  line\n{\"a\":1}\n";
+        fs::write(&path, original).unwrap();
+
+        let result = compact_session_file(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{\"type\":\"user\"}\n\n{broken  line\n{\"a\":1}\n"
+        );
+        assert_eq!(result.lines_compacted, 1);
+        assert_eq!(result.lines_skipped, 1);
+        assert_eq!(result.bytes_saved, 3);
+        let backup = result.backup_path.unwrap();
+        assert_eq!(fs::read_to_string(backup).unwrap(), original);
+
+        // Already compact: nothing written, no backup
+        let again = compact_session_file(&path).unwrap();
+        assert!(again.backup_path.is_none());
+        assert_eq!(again.bytes_saved, 0);
+    }
+
+    #[test]
+    fn test_replace_session_refuses_after_append() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        fs::write(&path, "{ \"type\": \"user\" }\n").unwrap();
+        let stamp = file_stamp(&path).unwrap();
+
+        // Claude appends a line between the read and the rewrite
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        std::io::Write::write_all(&mut file, b"{\"type\":\"assistant\"}\n").unwrap();
+        drop(file);
+
+        let result = replace_session(&path, b"{\"type\":\"user\"}\n", stamp);
+        assert!(matches!(result, Err(CommandError::Io(_))));
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{ \"type\": \"user\" }\n{\"type\":\"assistant\"}\n"
+        );
+        // Neither a backup nor a temp file is left behind
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_truncate_output_text_respects_char_boundaries() {
+        let mut text = format!("h{}", "é".repeat(20));
+        assert!(truncate_output_text(&mut text, 2));
+        assert_eq!(text, "h\n[truncated 40 bytes]");
+
+        let mut short = "ok".to_string();
+        assert!(!truncate_output_text(&mut short, 2));
+        assert_eq!(short, "ok");
+
+        // Already truncated to the limit
+        assert!(!truncate_output_text(&mut text, 2));
+    }
+
+    #[test]
+    fn test_truncate_output_text_only_when_shorter() {
+        // One byte over the limit: the marker would make it longer
+        let mut barely = "a".repeat(11);
+        assert!(!truncate_output_text(&mut barely, 10));
+        assert_eq!(barely, "a".repeat(11));
+
+        let mut long = "a".repeat(100);
+        assert!(truncate_output_text(&mut long, 10));
+        assert_eq!(long, format!("{}\n[truncated 90 bytes]", "a".repeat(10)));
+    }
+
+    #[test]
+    fn test_truncate_output_text_again_keeps_original_count() {
+        let mut text = "a".repeat(100);
+        assert!(truncate_output_text(&mut text, 50));
+        assert_eq!(text, format!("{}\n[truncated 50 bytes]", "a".repeat(50)));
+
+        assert!(truncate_output_text(&mut text, 10));
+        assert_eq!(text, format!("{}\n[truncated 90 bytes]", "a".repeat(10)));
+        assert!(!truncate_output_text(&mut text, 10));
+    }
+
+    #[test]
+    fn test_string_spans_key_by_json_pointer() {
+        let line = br#"{"a/b": ["x", {"c": "y\"z"}], "n": 1, "t": true, "s" :"w"}"#;
+        let spans: Vec<(String, &[u8])> = StringSpans::of(line)
+            .into_iter()
+            .map(|(pointer, span)| (pointer, &line[span]))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("/a~1b/0".to_string(), &br#""x""#[..]),
+                ("/a~1b/1/c".to_string(), &br#""y\"z""#[..]),
+                ("/s".to_string(), &br#""w""#[..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_truncate_session_outputs_rewrites_only_oversized_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let big = "y".repeat(100);
+        let lines = [
+            r#"{"type":"user","message":{"role":"user","content":"hello"}}"#.to_string(),
+            format!(
+                r#"{{"uuid":"u2","type":"user","message":{{"role":"user","content":[{{"type":"tool_result","tool_use_id":"t1","content":"{big}"}}]}},"toolUseResult":{{"stdout":"{big}","interrupted":false,"cost":1.50}},"cwd":"\/caf\u00e9"}}"#
+            ),
+            format!(
+                r#"{{"type":"user","message":{{"role":"user","content":[{{"type":"tool_result","tool_use_id":"t2","content":[{{"type":"text","text":"{big}"}}]}}]}}}}"#
+            ),
+            "{broken".to_string(),
+        ];
+        let original = lines.join("\n") + "\n";
+        fs::write(&path, &original).unwrap();
+
+        let result = truncate_session_outputs(&path, 10).unwrap();
+        assert_eq!(result.messages_truncated, 2);
+        assert!(result.bytes_saved > 200);
+        assert_eq!(
+            fs::read_to_string(result.backup_path.unwrap()).unwrap(),
+            original
+        );
+
+        let content = fs::read_to_string(&path).unwrap();
+        let rewritten: Vec<&str> = content.split('\n').collect();
+        assert_eq!(rewritten[0], lines[0]);
+        assert_eq!(rewritten[3], "{broken");
+        let marker = format!("{}\n[truncated 90 bytes]", "y".repeat(10));
+        // Only the truncated strings change; key order, escapes and number
+        // formatting of the rest of the line are kept byte for byte
+        assert_eq!(
+            rewritten[1],
+            lines[1].replace(&big, &format!("{}\\n[truncated 90 bytes]", "y".repeat(10)))
+        );
+        let second: Value = serde_json::from_str(rewritten[1]).unwrap();
+        assert_eq!(second["toolUseResult"]["stdout"], marker.as_str());
+        let third: Value = serde_json::from_str(rewritten[2]).unwrap();
+        assert_eq!(
+            third["message"]["content"][0]["content"][0]["text"],
+            marker.as_str()
+        );
+
+        // Nothing left to truncate: no rewrite, no backup
+        let again = truncate_session_outputs(&path, 10).unwrap();
+        assert!(again.backup_path.is_none());
+        assert_eq!(again.messages_truncated, 0);
+    }
+}
@@ -3,16 +3,52 @@
 //! Provides functionality to rename Claude Code sessions by modifying
 //! the first user message in the session JSONL file.
 
+use super::bundle::claude_dir;
+use crate::commands::error::CommandError;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
 use tauri::command;
 
 lazy_static! {
     /// Regex for validating JSONL filename pattern (alphanumeric, underscore, hyphen only)
     static ref FILENAME_REGEX: Regex = Regex::new(r"^[A-Za-z0-9_-]+$").unwrap();
+
+    /// Per-session locks so concurrent renames of one file run one after another
+    static ref RENAME_LOCKS: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>> = Mutex::new(HashMap::new());
+}
+
+/// Run `f` while holding the rename lock of `file_path`.
+///
+/// Renames of different sessions proceed in parallel. The lock entry is
+/// dropped again once no other caller is waiting for it.
+fn with_session_lock<T>(file_path: &str, f: impl FnOnce() -> T) -> T {
+    let key = Path::new(file_path)
+        .canonicalize()
+        .unwrap_or_else(|_| PathBuf::from(file_path));
+    let lock = RENAME_LOCKS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(key.clone())
+        .or_default()
+        .clone();
+
+    let result = {
+        let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+        f()
+    };
+
+    let mut locks = RENAME_LOCKS.lock().unwrap_or_else(PoisonError::into_inner);
+    // Only the map and this caller hold the lock: nobody else is waiting
+    if Arc::strong_count(&lock) == 2 {
+        locks.remove(&key);
+    }
+    result
 }
 
 /// Result structure for rename operations
@@ -56,6 +92,22 @@ impl std::fmt::Display for RenameError {
     }
 }
 
+impl From<RenameError> for CommandError {
+    fn from(err: RenameError) -> Self {
+        let message = err.to_string();
+        match err {
+            RenameError::FileNotFound(_) => CommandError::NotFound(message),
+            RenameError::PermissionDenied(_) => CommandError::PermissionDenied(message),
+            RenameError::IoError(_) => CommandError::Io(message),
+            RenameError::InvalidJsonFormat(_)
+            | RenameError::EmptySession
+            | RenameError::NoUserMessage
+            | RenameError::UnsupportedContentFormat
+            | RenameError::InvalidTitle(_) => CommandError::InvalidInput(message),
+        }
+    }
+}
+
 /// Renames a Claude Code session by modifying the first user message.
 ///
 /// # Arguments
@@ -64,39 +116,71 @@ impl std::fmt::Display for RenameError {
 ///
 /// # Returns
 /// * `Ok(NativeRenameResult)` - Success with previous and new titles
-/// * `Err(String)` - Error description
+/// * `Err(CommandError)` - Error kind and description
+///
+/// Concurrent renames of the same session are serialized; a later call sees
+/// the title written by the earlier one, and fails with `NotFound` if the
+/// file was moved away in the meantime.
 #[command]
 pub async fn rename_session_native(
     file_path: String,
     new_title: String,
-) -> Result<NativeRenameResult, String> {
-    // 1. Validate file exists
-    if !std::path::Path::new(&file_path).exists() {
-        return Err(RenameError::FileNotFound(file_path).to_string());
-    }
+) -> Result<NativeRenameResult, CommandError> {
+    let claude_dir = claude_dir().map_err(RenameError::IoError)?;
+    rename_session_in(file_path, new_title, claude_dir).await
+}
+
+/// Rename a session that must live inside `claude_dir`.
+///
+/// Waiting for the session lock and the file I/O run on the blocking pool so
+/// the async runtime is never blocked.
+async fn rename_session_in(
+    file_path: String,
+    new_title: String,
+    claude_dir: PathBuf,
+) -> Result<NativeRenameResult, CommandError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let lock_path = file_path.clone();
+        with_session_lock(&lock_path, || {
+            // 1. Validate file exists (under the lock, so a file moved away
+            //    while this call waited is reported instead of recreated)
+            if !Path::new(&file_path).exists() {
+                return Err(RenameError::FileNotFound(file_path).into());
+            }
 
-    // 2. Validate file path is within ~/.claude directory (security: prevent path traversal)
-    validate_claude_path(&file_path)?;
+            // 2. Validate file path is within ~/.claude directory (security: prevent path traversal)
+            validate_claude_path(&file_path, &claude_dir)?;
 
+            rename_session_file(file_path, &new_title)
+        })
+    })
+    .await
+    .map_err(|e| CommandError::Io(format!("Task join error: {e}")))?
+}
+
+/// Apply a title to a session file; callers must hold its rename lock
+fn rename_session_file(
+    file_path: String,
+    new_title: &str,
+) -> Result<NativeRenameResult, CommandError> {
     // 3. Validate title does not contain ']' character (due to nested bracket limitation)
     if new_title.contains(']') {
         return Err(RenameError::InvalidTitle(
             "Title cannot contain ']' character. Use '[' for nested prefixes instead.".to_string(),
         )
-        .to_string());
+        .into());
     }
 
     // 4. Read all lines from JSONL file
-    let file =
-        File::open(&file_path).map_err(|e| RenameError::IoError(e.to_string()).to_string())?;
+    let file = File::open(&file_path).map_err(|e| RenameError::IoError(e.to_string()))?;
     let reader = BufReader::new(file);
     let mut lines: Vec<String> = reader
         .lines()
         .collect::<Result<_, _>>()
-        .map_err(|e| RenameError::IoError(e.to_string()).to_string())?;
+        .map_err(|e| RenameError::IoError(e.to_string()))?;
 
     if lines.is_empty() {
-        return Err(RenameError::EmptySession.to_string());
+        return Err(RenameError::EmptySession.into());
     }
 
     // 5. Find first user message (type: "user", not isMeta)
@@ -104,12 +188,11 @@ pub async fn rename_session_native(
 
     // 6. Parse the user message line as JSON
     let mut user_message: serde_json::Value = serde_json::from_str(&lines[user_message_index])
-        .map_err(|e| RenameError::InvalidJsonFormat(e.to_string()).to_string())?;
+        .map_err(|e| RenameError::InvalidJsonFormat(e.to_string()))?;
 
     // 7. Extract current message content - handle nested structure
-    let current_message = extract_message_content(&user_message).ok_or_else(|| {
-        RenameError::InvalidJsonFormat("No 'message' field found".to_string()).to_string()
-    })?;
+    let current_message = extract_message_content(&user_message)
+        .ok_or_else(|| RenameError::InvalidJsonFormat("No 'message' field found".to_string()))?;
 
     // 8. Strip existing bracket prefix if present
     let base_message = strip_title_prefix(&current_message);
@@ -123,39 +206,36 @@ pub async fn rename_session_native(
 
     // 10. Update JSON object - handle nested structure
     if !update_message_content(&mut user_message, &new_message) {
-        return Err(RenameError::UnsupportedContentFormat.to_string());
+        return Err(RenameError::UnsupportedContentFormat.into());
     }
 
     // 11. Serialize back to JSON string
     lines[user_message_index] = serde_json::to_string(&user_message)
-        .map_err(|e| RenameError::InvalidJsonFormat(e.to_string()).to_string())?;
+        .map_err(|e| RenameError::InvalidJsonFormat(e.to_string()))?;
 
     // 12. Write atomically (write to temp, then rename)
     let temp_path = format!("{file_path}.tmp");
     {
-        let mut temp_file = File::create(&temp_path)
-            .map_err(|e| RenameError::IoError(e.to_string()).to_string())?;
+        let mut temp_file =
+            File::create(&temp_path).map_err(|e| RenameError::IoError(e.to_string()))?;
 
         for (i, line) in lines.iter().enumerate() {
             if i > 0 {
-                writeln!(temp_file).map_err(|e| RenameError::IoError(e.to_string()).to_string())?;
+                writeln!(temp_file).map_err(|e| RenameError::IoError(e.to_string()))?;
             }
-            write!(temp_file, "{line}")
-                .map_err(|e| RenameError::IoError(e.to_string()).to_string())?;
+            write!(temp_file, "{line}").map_err(|e| RenameError::IoError(e.to_string()))?;
         }
     }
 
     // 13. Atomic rename (Windows compatibility: remove existing file first)
     #[cfg(target_os = "windows")]
     {
-        if std::path::Path::new(&file_path).exists() {
-            fs::remove_file(&file_path)
-                .map_err(|e| RenameError::IoError(e.to_string()).to_string())?;
+        if Path::new(&file_path).exists() {
+            fs::remove_file(&file_path).map_err(|e| RenameError::IoError(e.to_string()))?;
         }
     }
 
-    fs::rename(&temp_path, &file_path)
-        .map_err(|e| RenameError::IoError(e.to_string()).to_string())?;
+    fs::rename(&temp_path, &file_path).map_err(|e| RenameError::IoError(e.to_string()))?;
 
     Ok(NativeRenameResult {
         success: true,
@@ -165,21 +245,21 @@ pub async fn rename_session_native(
     })
 }
 
-/// Validates that the file path is within the ~/.claude directory.
+/// Validates that the file path is within `claude_dir` (`~/.claude`).
 /// This prevents path traversal attacks that could modify arbitrary files.
 ///
 /// Security checks performed:
 /// 1. Path must be absolute
 /// 2. No symlinks allowed in any path component
 /// 3. Filename must match pattern ^[A-Za-z0-9_-]+$
-fn validate_claude_path(file_path: &str) -> Result<(), String> {
+fn validate_claude_path(file_path: &str, claude_dir: &Path) -> Result<(), CommandError> {
     let file_path_buf = std::path::PathBuf::from(file_path);
 
     // 1. Require absolute path
     if !file_path_buf.is_absolute() {
-        return Err(
-            RenameError::PermissionDenied("File path must be absolute".to_string()).to_string(),
-        );
+        return Err(CommandError::InvalidInput(
+            "File path must be absolute".to_string(),
+        ));
     }
 
     // 2. Block symlinks in path components
@@ -195,7 +275,7 @@ fn validate_claude_path(file_path: &str) -> Result<(), String> {
                 return Err(RenameError::PermissionDenied(
                     "Symlinks are not allowed in path".to_string(),
                 )
-                .to_string());
+                .into());
             }
         }
         current = parent;
@@ -205,8 +285,7 @@ fn validate_claude_path(file_path: &str) -> Result<(), String> {
     if let Ok(metadata) = fs::symlink_metadata(&file_path_buf) {
         if metadata.file_type().is_symlink() {
             return Err(
-                RenameError::PermissionDenied("File path cannot be a symlink".to_string())
-                    .to_string(),
+                RenameError::PermissionDenied("File path cannot be a symlink".to_string()).into(),
             );
         }
     }
@@ -219,31 +298,22 @@ fn validate_claude_path(file_path: &str) -> Result<(), String> {
                 "Filename must contain only alphanumeric characters, underscores, and hyphens"
                     .to_string(),
             )
-            .to_string());
+            .into());
         }
     } else {
-        return Err(RenameError::PermissionDenied("Invalid filename".to_string()).to_string());
+        return Err(RenameError::PermissionDenied("Invalid filename".to_string()).into());
     }
 
     // Canonicalize to resolve .. components (symlinks already blocked above)
     let canonical_path = file_path_buf
         .canonicalize()
-        .map_err(|e| RenameError::IoError(e.to_string()).to_string())?;
-
-    // Get home directory
-    let home_dir = dirs::home_dir().ok_or_else(|| {
-        RenameError::IoError("Cannot determine home directory".to_string()).to_string()
-    })?;
-
-    // Build the allowed claude directory path
-    let claude_dir = home_dir.join(".claude");
+        .map_err(|e| CommandError::io("Failed to resolve path", &e))?;
 
     // Verify the file is within ~/.claude
-    if !canonical_path.starts_with(&claude_dir) {
-        return Err(RenameError::PermissionDenied(
+    if !canonical_path.starts_with(claude_dir) {
+        return Err(CommandError::OutsideClaudeDir(
             "File path must be within ~/.claude directory".to_string(),
-        )
-        .to_string());
+        ));
     }
 
     Ok(())
@@ -363,7 +433,7 @@ fn strip_title_prefix(message: &str) -> String {
 
 /// Finds the index of the first real user message in the JSONL lines.
 /// Skips non-user messages (file-history-snapshot, progress, etc.) and meta messages.
-fn find_first_user_message_index(lines: &[String]) -> Result<usize, String> {
+fn find_first_user_message_index(lines: &[String]) -> Result<usize, RenameError> {
     for (index, line) in lines.iter().enumerate() {
         // Try to parse as JSON
         if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
@@ -390,12 +460,14 @@ fn find_first_user_message_index(lines: &[String]) -> Result<usize, String> {
         }
     }
 
-    Err(RenameError::NoUserMessage.to_string())
+    Err(RenameError::NoUserMessage)
 }
 
 /// Resets session name to original (removes title prefix)
 #[command]
-pub async fn reset_session_native_name(file_path: String) -> Result<NativeRenameResult, String> {
+pub async fn reset_session_native_name(
+    file_path: String,
+) -> Result<NativeRenameResult, CommandError> {
     rename_session_native(file_path, String::new()).await
 }
 
@@ -403,6 +475,10 @@ pub async fn reset_session_native_name(file_path: String) -> Result<NativeRename
 mod tests {
     use super::*;
 
+    fn default_claude_dir() -> PathBuf {
+        claude_dir().unwrap()
+    }
+
     #[test]
     fn test_strip_title_prefix() {
         assert_eq!(
@@ -599,7 +675,7 @@ mod tests {
         let lines: Vec<String> = vec![];
         let result = find_first_user_message_index(&lines);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("No user message"));
+        assert!(result.unwrap_err().to_string().contains("No user message"));
     }
 
     #[test]
@@ -666,15 +742,18 @@ mod tests {
 
     #[test]
     fn test_validate_claude_path_rejects_relative_path() {
-        let result = validate_claude_path("relative/path/file.jsonl");
+        let result = validate_claude_path("relative/path/file.jsonl", &default_claude_dir());
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("must be absolute"));
+        assert!(matches!(
+            result,
+            Err(CommandError::InvalidInput(message)) if message.contains("must be absolute")
+        ));
     }
 
     #[test]
     fn test_validate_claude_path_rejects_invalid_filename() {
         // Filename with dots should be rejected by regex
-        let result = validate_claude_path("/etc/passwd");
+        let result = validate_claude_path("/etc/passwd", &default_claude_dir());
         assert!(result.is_err());
         // Will fail on filename validation (passwd has no extension, or if it checks "passwd")
     }
@@ -682,7 +761,7 @@ mod tests {
     #[test]
     fn test_validate_claude_path_rejects_non_claude_directory() {
         // Use a path with valid filename but wrong directory
-        let result = validate_claude_path("/tmp/validfilename.jsonl");
+        let result = validate_claude_path("/tmp/validfilename.jsonl", &default_claude_dir());
         assert!(result.is_err());
         // Should fail on directory check or canonicalize
     }
@@ -702,7 +781,8 @@ mod tests {
                                     let path = file.path();
                                     if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
                                         let test_path = path.to_string_lossy().to_string();
-                                        let result = validate_claude_path(&test_path);
+                                        let result =
+                                            validate_claude_path(&test_path, &default_claude_dir());
                                         assert!(
                                             result.is_ok(),
                                             "Validation failed for valid path {test_path}: {result:?}"
@@ -722,8 +802,8 @@ mod tests {
     #[test]
     fn test_validate_claude_path_nonexistent_file() {
         // Nonexistent file should fail at canonicalize
-        let result = validate_claude_path("/nonexistent/path/to/file.jsonl");
-        assert!(result.is_err());
+        let result = validate_claude_path("/nonexistent/path/to/file.jsonl", &default_claude_dir());
+        assert!(matches!(result, Err(CommandError::NotFound(_))));
     }
 
     #[test]
@@ -736,7 +816,7 @@ mod tests {
                 .join("test.file.jsonl")
                 .to_string_lossy()
                 .to_string();
-            let result = validate_claude_path(&path_with_dot);
+            let result = validate_claude_path(&path_with_dot, &default_claude_dir());
             // Will fail either on filename validation or canonicalize (file doesn't exist)
             assert!(result.is_err());
         }
@@ -762,4 +842,93 @@ mod tests {
         assert_eq!(result, "] Message");
         // This is why we reject titles with ']' in rename_session_native
     }
+
+    // --- Concurrency tests ---
+
+    /// A session inside a fresh claude directory, with symlinked temp
+    /// prefixes (macOS `/var`) resolved so the path checks accept it
+    fn claude_session(temp_dir: &tempfile::TempDir) -> (PathBuf, PathBuf) {
+        let claude_dir = temp_dir.path().canonicalize().unwrap().join(".claude");
+        let project = claude_dir.join("projects").join("-app");
+        fs::create_dir_all(&project).unwrap();
+        let path = project.join("session.jsonl");
+        fs::write(
+            &path,
+            r#"{"type":"user","message":{"role":"user","content":"Original message"}}"#,
+        )
+        .unwrap();
+        (claude_dir, path)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_renames_of_same_session_serialize() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (claude_dir, path) = claude_session(&temp_dir);
+        let file_path = path.to_string_lossy().to_string();
+
+        let rename = |title: &str| {
+            rename_session_in(file_path.clone(), title.to_string(), claude_dir.clone())
+        };
+        let (first, second) = tokio::join!(rename("First"), rename("Second"));
+        let results = [first.unwrap(), second.unwrap()];
+
+        // Whichever ran second saw the title written by the first
+        let (earlier, later) = if results[0].previous_title == "Original message" {
+            (&results[0], &results[1])
+        } else {
+            (&results[1], &results[0])
+        };
+        assert_eq!(earlier.previous_title, "Original message");
+        assert_eq!(later.previous_title, earlier.new_title);
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains(&later.new_title));
+        assert!(!Path::new(&format!("{file_path}.tmp")).exists());
+        assert!(!RENAME_LOCKS
+            .lock()
+            .unwrap()
+            .contains_key(&path.canonicalize().unwrap()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_rename_reports_file_moved_away_while_waiting() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (claude_dir, path) = claude_session(&temp_dir);
+        let file_path = path.to_string_lossy().to_string();
+        let key = path.canonicalize().unwrap();
+        let waiters = |key: &PathBuf| {
+            RENAME_LOCKS
+                .lock()
+                .unwrap()
+                .get(key)
+                .map_or(0, Arc::strong_count)
+        };
+
+        // An earlier operation holds the session lock and moves the file away
+        // once the rename below is queued behind it
+        let locked = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let holder = {
+            let (file_path, path, key) = (file_path.clone(), path.clone(), key.clone());
+            let locked = Arc::clone(&locked);
+            tokio::task::spawn_blocking(move || {
+                with_session_lock(&file_path, || {
+                    locked.store(true, std::sync::atomic::Ordering::SeqCst);
+                    // The map, this holder and the queued rename
+                    while waiters(&key) < 3 {
+                        std::thread::yield_now();
+                    }
+                    fs::rename(&path, path.with_extension("moved")).unwrap();
+                });
+            })
+        };
+        while !locked.load(std::sync::atomic::Ordering::SeqCst) {
+            tokio::task::yield_now().await;
+        }
+
+        let result = rename_session_in(file_path, "T".to_string(), claude_dir).await;
+        holder.await.unwrap();
+        assert!(matches!(result, Err(CommandError::NotFound(_))));
+        assert!(!path.exists());
+        assert!(path.with_extension("moved").exists());
+    }
 }
@@ -2,7 +2,9 @@
 //!
 //! Provides functionality to continue a Claude Code session
 //! by opening a terminal with `claude --resume <session-id>`.
+//! On macOS the terminal can be Terminal.app or iTerm2.
 
+use crate::commands::error::CommandError;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::process::Command;
@@ -10,30 +12,132 @@ use tauri::command;
 
 lazy_static! {
     /// Regex for validating session ID (UUID format: alphanumeric and hyphens)
-    static ref SESSION_ID_REGEX: Regex = Regex::new(r"^[A-Za-z0-9_-]+$").unwrap();
+    pub(crate) static ref SESSION_ID_REGEX: Regex = Regex::new(r"^[A-Za-z0-9_-]+$").unwrap();
+
+    /// Regex for validating extra CLI arguments: letters, digits and
+    /// `- _ . = : / @ , +`. Spaces, quotes, backticks and shell
+    /// metacharacters such as `;`, `&`, `|`, `$`, `<`, `>` and `*` are rejected.
+    pub(crate) static ref CLI_ARG_REGEX: Regex = Regex::new(r"^[A-Za-z0-9_\-.=:/@,+]+$").unwrap();
 }
 
 /// Opens a terminal and resumes the given Claude Code session.
 ///
 /// # Arguments
 /// * `session_id` - The actual session ID (UUID) to resume
+/// * `terminal_app` - macOS only: "Terminal" or "iTerm". Defaults to iTerm2 when
+///   installed; falls back to Terminal.app if the requested app is missing
+/// * `extra_args` - Additional CLI flags appended after the session ID, e.g.
+///   `["--model", "opus"]` or `["--permission-mode=plan"]`
 ///
 /// # Security
 /// - Session ID is validated against a safe pattern
+/// - Each extra argument may only contain letters, digits and `- _ . = : / @ , +`;
+///   anything else (spaces, quotes, shell metacharacters) is rejected
 /// - Only `claude --resume` command is executed
 #[command]
-pub async fn resume_session(session_id: String) -> Result<(), String> {
+pub async fn resume_session(
+    session_id: String,
+    terminal_app: Option<String>,
+    extra_args: Option<Vec<String>>,
+) -> Result<(), CommandError> {
     // Validate session ID format
     if session_id.is_empty() || !SESSION_ID_REGEX.is_match(&session_id) {
-        return Err("Invalid session ID format".to_string());
+        return Err(CommandError::InvalidInput(
+            "Invalid session ID format".to_string(),
+        ));
+    }
+
+    let extra_args = extra_args.unwrap_or_default();
+    if let Some(arg) = extra_args.iter().find(|arg| !CLI_ARG_REGEX.is_match(arg)) {
+        return Err(CommandError::InvalidInput(format!(
+            "Invalid CLI argument: {arg}"
+        )));
     }
 
-    open_terminal_with_command(&format!("claude --resume {session_id}"))
+    open_terminal_with_command(
+        &resume_command(&session_id, &extra_args),
+        terminal_app.as_deref(),
+    )
+}
+
+/// Build `claude --resume <id> [args...]` from validated parts
+fn resume_command(session_id: &str, extra_args: &[String]) -> String {
+    let mut cmd = format!("claude --resume {session_id}");
+    for arg in extra_args {
+        cmd.push(' ');
+        cmd.push_str(arg);
+    }
+    cmd
+}
+
+/// Terminal applications supported on macOS
+#[cfg(any(target_os = "macos", test))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MacTerminal {
+    Terminal,
+    ITerm,
+}
+
+/// Check the usual install locations of iTerm2
+#[cfg(target_os = "macos")]
+fn is_iterm_installed() -> bool {
+    let user_app = dirs::home_dir().map(|home| home.join("Applications/iTerm.app"));
+    std::path::Path::new("/Applications/iTerm.app").exists()
+        || user_app.is_some_and(|path| path.exists())
+}
+
+/// Pick the macOS terminal: the requested app if available, otherwise
+/// iTerm2 when installed, otherwise Terminal.app.
+#[cfg(any(target_os = "macos", test))]
+fn resolve_mac_terminal(requested: Option<&str>, iterm_installed: bool) -> MacTerminal {
+    let requested = requested.map(|name| name.trim().to_lowercase());
+    match requested.as_deref() {
+        Some("iterm" | "iterm2" | "iterm.app") | None if iterm_installed => MacTerminal::ITerm,
+        _ => MacTerminal::Terminal,
+    }
+}
+
+/// Escape a shell command for embedding in an `AppleScript` string literal.
+///
+/// Backslashes and double quotes are escaped for `AppleScript`; `$` and
+/// backticks are additionally escaped for the shell that runs the script,
+/// so variable expansion and command substitution stay literal.
+#[cfg(any(target_os = "macos", test))]
+fn escape_applescript(cmd: &str) -> String {
+    let mut escaped = String::with_capacity(cmd.len());
+    for c in cmd.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '$' | '`' => {
+                escaped.push_str("\\\\");
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Build the `AppleScript` that runs `cmd` in a new window of `terminal`
+#[cfg(any(target_os = "macos", test))]
+fn mac_terminal_script(terminal: MacTerminal, cmd: &str) -> String {
+    let escaped = escape_applescript(cmd);
+    match terminal {
+        MacTerminal::Terminal => format!(
+            "tell application \"Terminal\"\n  activate\n  do script \"{escaped}\"\nend tell"
+        ),
+        MacTerminal::ITerm => format!(
+            "tell application \"iTerm\"\n  activate\n  set newWindow to (create window with default profile)\n  tell current session of newWindow\n    write text \"{escaped}\"\n  end tell\nend tell"
+        ),
+    }
 }
 
 /// Opens a platform-specific terminal with the given command.
 /// Clears the CLAUDECODE env var so `claude` doesn't think it's a nested session.
-fn open_terminal_with_command(cmd: &str) -> Result<(), String> {
+/// `terminal_app` selects the macOS terminal and is ignored elsewhere.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+fn open_terminal_with_command(cmd: &str, terminal_app: Option<&str>) -> Result<(), CommandError> {
     #[cfg(target_os = "windows")]
     {
         // On Windows, open a new cmd.exe window with the command
@@ -42,23 +146,21 @@ fn open_terminal_with_command(cmd: &str) -> Result<(), String> {
             .args(["/c", "start", "cmd", "/k", cmd])
             .env_remove("CLAUDECODE")
             .spawn()
-            .map_err(|e| format!("Failed to open terminal: {e}"))?;
+            .map_err(|e| CommandError::io("Failed to open terminal", &e))?;
     }
 
     #[cfg(target_os = "macos")]
     {
-        // On macOS, use osascript to open Terminal.app
+        // On macOS, use osascript to open Terminal.app or iTerm2
         // Prefix command with unset CLAUDECODE to avoid nested session check
         let full_cmd = format!("unset CLAUDECODE; {cmd}");
-        let script = format!(
-            "tell application \"Terminal\"\n  activate\n  do script \"{}\"\nend tell",
-            full_cmd.replace('\\', "\\\\").replace('"', "\\\"")
-        );
+        let terminal = resolve_mac_terminal(terminal_app, is_iterm_installed());
+        let script = mac_terminal_script(terminal, &full_cmd);
         Command::new("osascript")
             .args(["-e", &script])
             .env_remove("CLAUDECODE")
             .spawn()
-            .map_err(|e| format!("Failed to open terminal: {e}"))?;
+            .map_err(|e| CommandError::io("Failed to open terminal", &e))?;
     }
 
     #[cfg(target_os = "linux")]
@@ -85,7 +187,9 @@ fn open_terminal_with_command(cmd: &str) -> Result<(), String> {
             }
         }
 
-        return Err("No supported terminal emulator found".to_string());
+        return Err(CommandError::NotFound(
+            "No supported terminal emulator found".to_string(),
+        ));
     }
 
     Ok(())
@@ -114,4 +218,84 @@ mod tests {
     fn test_empty_session_id() {
         assert!(!SESSION_ID_REGEX.is_match(""));
     }
+
+    #[test]
+    fn test_cli_arg_validation() {
+        assert!(CLI_ARG_REGEX.is_match("--model"));
+        assert!(CLI_ARG_REGEX.is_match("claude-sonnet-4-5"));
+        assert!(CLI_ARG_REGEX.is_match("--permission-mode=acceptEdits"));
+        assert!(!CLI_ARG_REGEX.is_match(""));
+        assert!(!CLI_ARG_REGEX.is_match("opus; rm -rf /"));
+        assert!(!CLI_ARG_REGEX.is_match("$(id)"));
+        assert!(!CLI_ARG_REGEX.is_match("a|b"));
+        assert!(!CLI_ARG_REGEX.is_match("\"quoted\""));
+    }
+
+    #[test]
+    fn test_resume_command_appends_args() {
+        let args = vec!["--model".to_string(), "opus".to_string()];
+        assert_eq!(
+            resume_command("abc-123", &args),
+            "claude --resume abc-123 --model opus"
+        );
+        assert_eq!(resume_command("abc-123", &[]), "claude --resume abc-123");
+    }
+
+    #[tokio::test]
+    async fn test_resume_session_rejects_unsafe_args() {
+        let result = resume_session(
+            "abc-123".to_string(),
+            None,
+            Some(vec!["--model".to_string(), "opus && curl evil".to_string()]),
+        )
+        .await;
+        assert!(matches!(result, Err(CommandError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_resolve_mac_terminal() {
+        assert_eq!(resolve_mac_terminal(None, true), MacTerminal::ITerm);
+        assert_eq!(resolve_mac_terminal(None, false), MacTerminal::Terminal);
+        assert_eq!(
+            resolve_mac_terminal(Some("Terminal"), true),
+            MacTerminal::Terminal
+        );
+        assert_eq!(
+            resolve_mac_terminal(Some("iTerm2"), true),
+            MacTerminal::ITerm
+        );
+        // Requested but not installed, or unknown: fall back to Terminal.app
+        assert_eq!(
+            resolve_mac_terminal(Some("iTerm"), false),
+            MacTerminal::Terminal
+        );
+        assert_eq!(
+            resolve_mac_terminal(Some("Alacritty"), true),
+            MacTerminal::Terminal
+        );
+    }
+
+    #[test]
+    fn test_escape_applescript_quotes_and_backslashes() {
+        assert_eq!(escape_applescript(r#"say "hi""#), r#"say \"hi\""#);
+        assert_eq!(escape_applescript(r"C:\dir"), r"C:\\dir");
+    }
+
+    #[test]
+    fn test_escape_applescript_shell_expansion() {
+        assert_eq!(escape_applescript("echo $HOME"), r"echo \\$HOME");
+        assert_eq!(escape_applescript("cd $(pwd)"), r"cd \\$(pwd)");
+        assert_eq!(escape_applescript("echo `id`"), r"echo \\`id\\`");
+        assert_eq!(
+            escape_applescript("claude --resume abc-123"),
+            "claude --resume abc-123"
+        );
+    }
+
+    #[test]
+    fn test_mac_terminal_script_iterm() {
+        let script = mac_terminal_script(MacTerminal::ITerm, "echo \"hi\"");
+        assert!(script.starts_with("tell application \"iTerm\""));
+        assert!(script.contains("write text \"echo \\\"hi\\\"\""));
+    }
 }
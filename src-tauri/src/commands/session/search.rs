@@ -1,12 +1,22 @@
 //! Session search functions
+//!
+//! Full-text message search, project-wide search with snippets, and lookup
+//! of the sessions whose tool calls touched a given file.
 
+use super::raw::map_session_file;
+use crate::commands::file_limit::with_file_permit;
+use crate::commands::fs_utils::{projects_dir_of_root, validate_root_dir};
 use crate::models::{ClaudeMessage, RawLogEntry};
-use crate::utils::find_line_ranges;
+use crate::utils::{
+    decode_project_path, find_line_ranges, normalize_path, parse_message_timestamp,
+};
 use chrono::Utc;
+use memchr::memmem;
 use memmap2::Mmap;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 use walkdir::WalkDir;
 
@@ -16,16 +26,122 @@ const PARSE_BUFFER_INITIAL_CAPACITY: usize = 4096;
 /// Initial capacity for search results (most searches find few matches)
 const SEARCH_RESULTS_INITIAL_CAPACITY: usize = 8;
 
-/// Recursively search for a query within a `serde_json::Value`
-/// Returns true if the query is found in any string value.
+/// Part of a message that a search is restricted to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchScope {
+    /// Entire message content (previous behavior)
+    #[default]
+    All,
+    /// Text written by the user (excludes tool results)
+    UserText,
+    /// Text written by the assistant (excludes tool calls)
+    AssistantText,
+    /// Inputs of `tool_use` blocks
+    ToolInput,
+    /// Contents of `tool_result` blocks
+    ToolOutput,
+}
+
+/// Order of global search results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Ranking {
+    /// Newest messages first (previous behavior)
+    #[default]
+    Recency,
+    /// Messages matching the query most often first
+    Relevance,
+    /// Blend of relevance and recency
+    Combined,
+}
+
+/// Weight of relevance in a combined score; recency gets the rest
+const COMBINED_RELEVANCE_WEIGHT: f64 = 0.5;
+
+/// Age at which a hit's recency score halves, relative to the newest hit
+const RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+
+/// A search hit with the score it was ranked by
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedMessage {
+    #[serde(flatten)]
+    pub message: ClaudeMessage,
+    /// Occurrences of the query in the searched content
+    pub match_count: usize,
+    /// Score in `0.0..=1.0` under the requested ranking, higher first
+    pub score: f64,
+}
+
+/// Role filter sent by the frontend in `filters`
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SearchFilters {
+    /// "user", "assistant" or "all"
+    message_type: Option<String>,
+}
+
+impl SearchFilters {
+    /// Parse the frontend's `filters` object; `null` means no filters
+    fn from_value(filters: serde_json::Value) -> Result<Self, String> {
+        if filters.is_null() {
+            return Ok(Self::default());
+        }
+        serde_json::from_value(filters).map_err(|e| format!("Invalid search filters: {e}"))
+    }
+
+    fn matches_role(&self, message_type: &str) -> bool {
+        match self.message_type.as_deref() {
+            None | Some("all") => true,
+            Some(role) => role == message_type,
+        }
+    }
+}
+
+/// Select the parts of a message's content that fall within `scope`
+fn scoped_content<'a>(
+    content: &'a serde_json::Value,
+    message_type: &str,
+    scope: SearchScope,
+) -> Vec<&'a serde_json::Value> {
+    let blocks = |block_type: &'static str, field: &'static str| -> Vec<&'a serde_json::Value> {
+        content
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|item| item.get("type").and_then(serde_json::Value::as_str) == Some(block_type))
+            .filter_map(|item| item.get(field))
+            .collect()
+    };
+    let text_of = |role: &str| -> Vec<&'a serde_json::Value> {
+        if message_type != role {
+            return Vec::new();
+        }
+        match content {
+            serde_json::Value::String(_) => vec![content],
+            _ => blocks("text", "text"),
+        }
+    };
+
+    match scope {
+        SearchScope::All => vec![content],
+        SearchScope::UserText => text_of("user"),
+        SearchScope::AssistantText => text_of("assistant"),
+        SearchScope::ToolInput => blocks("tool_use", "input"),
+        SearchScope::ToolOutput => blocks("tool_result", "content"),
+    }
+}
+
+/// Recursively count occurrences of a query within a `serde_json::Value`
+/// Returns 0 if the query is not found in any string value.
 /// This avoids the expensive JSON serialization that was previously used.
 #[inline]
-fn search_in_value(value: &serde_json::Value, query: &str) -> bool {
+fn count_in_value(value: &serde_json::Value, query: &str) -> usize {
     match value {
-        serde_json::Value::String(s) => s.to_lowercase().contains(query),
-        serde_json::Value::Array(arr) => arr.iter().any(|item| search_in_value(item, query)),
-        serde_json::Value::Object(obj) => obj.values().any(|val| search_in_value(val, query)),
-        _ => false, // Numbers, booleans, null don't contain searchable text
+        serde_json::Value::String(s) => s.to_lowercase().matches(query).count(),
+        serde_json::Value::Array(arr) => arr.iter().map(|item| count_in_value(item, query)).sum(),
+        serde_json::Value::Object(obj) => obj.values().map(|val| count_in_value(val, query)).sum(),
+        _ => 0, // Numbers, booleans, null don't contain searchable text
     }
 }
 
@@ -39,11 +155,17 @@ fn extract_project_name(file_path: &PathBuf) -> Option<String> {
         .map(std::string::ToString::to_string)
 }
 
-/// Search for messages matching the query in a single file
+/// Search for messages matching the query in a single file, with the number
+/// of matches in each
 ///
 /// Uses a reusable buffer to avoid repeated heap allocations during JSON parsing.
 #[allow(unsafe_code)] // Required for mmap performance optimization
-fn search_in_file(file_path: &PathBuf, query: &str) -> Vec<ClaudeMessage> {
+fn search_in_file(
+    file_path: &PathBuf,
+    query: &str,
+    scope: SearchScope,
+    filters: &SearchFilters,
+) -> Vec<(ClaudeMessage, usize)> {
     let query_lower = query.to_lowercase();
     let project_name = extract_project_name(file_path);
 
@@ -81,6 +203,9 @@ fn search_in_file(file_path: &PathBuf, query: &str) -> Vec<ClaudeMessage> {
         if log_entry.message_type != "user" && log_entry.message_type != "assistant" {
             continue;
         }
+        if !filters.matches_role(&log_entry.message_type) {
+            continue;
+        }
 
         let message_content = match &log_entry.message {
             Some(mc) => mc,
@@ -88,15 +213,13 @@ fn search_in_file(file_path: &PathBuf, query: &str) -> Vec<ClaudeMessage> {
         };
 
         // Use recursive search to avoid JSON serialization overhead
-        let matches = match &message_content.content {
-            serde_json::Value::String(s) => s.to_lowercase().contains(&query_lower),
-            serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
-                search_in_value(&message_content.content, &query_lower)
-            }
-            _ => false,
-        };
+        let match_count: usize =
+            scoped_content(&message_content.content, &log_entry.message_type, scope)
+                .into_iter()
+                .map(|value| count_in_value(value, &query_lower))
+                .sum();
 
-        if !matches {
+        if match_count == 0 {
             continue;
         }
 
@@ -138,8 +261,11 @@ fn search_in_file(file_path: &PathBuf, query: &str) -> Vec<ClaudeMessage> {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
-        results.push(claude_message);
+        results.push((claude_message, match_count));
     }
 
     results
@@ -148,16 +274,25 @@ fn search_in_file(file_path: &PathBuf, query: &str) -> Vec<ClaudeMessage> {
 /// Default limit for search results
 const DEFAULT_SEARCH_LIMIT: usize = 100;
 
+/// Search messages across all projects.
+///
+/// `filters.messageType` restricts the role ("user", "assistant" or "all") and
+/// `scope` restricts which part of each message is matched (default: all).
+/// `ranking` orders the hits (default: newest first); each hit carries the
+/// score it was ranked by.
 #[tauri::command]
 pub async fn search_messages(
     claude_path: String,
     query: String,
-    _filters: serde_json::Value,
+    filters: serde_json::Value,
     limit: Option<usize>,
-) -> Result<Vec<ClaudeMessage>, String> {
+    scope: Option<SearchScope>,
+    ranking: Option<Ranking>,
+) -> Result<Vec<RankedMessage>, String> {
     #[cfg(debug_assertions)]
     let start_time = std::time::Instant::now();
 
+    let filters = SearchFilters::from_value(filters)?;
     let max_results = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
     let projects_path = PathBuf::from(&claude_path).join("projects");
 
@@ -165,8 +300,118 @@ pub async fn search_messages(
         return Ok(vec![]);
     }
 
+    let all_messages = search_projects_dir(
+        &projects_path,
+        &query,
+        max_results,
+        scope.unwrap_or_default(),
+        &filters,
+        ranking.unwrap_or_default(),
+    );
+
+    #[cfg(debug_assertions)]
+    {
+        let elapsed = start_time.elapsed();
+        eprintln!(
+            "📊 search_messages performance: {} results (limit: {}), {}ms elapsed",
+            all_messages.len(),
+            max_results,
+            elapsed.as_millis()
+        );
+    }
+
+    Ok(all_messages)
+}
+
+/// Search messages under an explicit data root (e.g. a backup of `~/.claude`)
+/// instead of the default location.
+///
+/// Takes the same `filters`, `scope` and `ranking` as `search_messages` and
+/// returns hits ranked the same way.
+#[tauri::command]
+pub async fn search_messages_in(
+    root: String,
+    query: String,
+    filters: serde_json::Value,
+    limit: Option<usize>,
+    scope: Option<SearchScope>,
+    ranking: Option<Ranking>,
+) -> Result<Vec<RankedMessage>, String> {
+    let root_path = validate_root_dir(&root)?;
+    let filters = SearchFilters::from_value(filters)?;
+
+    Ok(search_projects_dir(
+        &projects_dir_of_root(&root_path),
+        &query,
+        limit.unwrap_or(DEFAULT_SEARCH_LIMIT),
+        scope.unwrap_or_default(),
+        &filters,
+        ranking.unwrap_or_default(),
+    ))
+}
+
+/// Score hits under `ranking` and sort them best first.
+///
+/// Relevance is the log-scaled match count relative to the best hit.
+/// Recency halves every [`RECENCY_HALF_LIFE_DAYS`] of age relative to the
+/// newest hit; hits without a parseable timestamp score 0. Ties are broken
+/// newest first.
+fn rank_hits(hits: Vec<(ClaudeMessage, usize)>, ranking: Ranking) -> Vec<RankedMessage> {
+    let max_matches = hits.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    let times: Vec<_> = hits
+        .iter()
+        .map(|(message, _)| parse_message_timestamp(&message.timestamp))
+        .collect();
+    let newest = times.iter().flatten().max().copied();
+
+    let mut ranked: Vec<RankedMessage> = hits
+        .into_iter()
+        .zip(times)
+        .map(|((message, match_count), time)| {
+            let relevance = if max_matches == 0 {
+                0.0
+            } else {
+                (match_count as f64).ln_1p() / (max_matches as f64).ln_1p()
+            };
+            let recency = time.zip(newest).map_or(0.0, |(time, newest)| {
+                let age_days = (newest - time).num_seconds() as f64 / 86_400.0;
+                0.5_f64.powf(age_days / RECENCY_HALF_LIFE_DAYS)
+            });
+            let score = match ranking {
+                Ranking::Recency => recency,
+                Ranking::Relevance => relevance,
+                Ranking::Combined => {
+                    COMBINED_RELEVANCE_WEIGHT * relevance
+                        + (1.0 - COMBINED_RELEVANCE_WEIGHT) * recency
+                }
+            };
+            RankedMessage {
+                message,
+                match_count,
+                score,
+            }
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| b.message.timestamp.cmp(&a.message.timestamp))
+    });
+    ranked
+}
+
+/// Search all session files below a projects directory, best `ranking` first
+fn search_projects_dir(
+    projects_path: &Path,
+    query: &str,
+    max_results: usize,
+    scope: SearchScope,
+    filters: &SearchFilters,
+    ranking: Ranking,
+) -> Vec<RankedMessage> {
     // 1. Collect all JSONL file paths
-    let file_paths: Vec<PathBuf> = WalkDir::new(&projects_path)
+    let file_paths: Vec<PathBuf> = WalkDir::new(projects_path)
         .into_iter()
         .filter_map(std::result::Result::ok)
         .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
@@ -177,27 +422,471 @@ pub async fn search_messages(
     eprintln!("🔍 search_messages: searching {} files", file_paths.len());
 
     // 2. Parallel search using rayon
-    let mut all_messages: Vec<ClaudeMessage> = file_paths
+    let hits: Vec<(ClaudeMessage, usize)> = file_paths
         .par_iter()
-        .flat_map(|path| search_in_file(path, &query))
+        .flat_map(|path| with_file_permit(|| search_in_file(path, query, scope, filters)))
         .collect();
 
-    // 3. Sort by timestamp descending and truncate to limit
-    all_messages.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    // 3. Sort by ranking score and truncate to limit
+    let mut all_messages = rank_hits(hits, ranking);
     all_messages.truncate(max_results);
 
-    #[cfg(debug_assertions)]
-    {
-        let elapsed = start_time.elapsed();
-        eprintln!(
-            "📊 search_messages performance: {} results (limit: {}), {}ms elapsed",
-            all_messages.len(),
-            max_results,
-            elapsed.as_millis()
-        );
+    all_messages
+}
+
+/// Characters of context kept on each side of the first match in a snippet
+const SNIPPET_CONTEXT_CHARS: usize = 80;
+
+/// Content fields that hold identifiers rather than searchable text
+const NON_TEXT_FIELDS: [&str; 4] = ["type", "id", "tool_use_id", "signature"];
+
+/// A search hit inside a project, with highlight offsets into its snippet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSearchHit {
+    pub session_id: String,
+    pub file_path: String,
+    /// 0-based line index of the message (same indexing as `get_raw_line`)
+    pub line_index: usize,
+    pub uuid: Option<String>,
+    pub timestamp: String,
+    pub message_type: String,
+    pub snippet: String,
+    /// `(start, end)` char offsets of each match inside `snippet`
+    pub highlights: Vec<(usize, usize)>,
+}
+
+/// Options for project-wide search
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProjectSearchOptions {
+    pub case_sensitive: bool,
+    /// Maximum number of hits (capped at the live search limit)
+    pub limit: Option<usize>,
+    /// Part of each message to match
+    pub scope: SearchScope,
+}
+
+/// Collect the searchable text of a message content value
+fn collect_content_text<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(s) => out.push(s),
+        serde_json::Value::Array(arr) => {
+            for item in arr {
+                collect_content_text(item, out);
+            }
+        }
+        serde_json::Value::Object(obj) => {
+            for (key, val) in obj {
+                if !NON_TEXT_FIELDS.contains(&key.as_str()) {
+                    collect_content_text(val, out);
+                }
+            }
+        }
+        _ => {}
     }
+}
 
-    Ok(all_messages)
+fn chars_match(a: char, b: char, case_sensitive: bool) -> bool {
+    if case_sensitive {
+        a == b
+    } else {
+        a == b || a.to_lowercase().eq(b.to_lowercase())
+    }
+}
+
+/// Find non-overlapping matches of `query` in `text` as `(start, end)` char offsets
+pub(crate) fn find_highlight_offsets(
+    text: &str,
+    query: &str,
+    case_sensitive: bool,
+) -> Vec<(usize, usize)> {
+    let text_chars: Vec<char> = text.chars().collect();
+    let query_chars: Vec<char> = query.chars().collect();
+    let mut offsets = Vec::new();
+
+    if query_chars.is_empty() || query_chars.len() > text_chars.len() {
+        return offsets;
+    }
+
+    let mut i = 0;
+    while i + query_chars.len() <= text_chars.len() {
+        let matched = query_chars
+            .iter()
+            .enumerate()
+            .all(|(j, &q)| chars_match(text_chars[i + j], q, case_sensitive));
+        if matched {
+            offsets.push((i, i + query_chars.len()));
+            i += query_chars.len();
+        } else {
+            i += 1;
+        }
+    }
+
+    offsets
+}
+
+/// Cut a snippet around the first match and re-base highlight offsets onto it
+fn build_snippet(text: &str, offsets: &[(usize, usize)]) -> (String, Vec<(usize, usize)>) {
+    let Some(&(first_start, _)) = offsets.first() else {
+        return (String::new(), Vec::new());
+    };
+
+    let char_count = text.chars().count();
+    let start = first_start.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (first_start + SNIPPET_CONTEXT_CHARS).min(char_count);
+    let end = offsets
+        .iter()
+        .find(|(s, _)| *s == first_start)
+        .map_or(end, |(_, e)| end.max(*e));
+
+    let snippet: String = text.chars().skip(start).take(end - start).collect();
+    let highlights = offsets
+        .iter()
+        .filter(|(s, e)| *s >= start && *e <= end)
+        .map(|(s, e)| (s - start, e - start))
+        .collect();
+
+    (snippet, highlights)
+}
+
+/// Search a single session file, returning hits with snippets
+fn search_hits_in_file(
+    file_path: &Path,
+    query: &str,
+    options: &ProjectSearchOptions,
+) -> Vec<ProjectSearchHit> {
+    let Ok(mmap) = map_session_file(&file_path.to_string_lossy()) else {
+        return Vec::new();
+    };
+
+    let fallback_session_id = file_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut hits = Vec::new();
+    let mut parse_buffer = Vec::with_capacity(PARSE_BUFFER_INITIAL_CAPACITY);
+
+    for (line_index, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        parse_buffer.clear();
+        parse_buffer.extend_from_slice(&mmap[start..end]);
+
+        let Ok(log_entry) = simd_json::serde::from_slice::<RawLogEntry>(&mut parse_buffer) else {
+            continue;
+        };
+        if log_entry.message_type != "user" && log_entry.message_type != "assistant" {
+            continue;
+        }
+        let Some(message) = &log_entry.message else {
+            continue;
+        };
+
+        let mut parts = Vec::new();
+        for value in scoped_content(&message.content, &log_entry.message_type, options.scope) {
+            collect_content_text(value, &mut parts);
+        }
+        let text = parts.join("\n");
+
+        let offsets = find_highlight_offsets(&text, query, options.case_sensitive);
+        if offsets.is_empty() {
+            continue;
+        }
+        let (snippet, highlights) = build_snippet(&text, &offsets);
+
+        hits.push(ProjectSearchHit {
+            session_id: log_entry
+                .session_id
+                .clone()
+                .unwrap_or_else(|| fallback_session_id.clone()),
+            file_path: file_path.to_string_lossy().to_string(),
+            line_index,
+            uuid: log_entry.uuid.clone(),
+            timestamp: log_entry.timestamp.clone().unwrap_or_default(),
+            message_type: log_entry.message_type.clone(),
+            snippet,
+            highlights,
+        });
+    }
+
+    hits
+}
+
+/// Search all sessions of a project, newest hits first
+pub(crate) fn search_project_hits(
+    project_path: &Path,
+    query: &str,
+    options: &ProjectSearchOptions,
+) -> Vec<ProjectSearchHit> {
+    let max_results = options
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .min(DEFAULT_SEARCH_LIMIT);
+
+    let file_paths: Vec<PathBuf> = WalkDir::new(project_path)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let mut hits: Vec<ProjectSearchHit> = file_paths
+        .par_iter()
+        .flat_map(|path| with_file_permit(|| search_hits_in_file(path, query, options)))
+        .collect();
+
+    hits.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    hits.truncate(max_results);
+    hits
+}
+
+/// Escape a CSV field (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Wrap highlighted ranges of a snippet in Markdown bold
+fn highlight_markdown(snippet: &str, highlights: &[(usize, usize)]) -> String {
+    let mut out = String::with_capacity(snippet.len() + highlights.len() * 4);
+    for (i, c) in snippet.chars().enumerate() {
+        if highlights.iter().any(|(s, _)| *s == i) {
+            out.push_str("**");
+        }
+        out.push(c);
+        if highlights.iter().any(|(_, e)| *e == i + 1) {
+            out.push_str("**");
+        }
+    }
+    out
+}
+
+/// Editor-friendly link to the message line (1-based)
+fn hit_link(hit: &ProjectSearchHit) -> String {
+    format!("{}:{}", hit.file_path, hit.line_index + 1)
+}
+
+fn render_search_markdown(project_name: &str, query: &str, hits: &[ProjectSearchHit]) -> String {
+    let mut out = format!(
+        "# Search results: \"{query}\"\n\nProject: {project_name}\n\n{} hit(s)\n",
+        hits.len()
+    );
+    for hit in hits {
+        let snippet = highlight_markdown(&hit.snippet, &hit.highlights).replace('\n', " ");
+        out.push_str(&format!(
+            "\n## {} · {} ({})\n\n> {snippet}\n\n`{}`\n",
+            hit.timestamp,
+            hit.session_id,
+            hit.message_type,
+            hit_link(hit)
+        ));
+    }
+    out
+}
+
+fn render_search_csv(project_name: &str, hits: &[ProjectSearchHit]) -> String {
+    let mut out = String::from("project,session_id,timestamp,message_type,snippet,link\n");
+    for hit in hits {
+        let row = [
+            project_name,
+            &hit.session_id,
+            &hit.timestamp,
+            &hit.message_type,
+            &hit.snippet,
+            &hit_link(hit),
+        ]
+        .iter()
+        .map(|v| csv_field(v))
+        .collect::<Vec<_>>()
+        .join(",");
+        out.push_str(&row);
+        out.push('\n');
+    }
+    out
+}
+
+/// Run a project-wide search and render the hits as Markdown or CSV.
+///
+/// # Arguments
+/// * `project_path` - Project storage directory
+/// * `query` - Text to search for
+/// * `options` - Case sensitivity and result limit (capped at the live search limit)
+/// * `format` - `"markdown"` or `"csv"`
+#[tauri::command]
+pub async fn export_search_results(
+    project_path: String,
+    query: String,
+    options: Option<ProjectSearchOptions>,
+    format: String,
+) -> Result<String, String> {
+    if query.trim().is_empty() {
+        return Err("Search query is required".to_string());
+    }
+    let project_dir = PathBuf::from(&project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Project directory not found: {project_path}"));
+    }
+
+    let options = options.unwrap_or_default();
+    let hits = search_project_hits(&project_dir, &query, &options);
+    let project_name = decode_project_path(&project_path);
+
+    match format.to_lowercase().as_str() {
+        "markdown" | "md" => Ok(render_search_markdown(&project_name, &query, &hits)),
+        "csv" => Ok(render_search_csv(&project_name, &hits)),
+        other => Err(format!("Unsupported export format: {other}")),
+    }
+}
+
+/// Tool input fields naming the file a tool call reads or writes
+const TOOL_PATH_FIELDS: [&str; 3] = ["file_path", "notebook_path", "path"];
+
+/// A tool call that read or edited a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileOperation {
+    /// 0-based line index of the assistant message
+    pub line_index: usize,
+    /// Tool name (`Read`, `Edit`, `Write`, ...)
+    pub tool: String,
+    pub timestamp: Option<String>,
+}
+
+/// A session whose tool calls touched a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRef {
+    pub session_id: String,
+    pub file_path: String,
+    /// Claude session storage directory of the project
+    pub project_path: String,
+    /// Decoded project directory
+    pub project_actual_path: String,
+    /// Operations in file order
+    pub operations: Vec<FileOperation>,
+    pub last_timestamp: Option<String>,
+}
+
+/// Find the tool calls in one session file that touch `target`.
+///
+/// Only lines containing the target's file name are parsed. Relative tool
+/// paths are resolved against the message's `cwd`.
+fn file_operations_in(session_path: &Path, target: &Path) -> Option<SessionRef> {
+    let file_name = target.file_name()?.to_string_lossy().to_string();
+    let mmap = map_session_file(&session_path.to_string_lossy()).ok()?;
+    let finder = memmem::Finder::new(file_name.as_bytes());
+
+    let mut operations = Vec::new();
+    for (line_index, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        let line = &mmap[start..end];
+        if finder.find(line).is_none() {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_slice::<serde_json::Value>(line) else {
+            continue;
+        };
+        let cwd = entry.get("cwd").and_then(serde_json::Value::as_str);
+        let blocks = entry
+            .pointer("/message/content")
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|b| b.get("type").and_then(serde_json::Value::as_str) == Some("tool_use"));
+
+        for block in blocks {
+            let Some(input) = block.get("input") else {
+                continue;
+            };
+            let touches_target = TOOL_PATH_FIELDS
+                .iter()
+                .filter_map(|field| input.get(*field).and_then(serde_json::Value::as_str))
+                .filter_map(|path| match cwd {
+                    Some(cwd) => normalize_path(&Path::new(cwd).join(path)),
+                    None => normalize_path(Path::new(path)),
+                })
+                .any(|resolved| resolved == target);
+            if touches_target {
+                operations.push(FileOperation {
+                    line_index,
+                    tool: block
+                        .get("name")
+                        .and_then(serde_json::Value::as_str)
+                        .unwrap_or_default()
+                        .to_string(),
+                    timestamp: entry
+                        .get("timestamp")
+                        .and_then(serde_json::Value::as_str)
+                        .map(str::to_string),
+                });
+            }
+        }
+    }
+
+    if operations.is_empty() {
+        return None;
+    }
+
+    let project_path = session_path
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Some(SessionRef {
+        session_id: session_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        file_path: session_path.to_string_lossy().to_string(),
+        project_actual_path: decode_project_path(&project_path),
+        project_path,
+        last_timestamp: operations
+            .iter()
+            .filter_map(|op| op.timestamp.clone())
+            .max(),
+        operations,
+    })
+}
+
+/// Find every session whose tool calls read or edited a file.
+///
+/// # Arguments
+/// * `claude_path` - Claude data directory containing `projects/`
+/// * `absolute_path` - The file to look for
+///
+/// Tool paths (`file_path`, `notebook_path`, `path`) are compared after
+/// lexical normalization; relative ones are resolved against the message's
+/// working directory. Sessions are returned most recently touched first,
+/// each with the operations performed on the file.
+#[tauri::command]
+pub async fn sessions_referencing_file(
+    claude_path: String,
+    absolute_path: String,
+) -> Result<Vec<SessionRef>, String> {
+    let target_path = Path::new(&absolute_path);
+    if !target_path.is_absolute() {
+        return Err("absolute_path must be absolute".to_string());
+    }
+    let target = normalize_path(target_path)
+        .filter(|t| t.file_name().is_some())
+        .ok_or_else(|| format!("Invalid file path: {absolute_path}"))?;
+
+    let projects_path = PathBuf::from(&claude_path).join("projects");
+    if !projects_path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let file_paths: Vec<PathBuf> = WalkDir::new(&projects_path)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let mut sessions: Vec<SessionRef> = file_paths
+        .par_iter()
+        .filter_map(|path| with_file_permit(|| file_operations_in(path, &target)))
+        .collect();
+    sessions.sort_by(|a, b| b.last_timestamp.cmp(&a.last_timestamp));
+    Ok(sessions)
 }
 
 #[cfg(test)]
@@ -242,6 +931,8 @@ mod tests {
             "Rust".to_string(),
             serde_json::json!({}),
             None,
+            None,
+            None,
         )
         .await;
 
@@ -271,6 +962,8 @@ mod tests {
             "hello".to_string(), // lowercase
             serde_json::json!({}),
             None,
+            None,
+            None,
         )
         .await;
 
@@ -300,6 +993,8 @@ mod tests {
             "nonexistent".to_string(),
             serde_json::json!({}),
             None,
+            None,
+            None,
         )
         .await;
 
@@ -317,10 +1012,283 @@ mod tests {
             "test".to_string(),
             serde_json::json!({}),
             None,
+            None,
+            None,
         )
         .await;
 
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
     }
+
+    #[tokio::test]
+    async fn test_search_messages_ranking() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("projects").join("test-project");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let content = [
+            r#"{"uuid":"old","sessionId":"s","timestamp":"2025-01-01T00:00:00Z","type":"user","message":{"role":"user","content":"rust, rust and more rust"}}"#,
+            r#"{"uuid":"new","sessionId":"s","timestamp":"2025-06-01T00:00:00Z","type":"user","message":{"role":"user","content":"a rust question"}}"#,
+        ]
+        .join("\n");
+        std::fs::write(project_dir.join("test.jsonl"), content).unwrap();
+        let claude_path = temp_dir.path().to_string_lossy().to_string();
+
+        let search = |ranking: Option<Ranking>| {
+            search_messages(
+                claude_path.clone(),
+                "rust".to_string(),
+                serde_json::json!({}),
+                None,
+                None,
+                ranking,
+            )
+        };
+        let order = |hits: &[RankedMessage]| -> Vec<String> {
+            hits.iter().map(|h| h.message.uuid.clone()).collect()
+        };
+
+        let recency = search(None).await.unwrap();
+        assert_eq!(order(&recency), vec!["new", "old"]);
+        assert!((recency[0].score - 1.0).abs() < f64::EPSILON);
+
+        let relevance = search(Some(Ranking::Relevance)).await.unwrap();
+        assert_eq!(order(&relevance), vec!["old", "new"]);
+        assert_eq!(relevance[0].match_count, 3);
+
+        let combined = search(Some(Ranking::Combined)).await.unwrap();
+        assert!(combined.iter().all(|h| (0.0..=1.0).contains(&h.score)));
+        assert!(combined[0].score >= combined[1].score);
+    }
+
+    #[tokio::test]
+    async fn test_search_messages_in_applies_filters_and_ranking() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("projects").join("test-project");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let content = [
+            r#"{"uuid":"old","sessionId":"s","timestamp":"2025-01-01T00:00:00Z","type":"user","message":{"role":"user","content":"rust, rust and more rust"}}"#,
+            r#"{"uuid":"new","sessionId":"s","timestamp":"2025-06-01T00:00:00Z","type":"user","message":{"role":"user","content":"a rust question"}}"#,
+            r#"{"uuid":"reply","sessionId":"s","timestamp":"2025-06-02T00:00:00Z","type":"assistant","message":{"role":"assistant","content":"rust rust"}}"#,
+        ]
+        .join("\n");
+        std::fs::write(project_dir.join("test.jsonl"), content).unwrap();
+
+        let hits = search_messages_in(
+            temp_dir.path().to_string_lossy().to_string(),
+            "rust".to_string(),
+            serde_json::json!({ "messageType": "user" }),
+            None,
+            None,
+            Some(Ranking::Relevance),
+        )
+        .await
+        .unwrap();
+        let order: Vec<&str> = hits.iter().map(|h| h.message.uuid.as_str()).collect();
+        assert_eq!(order, vec!["old", "new"]);
+        assert_eq!(hits[0].match_count, 3);
+
+        // Malformed filters are reported instead of silently ignored
+        let err = search_messages_in(
+            temp_dir.path().to_string_lossy().to_string(),
+            "rust".to_string(),
+            serde_json::json!({ "messageType": 1 }),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("Invalid search filters"));
+        let err = search_messages(
+            temp_dir.path().to_string_lossy().to_string(),
+            "rust".to_string(),
+            serde_json::json!("user"),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("Invalid search filters"));
+    }
+
+    #[test]
+    fn test_find_highlight_offsets() {
+        assert_eq!(
+            find_highlight_offsets("Rust and rust and RUST", "rust", false),
+            vec![(0, 4), (9, 13), (18, 22)]
+        );
+        assert_eq!(
+            find_highlight_offsets("Rust and rust", "rust", true),
+            vec![(9, 13)]
+        );
+        // Offsets are in chars, not bytes
+        assert_eq!(
+            find_highlight_offsets("한글 rust", "rust", false),
+            vec![(3, 7)]
+        );
+        assert!(find_highlight_offsets("abc", "", false).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_export_search_results_markdown_and_csv() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("test-project");
+        std::fs::create_dir_all(&project_dir).unwrap();
+
+        let content = format!(
+            "{}\n{}\n",
+            create_sample_user_message("uuid-1", "session-1", "How do I use Rust, again?"),
+            create_sample_assistant_message("uuid-2", "session-1", "Nothing relevant")
+        );
+        std::fs::write(project_dir.join("session-1.jsonl"), content).unwrap();
+        let project_path = project_dir.to_string_lossy().to_string();
+
+        let markdown = export_search_results(
+            project_path.clone(),
+            "rust".to_string(),
+            None,
+            "markdown".to_string(),
+        )
+        .await
+        .unwrap();
+        assert!(markdown.contains("1 hit(s)"));
+        assert!(markdown.contains("How do I use **Rust**, again?"));
+        assert!(markdown.contains("session-1.jsonl:1"));
+
+        let csv = export_search_results(
+            project_path.clone(),
+            "rust".to_string(),
+            None,
+            "csv".to_string(),
+        )
+        .await
+        .unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[1].contains("\"How do I use Rust, again?\""));
+
+        let result =
+            export_search_results(project_path, "rust".to_string(), None, "pdf".to_string()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_messages_scope_and_role() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("projects").join("test-project");
+        std::fs::create_dir_all(&project_dir).unwrap();
+
+        let content = [
+            create_sample_user_message("uuid-1", "session-1", "Why does the build panic"),
+            r#"{"uuid":"uuid-2","sessionId":"session-1","timestamp":"2025-06-26T10:01:00Z","type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Checking the build"},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo build"}}]}}"#.to_string(),
+            r#"{"uuid":"uuid-3","sessionId":"session-1","timestamp":"2025-06-26T10:02:00Z","type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"thread 'main' panicked at build.rs"}]}}"#.to_string(),
+        ]
+        .join("\n");
+        std::fs::write(project_dir.join("test.jsonl"), content).unwrap();
+        let claude_path = temp_dir.path().to_string_lossy().to_string();
+
+        let search = |query: &str, filters: serde_json::Value, scope: Option<SearchScope>| {
+            search_messages(
+                claude_path.clone(),
+                query.to_string(),
+                filters,
+                None,
+                scope,
+                None,
+            )
+        };
+        let uuids = |messages: Vec<RankedMessage>| -> Vec<String> {
+            let mut uuids: Vec<String> = messages.into_iter().map(|m| m.message.uuid).collect();
+            uuids.sort();
+            uuids
+        };
+
+        let all = search("panic", serde_json::json!({}), None).await.unwrap();
+        assert_eq!(uuids(all), vec!["uuid-1", "uuid-3"]);
+
+        let output = search(
+            "panic",
+            serde_json::json!({}),
+            Some(SearchScope::ToolOutput),
+        )
+        .await
+        .unwrap();
+        assert_eq!(uuids(output), vec!["uuid-3"]);
+
+        let user_text = search("panic", serde_json::json!({}), Some(SearchScope::UserText))
+            .await
+            .unwrap();
+        assert_eq!(uuids(user_text), vec!["uuid-1"]);
+
+        let input = search("cargo", serde_json::json!({}), Some(SearchScope::ToolInput))
+            .await
+            .unwrap();
+        assert_eq!(uuids(input), vec!["uuid-2"]);
+
+        let assistant_build = search(
+            "build",
+            serde_json::json!({"messageType": "assistant"}),
+            Some(SearchScope::AssistantText),
+        )
+        .await
+        .unwrap();
+        assert_eq!(uuids(assistant_build), vec!["uuid-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_referencing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("projects").join("-app");
+        fs::create_dir_all(&project).unwrap();
+        let tool_use = |ts: &str, name: &str, path: &str| {
+            format!(
+                r#"{{"type":"assistant","cwd":"/app","timestamp":"{ts}","message":{{"role":"assistant","content":[{{"type":"tool_use","id":"t","name":"{name}","input":{{"file_path":"{path}"}}}}]}}}}"#
+            )
+        };
+        fs::write(
+            project.join("old.jsonl"),
+            [
+                tool_use("2025-01-01T10:00:00Z", "Read", "/app/src/main.rs"),
+                tool_use("2025-01-01T10:01:00Z", "Edit", "./src/../src/main.rs"),
+                tool_use("2025-01-01T10:02:00Z", "Read", "/app/other/main.rs"),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        fs::write(
+            project.join("new.jsonl"),
+            tool_use("2025-02-01T10:00:00Z", "Write", "/app/src/main.rs"),
+        )
+        .unwrap();
+        fs::write(
+            project.join("unrelated.jsonl"),
+            tool_use("2025-03-01T10:00:00Z", "Write", "/app/src/lib.rs"),
+        )
+        .unwrap();
+
+        let sessions = sessions_referencing_file(
+            temp_dir.path().to_string_lossy().to_string(),
+            "/app/src/main.rs".to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].session_id, "new");
+        assert_eq!(sessions[1].session_id, "old");
+        let tools: Vec<(usize, &str)> = sessions[1]
+            .operations
+            .iter()
+            .map(|op| (op.line_index, op.tool.as_str()))
+            .collect();
+        assert_eq!(tools, vec![(0, "Read"), (1, "Edit")]);
+
+        assert!(
+            sessions_referencing_file(String::new(), "src/main.rs".to_string())
+                .await
+                .is_err()
+        );
+    }
 }
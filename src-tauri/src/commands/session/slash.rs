@@ -0,0 +1,252 @@
+//! Slash command invocations
+//!
+//! Claude records a slash command as a user message wrapping the command in
+//! `<command-name>` / `<command-args>` tags. Listing them shows how the user
+//! steered a session (`/clear`, `/compact`, custom commands, MCP prompts).
+
+use super::raw::map_session_file;
+use super::summary::parse_conversation_entry;
+use crate::utils::find_line_ranges;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::command;
+
+/// Commands shipped with the Claude CLI
+const BUILT_IN_COMMANDS: &[&str] = &[
+    "add-dir",
+    "agents",
+    "bashes",
+    "bug",
+    "clear",
+    "compact",
+    "config",
+    "context",
+    "cost",
+    "doctor",
+    "exit",
+    "export",
+    "help",
+    "hooks",
+    "ide",
+    "init",
+    "install-github-app",
+    "login",
+    "logout",
+    "mcp",
+    "memory",
+    "migrate-installer",
+    "model",
+    "output-style",
+    "permissions",
+    "plugin",
+    "pr-comments",
+    "privacy-settings",
+    "release-notes",
+    "resume",
+    "review",
+    "rewind",
+    "sandbox",
+    "security-review",
+    "status",
+    "statusline",
+    "terminal-setup",
+    "todos",
+    "upgrade",
+    "usage",
+    "vim",
+];
+
+/// Name prefix of prompts exposed by MCP servers (`/mcp__<server>__<prompt>`)
+const MCP_PROMPT_PREFIX: &str = "mcp__";
+
+/// Where a slash command comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SlashCommandKind {
+    /// Shipped with the CLI
+    BuiltIn,
+    /// Prompt provided by an MCP server
+    Mcp,
+    /// User, project or plugin command (anything not recognized as built-in)
+    Custom,
+}
+
+/// A slash command the user ran
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlashCommand {
+    /// 0-based line index of the user message
+    pub line_index: usize,
+    pub uuid: Option<String>,
+    pub timestamp: Option<String>,
+    /// Command name without the leading slash
+    pub name: String,
+    /// Arguments after the name, empty when none were given
+    pub args: String,
+    pub kind: SlashCommandKind,
+    /// Whether Claude tagged the message as a command; false when only the
+    /// leading slash of plain text identified it
+    pub tagged: bool,
+}
+
+/// Trimmed text between `<tag>` and `</tag>`
+fn tag_text<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = text.find(&open)? + open.len();
+    let end = text[start..].find(&close)? + start;
+    Some(text[start..end].trim())
+}
+
+/// Whether `name` is usable as a command name (`clear`, `frontend:review`)
+fn is_command_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+}
+
+fn classify(name: &str) -> SlashCommandKind {
+    if BUILT_IN_COMMANDS.contains(&name) {
+        SlashCommandKind::BuiltIn
+    } else if name.starts_with(MCP_PROMPT_PREFIX) {
+        SlashCommandKind::Mcp
+    } else {
+        SlashCommandKind::Custom
+    }
+}
+
+/// Parse a command out of user message text as `(name, args, tagged)`.
+///
+/// Tagged messages are read from their `<command-name>` and `<command-args>`
+/// tags. Otherwise the text must start with `/name`, where the name has no
+/// further slash, so file paths like `/usr/bin` are not taken for commands.
+fn parse_slash_command(text: &str) -> Option<(String, String, bool)> {
+    if let Some(tagged_name) = tag_text(text, "command-name") {
+        let name = tagged_name.strip_prefix('/').unwrap_or(tagged_name);
+        if !name.is_empty() {
+            let args = tag_text(text, "command-args").unwrap_or_default();
+            return Some((name.to_string(), args.to_string(), true));
+        }
+    }
+
+    let rest = text.trim_start().strip_prefix('/')?;
+    let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    is_command_name(name).then(|| (name.to_string(), args.trim().to_string(), false))
+}
+
+/// First text of a message content (plain string or first text block)
+fn content_text(content: &Value) -> Option<&str> {
+    match content {
+        Value::String(text) => Some(text),
+        Value::Array(blocks) => blocks
+            .iter()
+            .find(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+            .and_then(|block| block.get("text"))
+            .and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// List the slash commands run in a session, in session order.
+///
+/// Commands are read from the tags Claude wraps them in, falling back to
+/// user text starting with `/name`. Each is classified as built-in, MCP
+/// prompt or custom; built-ins are matched against the commands the CLI
+/// ships with, so a built-in newer than that list reports as custom.
+/// Meta lines (command output, expanded prompts) are skipped.
+#[command]
+pub async fn list_slash_commands(file_path: String) -> Result<Vec<SlashCommand>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let mut commands = Vec::new();
+
+    for (line_index, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        let Some(entry) = parse_conversation_entry(&mmap[start..end]) else {
+            continue;
+        };
+        if entry.message_type != "user" {
+            continue;
+        }
+        let Some((name, args, tagged)) = entry
+            .message
+            .as_ref()
+            .and_then(|message| content_text(&message.content))
+            .and_then(parse_slash_command)
+        else {
+            continue;
+        };
+
+        commands.push(SlashCommand {
+            line_index,
+            uuid: entry.uuid,
+            timestamp: entry.timestamp,
+            kind: classify(&name),
+            name,
+            args,
+            tagged,
+        });
+    }
+
+    Ok(commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_slash_command() {
+        assert_eq!(
+            parse_slash_command(
+                "<command-message>review is running…</command-message>\n<command-name>/review</command-name>\n<command-args>src/lib.rs</command-args>"
+            ),
+            Some(("review".to_string(), "src/lib.rs".to_string(), true))
+        );
+        assert_eq!(
+            parse_slash_command("/deploy  staging now"),
+            Some(("deploy".to_string(), "staging now".to_string(), false))
+        );
+        assert_eq!(parse_slash_command("/usr/bin/env is missing"), None);
+        assert_eq!(parse_slash_command("fix the /clear handler"), None);
+    }
+
+    #[tokio::test]
+    async fn test_list_slash_commands_classifies() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let lines = [
+            r#"{"type":"user","uuid":"u1","message":{"role":"user","content":"<command-name>/clear</command-name>\n<command-message>clear</command-message>\n<command-args></command-args>"}}"#,
+            r#"{"type":"user","uuid":"u2","isMeta":true,"message":{"role":"user","content":"/not-a-command expanded prompt"}}"#,
+            r#"{"type":"assistant","uuid":"u3","message":{"role":"assistant","content":"/clear"}}"#,
+            r#"{"type":"user","uuid":"u4","message":{"role":"user","content":[{"type":"text","text":"<command-name>/mcp__github__triage</command-name>\n<command-args>42</command-args>"}]}}"#,
+            r#"{"type":"user","uuid":"u5","message":{"role":"user","content":"/frontend:lint --fix"}}"#,
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let commands = list_slash_commands(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        let summary: Vec<(usize, &str, &str, SlashCommandKind, bool)> = commands
+            .iter()
+            .map(|c| {
+                (
+                    c.line_index,
+                    c.name.as_str(),
+                    c.args.as_str(),
+                    c.kind,
+                    c.tagged,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, "clear", "", SlashCommandKind::BuiltIn, true),
+                (3, "mcp__github__triage", "42", SlashCommandKind::Mcp, true),
+                (4, "frontend:lint", "--fix", SlashCommandKind::Custom, false),
+            ]
+        );
+        assert_eq!(commands[0].uuid.as_deref(), Some("u1"));
+    }
+}
@@ -0,0 +1,491 @@
+//! Session title and preview derivation
+//!
+//! Builds human-readable labels for the session list from the conversation
+//! itself, parsing only as many lines as needed. Also finds the largest
+//! lines of a session with a preview of what they contain, and lists a
+//! project's sessions with their titles in one batch.
+
+use super::load::{is_genuine_user_text, is_system_message_type, truncate_text};
+use super::raw::map_session_file;
+use super::timing::compute_session_time_bounds;
+use crate::commands::file_limit::with_file_permit;
+use crate::commands::metadata::MetadataState;
+use crate::models::{RawLogEntry, SessionWithTitle, UserMetadata};
+use crate::utils::{estimate_message_count_from_size, find_line_ranges};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use tauri::{command, State};
+use walkdir::WalkDir;
+
+/// Maximum characters in a derived session title
+const TITLE_MAX_CHARS: usize = 80;
+
+/// Maximum characters per line of a session preview
+const PREVIEW_MAX_CHARS: usize = 200;
+
+/// Two-line session preview: what was asked and what happened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPreview {
+    pub first_user_message: Option<String>,
+    pub last_assistant_message: Option<String>,
+}
+
+/// A single session line ranked by size
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageSizeInfo {
+    /// 0-based index among non-empty lines
+    pub line_index: usize,
+    pub bytes: usize,
+    /// Entry `type` (`user`, `assistant`, `file-history-snapshot`, ...)
+    pub message_type: Option<String>,
+    /// Message role, None for non-message entries
+    pub role: Option<String>,
+    /// Start of the first text or tool output, whitespace collapsed
+    pub preview: Option<String>,
+}
+
+/// Parse a raw line, skipping meta and system bookkeeping entries
+pub(crate) fn parse_conversation_entry(line: &[u8]) -> Option<RawLogEntry> {
+    // simd-json requires mutable slice
+    let mut line_bytes = line.to_vec();
+    let entry: RawLogEntry = simd_json::serde::from_slice(&mut line_bytes).ok()?;
+
+    if entry.is_meta.unwrap_or(false) || is_system_message_type(&entry.message_type) {
+        return None;
+    }
+    Some(entry)
+}
+
+/// Collapse newlines and runs of whitespace into single spaces
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Find the first text in message content.
+/// With `genuine_only`, system-like user text (tags, caveats) is skipped.
+fn first_content_text(content: &Value, genuine_only: bool) -> Option<String> {
+    let accept = |text: &str| {
+        if genuine_only {
+            is_genuine_user_text(text)
+        } else {
+            !text.trim().is_empty()
+        }
+    };
+
+    match content {
+        Value::String(text) if accept(text) => Some(text.clone()),
+        Value::Array(items) => items.iter().find_map(|item| {
+            let text = match item.get("type").and_then(Value::as_str) {
+                Some("text") => item.get("text").and_then(Value::as_str),
+                Some("tool_result") if !genuine_only => item.get("content").and_then(Value::as_str),
+                _ => None,
+            }?;
+            accept(text).then(|| text.to_string())
+        }),
+        _ => None,
+    }
+}
+
+/// Derive a single-line title from session content
+pub(crate) fn derive_title_from_file(file_path: &str) -> Result<String, String> {
+    let mmap = map_session_file(file_path)?;
+    let mut fallback: Option<String> = None;
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let Some(entry) = parse_conversation_entry(&mmap[start..end]) else {
+            continue;
+        };
+        let Some(message) = entry.message.as_ref() else {
+            continue;
+        };
+
+        if entry.message_type == "user" {
+            if let Some(text) = first_content_text(&message.content, true) {
+                return Ok(truncate_text(&collapse_whitespace(&text), TITLE_MAX_CHARS));
+            }
+        }
+
+        if fallback.is_none() {
+            fallback = first_content_text(&message.content, false);
+        }
+    }
+
+    // Tool-only start: use the first non-empty content, then the session id
+    Ok(fallback.map_or_else(
+        || {
+            Path::new(file_path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        },
+        |text| truncate_text(&collapse_whitespace(&text), TITLE_MAX_CHARS),
+    ))
+}
+
+/// Derive a human title from the first user prompt of a session.
+///
+/// Returns the first ~80 characters with newlines collapsed. Parsing stops at
+/// the first user message with real text; system/meta lines are skipped.
+#[command]
+pub async fn derive_session_title(file_path: String) -> Result<String, String> {
+    derive_title_from_file(&file_path)
+}
+
+/// Title, activity and metadata of every session in a project, most recent first
+fn sessions_with_titles(
+    project_path: &str,
+    metadata: Option<&UserMetadata>,
+) -> Result<Vec<SessionWithTitle>, String> {
+    let project_dir = Path::new(project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Project directory not found: {project_path}"));
+    }
+
+    let file_paths: Vec<PathBuf> = WalkDir::new(project_dir)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .filter(|e| !e.path().components().any(|c| c.as_os_str() == "subagents"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let mut sessions: Vec<SessionWithTitle> = file_paths
+        .par_iter()
+        .filter_map(|path| {
+            with_file_permit(|| {
+                let file_path = path.to_string_lossy().to_string();
+                let title = derive_title_from_file(&file_path).ok()?;
+                let last_activity = compute_session_time_bounds(path).ok()?.1;
+                let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+                let user_meta = metadata.and_then(|m| m.get_session(&file_path));
+
+                Some(SessionWithTitle {
+                    session_id: path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    title,
+                    last_activity: last_activity.map(|t| t.to_rfc3339()),
+                    message_count_estimate: estimate_message_count_from_size(size),
+                    custom_name: user_meta.and_then(|s| s.custom_name.clone()),
+                    tags: user_meta.map(|s| s.tags.clone()).unwrap_or_default(),
+                    file_path,
+                })
+            })
+        })
+        .collect();
+
+    // RFC 3339 in UTC sorts chronologically; sessions without activity last
+    sessions.sort_by_key(|s| Reverse(s.last_activity.clone()));
+    Ok(sessions)
+}
+
+/// List a project's sessions with derived titles in one call.
+///
+/// Titles stop parsing at the first user prompt and activity comes from a
+/// backward scan, so each file is only partly read; files are processed in
+/// parallel. Custom names and tags saved in the app metadata are included,
+/// keyed by session file path. Subagent transcripts are skipped.
+#[command]
+pub async fn list_sessions_with_titles(
+    project_path: String,
+    state: State<'_, MetadataState>,
+) -> Result<Vec<SessionWithTitle>, String> {
+    let metadata = state
+        .metadata
+        .lock()
+        .map_err(|e| format!("Failed to lock metadata: {e}"))?
+        .clone();
+
+    sessions_with_titles(&project_path, metadata.as_ref())
+}
+
+/// Find the last non-empty text block in message content
+fn last_content_text(content: &Value) -> Option<String> {
+    match content {
+        Value::String(text) if !text.trim().is_empty() => Some(text.clone()),
+        Value::Array(items) => items.iter().rev().find_map(|item| {
+            if item.get("type").and_then(Value::as_str) != Some("text") {
+                return None;
+            }
+            let text = item.get("text").and_then(Value::as_str)?;
+            (!text.trim().is_empty()).then(|| text.to_string())
+        }),
+        _ => None,
+    }
+}
+
+/// Build a preview from the opening user ask and the final assistant response.
+///
+/// Scans forward for the first user message and backward for the last
+/// assistant message, so only the needed lines are parsed. Either side is
+/// `None` when the session has no such text (empty or tool-only sessions).
+#[command]
+pub async fn session_preview(file_path: String) -> Result<SessionPreview, String> {
+    let mmap = map_session_file(&file_path)?;
+    let line_ranges = find_line_ranges(&mmap);
+
+    let first_user_message = line_ranges.iter().find_map(|&(start, end)| {
+        let entry = parse_conversation_entry(&mmap[start..end])?;
+        if entry.message_type != "user" {
+            return None;
+        }
+        first_content_text(&entry.message?.content, true)
+    });
+
+    let last_assistant_message = line_ranges.iter().rev().find_map(|&(start, end)| {
+        let entry = parse_conversation_entry(&mmap[start..end])?;
+        if entry.message_type != "assistant" {
+            return None;
+        }
+        last_content_text(&entry.message?.content)
+    });
+
+    let format = |text: String| truncate_text(&collapse_whitespace(&text), PREVIEW_MAX_CHARS);
+
+    Ok(SessionPreview {
+        first_user_message: first_user_message.map(format),
+        last_assistant_message: last_assistant_message.map(format),
+    })
+}
+
+/// First text of a tool result whose content is a list of blocks
+fn tool_result_block_text(content: &Value) -> Option<String> {
+    content.as_array()?.iter().find_map(|item| {
+        let blocks = item.get("content")?.as_array()?;
+        blocks.iter().find_map(|block| {
+            let text = block.get("text").and_then(Value::as_str)?;
+            (!text.trim().is_empty()).then(|| text.to_string())
+        })
+    })
+}
+
+/// Find the largest messages of a session by line size.
+///
+/// Lines are ranked by byte length without parsing; only the `top_n`
+/// winners are parsed for their role and a short preview. Useful for
+/// spotting a huge tool output that slows rendering down.
+#[command]
+pub async fn largest_messages(
+    file_path: String,
+    top_n: usize,
+) -> Result<Vec<MessageSizeInfo>, String> {
+    let mmap = map_session_file(&file_path)?;
+
+    let mut ranges: Vec<(usize, (usize, usize))> =
+        find_line_ranges(&mmap).into_iter().enumerate().collect();
+    // Largest first; ties keep file order
+    ranges.sort_by(|(a_index, (a_start, a_end)), (b_index, (b_start, b_end))| {
+        (b_end - b_start)
+            .cmp(&(a_end - a_start))
+            .then_with(|| a_index.cmp(b_index))
+    });
+    ranges.truncate(top_n);
+
+    Ok(ranges
+        .into_iter()
+        .map(|(line_index, (start, end))| {
+            // simd-json requires mutable slice
+            let mut line_bytes = mmap[start..end].to_vec();
+            let entry: Option<RawLogEntry> = simd_json::serde::from_slice(&mut line_bytes).ok();
+            let message = entry.as_ref().and_then(|e| e.message.as_ref());
+            let preview = message.and_then(|m| {
+                first_content_text(&m.content, false).or_else(|| tool_result_block_text(&m.content))
+            });
+
+            MessageSizeInfo {
+                line_index,
+                bytes: end - start,
+                message_type: entry.as_ref().map(|e| e.message_type.clone()),
+                role: message.map(|m| m.role.clone()),
+                preview: preview
+                    .map(|text| truncate_text(&collapse_whitespace(&text), PREVIEW_MAX_CHARS)),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_session(dir: &TempDir, lines: &[&str]) -> String {
+        let path = dir.path().join("session-abc.jsonl");
+        fs::write(&path, lines.join("\n")).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_derive_session_title_uses_first_user_prompt() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = write_session(
+            &temp_dir,
+            &[
+                r#"{"type":"system","content":"init"}"#,
+                r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"Caveat: meta"}}"#,
+                r#"{"type":"user","message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#,
+                r#"{"type":"user","message":{"role":"user","content":[{"type":"text","text":"Fix the\nlogin   bug"}]}}"#,
+                r#"{"type":"user","message":{"role":"user","content":"Second prompt"}}"#,
+            ],
+        );
+
+        assert_eq!(
+            derive_session_title(path).await.unwrap(),
+            "Fix the login bug"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_derive_session_title_truncates_long_prompt() {
+        let temp_dir = TempDir::new().unwrap();
+        let long = "a".repeat(200);
+        let line = format!(r#"{{"type":"user","message":{{"role":"user","content":"{long}"}}}}"#);
+        let path = write_session(&temp_dir, &[&line]);
+
+        let title = derive_session_title(path).await.unwrap();
+        assert_eq!(title, format!("{}...", "a".repeat(80)));
+    }
+
+    #[tokio::test]
+    async fn test_derive_session_title_fallbacks() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = write_session(
+            &temp_dir,
+            &[
+                r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"build ok"}]}}"#,
+            ],
+        );
+        assert_eq!(derive_session_title(path).await.unwrap(), "build ok");
+
+        let empty_dir = TempDir::new().unwrap();
+        let path = write_session(&empty_dir, &[]);
+        assert_eq!(derive_session_title(path).await.unwrap(), "session-abc");
+    }
+
+    #[tokio::test]
+    async fn test_session_preview_first_and_last_exchange() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = write_session(
+            &temp_dir,
+            &[
+                r#"{"type":"user","message":{"role":"user","content":"Add a dark mode toggle"}}"#,
+                r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Looking at the settings"}]}}"#,
+                r#"{"type":"user","message":{"role":"user","content":"Also persist it"}}"#,
+                r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done. Toggle\nadded"},{"type":"tool_use","id":"t1","name":"Bash","input":{}}]}}"#,
+                r#"{"type":"system","content":"turn finished"}"#,
+            ],
+        );
+
+        let preview = session_preview(path).await.unwrap();
+        assert_eq!(
+            preview.first_user_message.as_deref(),
+            Some("Add a dark mode toggle")
+        );
+        assert_eq!(
+            preview.last_assistant_message.as_deref(),
+            Some("Done. Toggle added")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_preview_tool_only_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = write_session(
+            &temp_dir,
+            &[
+                r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Read","input":{}}]}}"#,
+            ],
+        );
+
+        let preview = session_preview(path).await.unwrap();
+        assert!(preview.first_user_message.is_none());
+        assert!(preview.last_assistant_message.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_largest_messages_ranks_by_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = "x".repeat(500);
+        let big = format!(
+            r#"{{"type":"user","message":{{"role":"user","content":[{{"type":"tool_result","tool_use_id":"t1","content":[{{"type":"text","text":"{output}"}}]}}]}}}}"#
+        );
+        let path = write_session(
+            &temp_dir,
+            &[
+                r#"{"type":"user","message":{"role":"user","content":"Run the build"}}"#,
+                &big,
+                r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"The build printed a lot"}]}}"#,
+                "not json",
+            ],
+        );
+
+        let largest = largest_messages(path.clone(), 2).await.unwrap();
+        assert_eq!(largest.len(), 2);
+        assert_eq!(largest[0].line_index, 1);
+        assert_eq!(largest[0].bytes, big.len());
+        assert_eq!(largest[0].role.as_deref(), Some("user"));
+        assert_eq!(
+            largest[0].preview.as_deref(),
+            Some(format!("{}...", "x".repeat(200)).as_str())
+        );
+        assert_eq!(largest[1].line_index, 2);
+        assert_eq!(
+            largest[1].preview.as_deref(),
+            Some("The build printed a lot")
+        );
+
+        let all = largest_messages(path, 10).await.unwrap();
+        assert_eq!(all.len(), 4);
+        assert!(all[3].message_type.is_none());
+    }
+
+    #[test]
+    fn test_sessions_with_titles() {
+        let temp_dir = TempDir::new().unwrap();
+        let older = temp_dir.path().join("older.jsonl");
+        let newer = temp_dir.path().join("newer.jsonl");
+        fs::create_dir_all(temp_dir.path().join("subagents")).unwrap();
+        fs::write(
+            &older,
+            r#"{"type":"user","timestamp":"2025-01-01T00:00:00Z","message":{"role":"user","content":"Old task"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            &newer,
+            r#"{"type":"user","timestamp":"2025-02-01T00:00:00Z","message":{"role":"user","content":"New task"}}"#,
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("subagents/agent.jsonl"), "{}").unwrap();
+
+        let mut metadata = UserMetadata::default();
+        metadata.sessions.insert(
+            older.to_string_lossy().to_string(),
+            crate::models::SessionMetadata {
+                custom_name: Some("Renamed".to_string()),
+                tags: vec!["bug".to_string()],
+                ..Default::default()
+            },
+        );
+
+        let sessions =
+            sessions_with_titles(&temp_dir.path().to_string_lossy(), Some(&metadata)).unwrap();
+
+        let found: Vec<(&str, &str)> = sessions
+            .iter()
+            .map(|s| (s.session_id.as_str(), s.title.as_str()))
+            .collect();
+        assert_eq!(found, vec![("newer", "New task"), ("older", "Old task")]);
+        assert_eq!(sessions[1].custom_name.as_deref(), Some("Renamed"));
+        assert_eq!(sessions[1].tags, vec!["bug"]);
+        assert!(sessions[0].custom_name.is_none());
+        assert_eq!(
+            sessions[0].last_activity.as_deref(),
+            Some("2025-02-01T00:00:00+00:00")
+        );
+    }
+}
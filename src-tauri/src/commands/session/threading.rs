@@ -0,0 +1,176 @@
+//! Message threading integrity checks
+//!
+//! Every message links to its predecessor through `parentUuid`. Merged,
+//! hand-edited or truncated files can break those links, which shows up as
+//! detached branches in the branch view.
+
+use super::raw::map_session_file;
+use crate::utils::find_line_ranges;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::command;
+
+/// Minimal view of a log line carrying only threading fields
+#[derive(Debug, Deserialize)]
+struct ThreadProbe {
+    uuid: Option<String>,
+    #[serde(rename = "parentUuid")]
+    parent_uuid: Option<String>,
+}
+
+/// What is wrong with a message's place in the thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadIssueKind {
+    /// `parentUuid` names a message that is not in the file
+    Orphan,
+    /// `parentUuid` names a message that only appears later in the file
+    ForwardReference,
+    /// The `uuid` was already used by an earlier line
+    DuplicateUuid,
+}
+
+/// A message whose threading is broken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadIssue {
+    /// 0-based index of the line among non-empty lines
+    pub line_index: usize,
+    pub uuid: String,
+    pub parent_uuid: Option<String>,
+    pub kind: ThreadIssueKind,
+}
+
+/// Threading integrity of a session file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadReport {
+    /// Lines carrying a `uuid`
+    pub message_count: usize,
+    /// Messages without a `parentUuid`
+    pub root_count: usize,
+    /// Issues in file order
+    pub issues: Vec<ThreadIssue>,
+    pub is_intact: bool,
+}
+
+/// Check threading of `(line_index, uuid, parent_uuid)` triples in file order
+fn check_threads(messages: &[(usize, String, Option<String>)]) -> ThreadReport {
+    let all_uuids: HashSet<&str> = messages.iter().map(|(_, uuid, _)| uuid.as_str()).collect();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut root_count = 0;
+    let mut issues = Vec::new();
+
+    for (line_index, uuid, parent_uuid) in messages {
+        let issue = |kind| ThreadIssue {
+            line_index: *line_index,
+            uuid: uuid.clone(),
+            parent_uuid: parent_uuid.clone(),
+            kind,
+        };
+
+        if seen.contains_key(uuid.as_str()) {
+            issues.push(issue(ThreadIssueKind::DuplicateUuid));
+        }
+
+        match parent_uuid.as_deref() {
+            None => root_count += 1,
+            Some(parent) if seen.contains_key(parent) => {}
+            Some(parent) if all_uuids.contains(parent) => {
+                issues.push(issue(ThreadIssueKind::ForwardReference));
+            }
+            Some(_) => issues.push(issue(ThreadIssueKind::Orphan)),
+        }
+
+        seen.entry(uuid.as_str()).or_insert(*line_index);
+    }
+
+    ThreadReport {
+        message_count: messages.len(),
+        root_count,
+        is_intact: issues.is_empty(),
+        issues,
+    }
+}
+
+/// Verify that every message's `parentUuid` points to an earlier message in
+/// the same file and that no `uuid` is used twice.
+///
+/// The first message of a resumed session may legitimately point into the
+/// session it continues; it is reported as an orphan all the same.
+/// Lines without a `uuid` (summaries, snapshots) are ignored.
+#[command]
+pub async fn thread_integrity_check(file_path: String) -> Result<ThreadReport, String> {
+    let mmap = map_session_file(&file_path)?;
+
+    let messages: Vec<(usize, String, Option<String>)> = find_line_ranges(&mmap)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(line_index, (start, end))| {
+            // simd-json requires mutable slice
+            let mut line_bytes = mmap[start..end].to_vec();
+            let probe: ThreadProbe = simd_json::serde::from_slice(&mut line_bytes).ok()?;
+            Some((line_index, probe.uuid?, probe.parent_uuid))
+        })
+        .collect();
+
+    Ok(check_threads(&messages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_thread_integrity_check_reports_issues() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = temp_dir.path().join("session.jsonl");
+        let lines = [
+            r#"{"type":"user","uuid":"a","parentUuid":null}"#,
+            r#"{"type":"summary","summary":"t","leafUuid":"a"}"#,
+            r#"{"type":"assistant","uuid":"b","parentUuid":"a"}"#,
+            r#"{"type":"user","uuid":"c","parentUuid":"d"}"#,
+            r#"{"type":"assistant","uuid":"d","parentUuid":"missing"}"#,
+            r#"{"type":"assistant","uuid":"b","parentUuid":"a"}"#,
+        ];
+        fs::write(&session, lines.join("\n")).unwrap();
+
+        let report = thread_integrity_check(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(report.message_count, 5);
+        assert_eq!(report.root_count, 1);
+        assert!(!report.is_intact);
+        let found: Vec<(usize, &str, ThreadIssueKind)> = report
+            .issues
+            .iter()
+            .map(|i| (i.line_index, i.uuid.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (3, "c", ThreadIssueKind::ForwardReference),
+                (4, "d", ThreadIssueKind::Orphan),
+                (5, "b", ThreadIssueKind::DuplicateUuid),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_thread_integrity_check_intact() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = temp_dir.path().join("session.jsonl");
+        fs::write(
+            &session,
+            "{\"uuid\":\"a\",\"parentUuid\":null}\n{\"uuid\":\"b\",\"parentUuid\":\"a\"}\n",
+        )
+        .unwrap();
+
+        let report = thread_integrity_check(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert!(report.is_intact);
+        assert_eq!(report.message_count, 2);
+    }
+}
@@ -0,0 +1,320 @@
+//! Session timing analysis
+//!
+//! Measures when a session took place, how long Claude took to respond
+//! to each user turn and how often turns were interrupted.
+
+use super::raw::map_session_file;
+use super::summary::parse_conversation_entry;
+use crate::utils::{find_line_ranges, parse_message_timestamp};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use tauri::command;
+
+/// First and last message timestamps of a session, in file order
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionTimeBounds {
+    /// Timestamp of the first line that has one (RFC 3339)
+    pub first_timestamp: Option<String>,
+    /// Timestamp of the last line that has one (RFC 3339)
+    pub last_timestamp: Option<String>,
+    /// `last_timestamp - first_timestamp`; negative if the file's lines are
+    /// out of chronological order
+    pub duration_ms: Option<i64>,
+}
+
+/// First and last parsed timestamps, `None` when no line has one
+pub(crate) type TimeBounds = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Minimal view of a log line carrying only its timestamp
+#[derive(Debug, Deserialize)]
+struct TimestampProbe {
+    timestamp: Option<String>,
+}
+
+fn line_timestamp(line: &[u8]) -> Option<DateTime<Utc>> {
+    // simd-json requires mutable slice
+    let mut line_bytes = line.to_vec();
+    let probe: TimestampProbe = simd_json::serde::from_slice(&mut line_bytes).ok()?;
+    parse_message_timestamp(&probe.timestamp?)
+}
+
+/// Find the timestamps of the first and last timestamped lines of a session
+/// file, in file order.
+///
+/// Scans forward for the first timestamped line and backward for the last,
+/// so only a few lines are parsed even for large sessions. Lines are appended
+/// as the session runs, so these are normally its earliest and latest
+/// timestamps, but out-of-order lines in between are not considered.
+pub(crate) fn compute_session_time_bounds(file_path: &Path) -> Result<TimeBounds, String> {
+    let mmap = map_session_file(&file_path.to_string_lossy())?;
+    let line_ranges = find_line_ranges(&mmap);
+
+    let first = line_ranges
+        .iter()
+        .find_map(|&(start, end)| line_timestamp(&mmap[start..end]));
+    let last = line_ranges
+        .iter()
+        .rev()
+        .find_map(|&(start, end)| line_timestamp(&mmap[start..end]));
+
+    Ok((first, last))
+}
+
+/// Get the first/last message timestamps and duration of a session
+#[command]
+pub async fn session_time_bounds(file_path: String) -> Result<SessionTimeBounds, String> {
+    let (first, last) = compute_session_time_bounds(Path::new(&file_path))?;
+
+    Ok(SessionTimeBounds {
+        first_timestamp: first.map(|t| t.to_rfc3339()),
+        last_timestamp: last.map(|t| t.to_rfc3339()),
+        duration_ms: first
+            .zip(last)
+            .map(|(first, last)| (last - first).num_milliseconds()),
+    })
+}
+
+/// Response latency of a single assistant turn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnLatency {
+    /// 0-based line index of the first assistant message of the turn
+    pub line_index: usize,
+    pub uuid: Option<String>,
+    pub user_timestamp: String,
+    pub assistant_timestamp: String,
+    pub latency_ms: i64,
+}
+
+/// Per-turn latencies plus aggregates over all measured turns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnLatencyStats {
+    pub turns: Vec<TurnLatency>,
+    pub min_ms: Option<i64>,
+    pub max_ms: Option<i64>,
+    pub median_ms: Option<f64>,
+}
+
+/// Median of a list of values (average of the two middle values for even counts)
+fn median(values: &[i64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    let value = if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] as f64 + sorted[mid] as f64) / 2.0
+    } else {
+        sorted[mid] as f64
+    };
+    Some(value)
+}
+
+/// Compute response latency for each assistant turn.
+///
+/// A turn starts at a user message and is answered by the first assistant
+/// message after it; later assistant messages before the next user message
+/// belong to the same turn. Turns with a missing timestamp on either side are
+/// skipped.
+#[command]
+pub async fn turn_latencies(file_path: String) -> Result<TurnLatencyStats, String> {
+    let mmap = map_session_file(&file_path)?;
+
+    let mut turns = Vec::new();
+    // Timestamp of the user message awaiting a response (None once answered)
+    let mut pending_user: Option<(String, Option<DateTime<Utc>>)> = None;
+
+    for (line_index, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        let Some(entry) = parse_conversation_entry(&mmap[start..end]) else {
+            continue;
+        };
+
+        match entry.message_type.as_str() {
+            "user" => {
+                let raw = entry.timestamp.unwrap_or_default();
+                let parsed = parse_message_timestamp(&raw);
+                pending_user = Some((raw, parsed));
+            }
+            "assistant" => {
+                let Some((user_raw, user_time)) = pending_user.take() else {
+                    continue;
+                };
+                let assistant_raw = entry.timestamp.unwrap_or_default();
+                let (Some(user_time), Some(assistant_time)) =
+                    (user_time, parse_message_timestamp(&assistant_raw))
+                else {
+                    continue;
+                };
+
+                turns.push(TurnLatency {
+                    line_index,
+                    uuid: entry.uuid,
+                    user_timestamp: user_raw,
+                    assistant_timestamp: assistant_raw,
+                    latency_ms: (assistant_time - user_time).num_milliseconds(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let latencies: Vec<i64> = turns.iter().map(|t| t.latency_ms).collect();
+
+    Ok(TurnLatencyStats {
+        min_ms: latencies.iter().min().copied(),
+        max_ms: latencies.iter().max().copied(),
+        median_ms: median(&latencies),
+        turns,
+    })
+}
+
+/// Prefix of the marker Claude writes when the user interrupts a turn,
+/// e.g. `[Request interrupted by user]` or
+/// `[Request interrupted by user for tool use]`
+pub(crate) const INTERRUPTION_MARKER: &str = "[Request interrupted by user";
+
+/// Interrupted turns of a session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InterruptionStats {
+    pub count: usize,
+    /// 0-based line indices of the interruption markers
+    pub line_indices: Vec<usize>,
+}
+
+/// Whether user message content carries an interruption marker, either as
+/// plain text or inside a text or tool result block
+pub(crate) fn is_interruption(content: &Value) -> bool {
+    match content {
+        Value::String(text) => text.trim_start().starts_with(INTERRUPTION_MARKER),
+        Value::Array(blocks) => blocks.iter().any(|block| {
+            block.get("text").is_some_and(is_interruption)
+                || block.get("content").is_some_and(is_interruption)
+        }),
+        _ => false,
+    }
+}
+
+/// Count turns the user interrupted (escape pressed mid-generation or
+/// during a tool call), with the line index of each interruption
+#[command]
+pub async fn count_interruptions(file_path: String) -> Result<InterruptionStats, String> {
+    let mmap = map_session_file(&file_path)?;
+
+    let line_indices: Vec<usize> = find_line_ranges(&mmap)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(line_index, (start, end))| {
+            let entry = parse_conversation_entry(&mmap[start..end])?;
+            let message = entry.message.filter(|_| entry.message_type == "user")?;
+            is_interruption(&message.content).then_some(line_index)
+        })
+        .collect();
+
+    Ok(InterruptionStats {
+        count: line_indices.len(),
+        line_indices,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn line(kind: &str, uuid: &str, timestamp: &str) -> String {
+        format!(
+            r#"{{"type":"{kind}","uuid":"{uuid}","timestamp":"{timestamp}","message":{{"role":"{kind}","content":"x"}}}}"#
+        )
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[5]), Some(5.0));
+        assert_eq!(median(&[9, 1, 5]), Some(5.0));
+        assert_eq!(median(&[4, 1, 3, 2]), Some(2.5));
+    }
+
+    #[tokio::test]
+    async fn test_turn_latencies() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let lines = [
+            line("user", "u1", "2025-01-01T10:00:00.000Z"),
+            line("assistant", "a1", "2025-01-01T10:00:01.500Z"),
+            // Same turn, not measured again
+            line("assistant", "a2", "2025-01-01T10:00:09.000Z"),
+            line("user", "u2", "2025-01-01T10:01:00.000Z"),
+            line("assistant", "a3", "2025-01-01T10:01:04.000Z"),
+            // Missing user timestamp: skipped
+            line("user", "u3", ""),
+            line("assistant", "a4", "2025-01-01T10:02:00.000Z"),
+            line("user", "u4", "2025-01-01T10:03:00.000Z"),
+            line("assistant", "a5", "2025-01-01T10:03:00.500Z"),
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let stats = turn_latencies(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        let latencies: Vec<i64> = stats.turns.iter().map(|t| t.latency_ms).collect();
+        assert_eq!(latencies, vec![1500, 4000, 500]);
+        assert_eq!(stats.turns[0].line_index, 1);
+        assert_eq!(stats.turns[1].uuid.as_deref(), Some("a3"));
+        assert_eq!(stats.min_ms, Some(500));
+        assert_eq!(stats.max_ms, Some(4000));
+        assert_eq!(stats.median_ms, Some(1500.0));
+    }
+
+    #[tokio::test]
+    async fn test_session_time_bounds() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let lines = [
+            r#"{"type":"summary","summary":"Title"}"#.to_string(),
+            line("user", "u1", "2025-01-01T10:00:00Z"),
+            line("assistant", "a1", "2025-01-01T10:05:30Z"),
+            r#"{"type":"file-history-snapshot","messageId":"m1"}"#.to_string(),
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let bounds = session_time_bounds(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            bounds.first_timestamp.as_deref(),
+            Some("2025-01-01T10:00:00+00:00")
+        );
+        assert_eq!(
+            bounds.last_timestamp.as_deref(),
+            Some("2025-01-01T10:05:30+00:00")
+        );
+        assert_eq!(bounds.duration_ms, Some(330_000));
+    }
+
+    #[tokio::test]
+    async fn test_count_interruptions() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let lines = [
+            line("user", "u1", "2025-01-01T10:00:00Z"),
+            line("assistant", "a1", "2025-01-01T10:00:01Z"),
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"text","text":"[Request interrupted by user]"}]}}"#.to_string(),
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"[Request interrupted by user for tool use]"}]}}"#.to_string(),
+            // Quoting the marker mid-sentence is not an interruption
+            r#"{"type":"user","message":{"role":"user","content":"why did I see [Request interrupted by user]?"}}"#.to_string(),
+            r#"{"type":"user","message":{"role":"user","content":"[Request interrupted by user]"}}"#.to_string(),
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let stats = count_interruptions(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.line_indices, vec![2, 3, 5]);
+    }
+}
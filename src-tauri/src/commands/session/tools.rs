@@ -0,0 +1,608 @@
+//! Tool call analysis
+//!
+//! Correlates `tool_use` blocks with their `tool_result` blocks across
+//! messages by `tool_use_id`, finds runs of consecutive tool errors, and
+//! picks out calls to MCP server tools.
+
+use super::raw::map_session_file;
+use super::summary::parse_conversation_entry;
+use crate::utils::find_line_ranges;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use tauri::command;
+
+/// Bucket for tool results whose `tool_use` was not found in the session
+pub const UNMATCHED_TOOL: &str = "unmatched";
+
+/// Name prefix of MCP tools (`mcp__<server>__<tool>`)
+const MCP_TOOL_PREFIX: &str = "mcp__";
+
+/// Iterate the content blocks of a given type in a message's content array
+fn content_blocks<'a>(content: &'a Value, block_type: &'a str) -> impl Iterator<Item = &'a Value> {
+    content
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(move |item| item.get("type").and_then(Value::as_str) == Some(block_type))
+}
+
+/// Result half of a tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallResult {
+    pub tool_use_id: String,
+    /// `content` of the `tool_result` block (string or block array)
+    pub output: Value,
+    pub is_error: bool,
+    /// 0-based line index of the message carrying the result
+    pub line_index: usize,
+}
+
+/// A `tool_use` block combined with its `tool_result`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallPair {
+    pub tool_use_id: String,
+    pub name: String,
+    pub input: Value,
+    /// 0-based line index of the message carrying the call
+    pub line_index: usize,
+    /// None while the call has no result yet (e.g. a live session)
+    pub result: Option<ToolCallResult>,
+}
+
+/// Tool calls of a session paired with their results
+#[derive(Debug, Default)]
+pub(crate) struct PairedToolCalls {
+    /// Calls in session order
+    pub calls: Vec<ToolCallPair>,
+    /// Results whose `tool_use` is not in the session
+    pub unmatched_results: Vec<ToolCallResult>,
+}
+
+/// Pair every `tool_use` with its `tool_result` by `tool_use_id`.
+pub(crate) fn pair_tool_calls(data: &[u8]) -> PairedToolCalls {
+    let mut paired = PairedToolCalls::default();
+    let mut call_positions: HashMap<String, usize> = HashMap::new();
+
+    for (line_index, (start, end)) in find_line_ranges(data).into_iter().enumerate() {
+        let Some(entry) = parse_conversation_entry(&data[start..end]) else {
+            continue;
+        };
+        let Some(message) = entry.message else {
+            continue;
+        };
+
+        for block in content_blocks(&message.content, "tool_use") {
+            let Some(id) = block.get("id").and_then(Value::as_str) else {
+                continue;
+            };
+            call_positions.insert(id.to_string(), paired.calls.len());
+            paired.calls.push(ToolCallPair {
+                tool_use_id: id.to_string(),
+                name: block
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                input: block.get("input").cloned().unwrap_or(Value::Null),
+                line_index,
+                result: None,
+            });
+        }
+
+        for block in content_blocks(&message.content, "tool_result") {
+            let result = ToolCallResult {
+                tool_use_id: block
+                    .get("tool_use_id")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                output: block.get("content").cloned().unwrap_or(Value::Null),
+                is_error: block.get("is_error").and_then(Value::as_bool) == Some(true),
+                line_index,
+            };
+
+            match call_positions.get(&result.tool_use_id) {
+                Some(&position) if paired.calls[position].result.is_none() => {
+                    paired.calls[position].result = Some(result);
+                }
+                _ => paired.unmatched_results.push(result),
+            }
+        }
+    }
+
+    paired
+}
+
+/// Pair each `tool_use` block with its `tool_result` across messages.
+///
+/// Calls are returned in session order with the tool name, input, output,
+/// error flag and the line indices of both messages. Calls without a result
+/// yet have `result: None`.
+#[command]
+pub async fn paired_tool_calls(file_path: String) -> Result<Vec<ToolCallPair>, String> {
+    let mmap = map_session_file(&file_path)?;
+    Ok(pair_tool_calls(&mmap).calls)
+}
+
+/// Count tool errors per tool name.
+///
+/// Each `tool_result` with `is_error: true` is attributed to the tool of the
+/// `tool_use` with the same id; results without a matching use are counted
+/// under `unmatched`. Sorted by error count (descending), then name.
+#[command]
+pub async fn tool_error_stats(file_path: String) -> Result<Vec<(String, usize)>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let paired = pair_tool_calls(&mmap);
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for call in &paired.calls {
+        if call.result.as_ref().is_some_and(|r| r.is_error) {
+            *counts.entry(call.name.clone()).or_insert(0) += 1;
+        }
+    }
+    let unmatched_errors = paired
+        .unmatched_results
+        .iter()
+        .filter(|r| r.is_error)
+        .count();
+    if unmatched_errors > 0 {
+        counts.insert(UNMATCHED_TOOL.to_string(), unmatched_errors);
+    }
+
+    let mut stats: Vec<(String, usize)> = counts.into_iter().collect();
+    stats.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(stats)
+}
+
+/// Fewest consecutive failed tool results reported as a streak
+const MIN_ERROR_STREAK: usize = 2;
+
+/// A run of consecutive failed tool results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorStreak {
+    /// 0-based line index of the first failed result
+    pub start_line_index: usize,
+    /// 0-based line index of the last failed result
+    pub end_line_index: usize,
+    /// Failed results in the run
+    pub length: usize,
+    /// Tools that failed, in first-seen order
+    pub tools: Vec<String>,
+}
+
+/// Find runs of consecutive tool errors.
+///
+/// Tool results are taken in session order and paired with their calls by
+/// `tool_use_id`; results without a matching use count under `unmatched`.
+/// Any successful result ends a run. Runs of at least two errors are
+/// returned longest first (ties in session order), so a session stuck
+/// retrying a failing command shows its worst stretch on top.
+#[command]
+pub async fn error_streaks(file_path: String) -> Result<Vec<ErrorStreak>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let paired = pair_tool_calls(&mmap);
+
+    let mut results: Vec<(&str, &ToolCallResult)> = paired
+        .calls
+        .iter()
+        .filter_map(|call| call.result.as_ref().map(|r| (call.name.as_str(), r)))
+        .chain(paired.unmatched_results.iter().map(|r| (UNMATCHED_TOOL, r)))
+        .collect();
+    results.sort_by_key(|(_, result)| result.line_index);
+
+    let mut streaks: Vec<ErrorStreak> = Vec::new();
+    let mut current: Option<ErrorStreak> = None;
+    for (name, result) in results {
+        if !result.is_error {
+            streaks.extend(current.take());
+            continue;
+        }
+        let streak = current.get_or_insert_with(|| ErrorStreak {
+            start_line_index: result.line_index,
+            end_line_index: result.line_index,
+            length: 0,
+            tools: Vec::new(),
+        });
+        streak.end_line_index = result.line_index;
+        streak.length += 1;
+        if !streak.tools.iter().any(|tool| tool == name) {
+            streak.tools.push(name.to_string());
+        }
+    }
+    streaks.extend(current);
+
+    streaks.retain(|streak| streak.length >= MIN_ERROR_STREAK);
+    streaks.sort_by(|a, b| {
+        b.length
+            .cmp(&a.length)
+            .then_with(|| a.start_line_index.cmp(&b.start_line_index))
+    });
+    Ok(streaks)
+}
+
+/// A call to a tool provided by an MCP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpToolCall {
+    pub tool_use_id: String,
+    pub server: String,
+    pub tool: String,
+    pub input: Value,
+    /// 0-based line index of the message carrying the call
+    pub line_index: usize,
+}
+
+/// MCP tool calls of a session with the distinct servers they went to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpToolCalls {
+    /// Calls in session order
+    pub calls: Vec<McpToolCall>,
+    /// Distinct server names, sorted
+    pub servers: Vec<String>,
+}
+
+/// Split `mcp__<server>__<tool>` into server and tool name
+fn parse_mcp_tool_name(name: &str) -> Option<(&str, &str)> {
+    let (server, tool) = name.strip_prefix(MCP_TOOL_PREFIX)?.split_once("__")?;
+    (!server.is_empty() && !tool.is_empty()).then_some((server, tool))
+}
+
+/// List the calls a session made to MCP server tools.
+///
+/// MCP tools are namespaced as `mcp__<server>__<tool>`; all other tools are
+/// skipped. The distinct servers are returned alongside the calls so users
+/// can audit which external servers a session touched.
+#[command]
+pub async fn list_mcp_tool_calls(file_path: String) -> Result<McpToolCalls, String> {
+    let mmap = map_session_file(&file_path)?;
+
+    let calls: Vec<McpToolCall> = pair_tool_calls(&mmap)
+        .calls
+        .into_iter()
+        .filter_map(|call| {
+            let (server, tool) = parse_mcp_tool_name(&call.name)?;
+            Some(McpToolCall {
+                server: server.to_string(),
+                tool: tool.to_string(),
+                tool_use_id: call.tool_use_id,
+                input: call.input,
+                line_index: call.line_index,
+            })
+        })
+        .collect();
+
+    let servers: BTreeSet<String> = calls.iter().map(|call| call.server.clone()).collect();
+
+    Ok(McpToolCalls {
+        calls,
+        servers: servers.into_iter().collect(),
+    })
+}
+
+/// How much of a session's assistant output was tool use
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgenticRatio {
+    /// Assistant turns with at least one `tool_use` block
+    pub tool_turns: usize,
+    /// Assistant turns without any `tool_use` block
+    pub text_turns: usize,
+    /// `tool_turns` as a fraction of all assistant turns, 0 without any
+    pub ratio: f64,
+}
+
+/// Fraction of assistant turns that called a tool.
+///
+/// Claude writes each content block of a response as its own line sharing
+/// the API `message.id`, so lines are grouped by that id into turns; lines
+/// without one count as a turn each. A high ratio means the session was
+/// mostly acting, a low one mostly explaining.
+#[command]
+pub async fn agentic_ratio(file_path: String) -> Result<AgenticRatio, String> {
+    let mmap = map_session_file(&file_path)?;
+    // Whether each turn used a tool, in first-seen order
+    let mut turns: Vec<bool> = Vec::new();
+    let mut turn_positions: HashMap<String, usize> = HashMap::new();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let Some(message) = parse_conversation_entry(&mmap[start..end]).and_then(|e| e.message)
+        else {
+            continue;
+        };
+        if message.role != "assistant" {
+            continue;
+        }
+
+        let uses_tool = content_blocks(&message.content, "tool_use")
+            .next()
+            .is_some();
+        match message.id {
+            Some(id) => {
+                let position = *turn_positions.entry(id).or_insert_with(|| {
+                    turns.push(false);
+                    turns.len() - 1
+                });
+                turns[position] |= uses_tool;
+            }
+            None => turns.push(uses_tool),
+        }
+    }
+
+    let tool_turns = turns.iter().filter(|&&uses_tool| uses_tool).count();
+    let text_turns = turns.len() - tool_turns;
+    Ok(AgenticRatio {
+        tool_turns,
+        text_turns,
+        ratio: if turns.is_empty() {
+            0.0
+        } else {
+            tool_turns as f64 / turns.len() as f64
+        },
+    })
+}
+
+/// Lines scanned for tool declarations; they are written at session start
+const DECLARATION_SCAN_LINES: usize = 50;
+
+/// Name of a declared tool: a bare string or an object with a `name`
+fn declared_tool_name(tool: &Value) -> Option<&str> {
+    tool.as_str()
+        .or_else(|| tool.get("name").and_then(Value::as_str))
+        .filter(|name| !name.is_empty())
+}
+
+/// Collect the names in the `tools` arrays of the leading lines, stopping at
+/// the first assistant message
+fn scan_declared_tools(data: &[u8]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+
+    for (start, end) in find_line_ranges(data)
+        .into_iter()
+        .take(DECLARATION_SCAN_LINES)
+    {
+        let line = &data[start..end];
+        let Ok(entry) = serde_json::from_slice::<Value>(line) else {
+            continue;
+        };
+        if entry.get("type").and_then(Value::as_str) == Some("assistant") {
+            break;
+        }
+        let Some(tools) = entry.get("tools").and_then(Value::as_array) else {
+            continue;
+        };
+        for name in tools.iter().filter_map(declared_tool_name) {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    names
+}
+
+/// List the tools declared as available when a session started.
+///
+/// Reads the `tools` arrays of system/init lines written before the first
+/// assistant message, which unlike the tools actually called reflect the
+/// session's MCP configuration. Entries may be bare names or tool definitions
+/// with a `name`. Names keep declaration order; returns an empty list when
+/// the session records no declarations.
+#[command]
+pub async fn declared_tools(file_path: String) -> Result<Vec<String>, String> {
+    let mmap = map_session_file(&file_path)?;
+    Ok(scan_declared_tools(&mmap))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn tool_use(id: &str, name: &str) -> String {
+        format!(
+            r#"{{"type":"assistant","message":{{"role":"assistant","content":[{{"type":"tool_use","id":"{id}","name":"{name}","input":{{}}}}]}}}}"#
+        )
+    }
+
+    fn tool_result(id: &str, is_error: bool) -> String {
+        format!(
+            r#"{{"type":"user","message":{{"role":"user","content":[{{"type":"tool_result","tool_use_id":"{id}","content":"out","is_error":{is_error}}}]}}}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_tool_error_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let lines = [
+            tool_use("t1", "Bash"),
+            tool_result("t1", true),
+            tool_use("t2", "Bash"),
+            tool_result("t2", true),
+            tool_use("t3", "Read"),
+            tool_result("t3", true),
+            tool_use("t4", "Edit"),
+            tool_result("t4", false),
+            tool_result("missing", true),
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let stats = tool_error_stats(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            vec![
+                ("Bash".to_string(), 2),
+                ("Read".to_string(), 1),
+                (UNMATCHED_TOOL.to_string(), 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_streaks_longest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let lines = [
+            tool_use("t1", "Bash"),
+            tool_result("t1", true),
+            tool_use("t2", "Bash"),
+            tool_result("t2", true),
+            tool_use("t3", "Read"),
+            tool_result("t3", false),
+            tool_use("t4", "Bash"),
+            tool_result("t4", true),
+            tool_use("t5", "Edit"),
+            tool_result("t5", true),
+            tool_result("missing", true),
+            tool_use("t6", "Bash"),
+            tool_result("t6", true),
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let streaks = error_streaks(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            streaks,
+            vec![
+                ErrorStreak {
+                    start_line_index: 7,
+                    end_line_index: 12,
+                    length: 4,
+                    tools: vec![
+                        "Bash".to_string(),
+                        "Edit".to_string(),
+                        UNMATCHED_TOOL.to_string()
+                    ],
+                },
+                ErrorStreak {
+                    start_line_index: 1,
+                    end_line_index: 3,
+                    length: 2,
+                    tools: vec!["Bash".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paired_tool_calls() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let lines = [
+            tool_use("t1", "Bash"),
+            tool_use("t2", "Read"),
+            tool_result("t1", true),
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let pairs = paired_tool_calls(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(pairs.len(), 2);
+
+        assert_eq!(pairs[0].name, "Bash");
+        assert_eq!(pairs[0].line_index, 0);
+        let result = pairs[0].result.as_ref().unwrap();
+        assert_eq!(result.line_index, 2);
+        assert!(result.is_error);
+        assert_eq!(result.output, Value::String("out".to_string()));
+
+        assert_eq!(pairs[1].name, "Read");
+        assert!(pairs[1].result.is_none());
+    }
+
+    #[test]
+    fn test_parse_mcp_tool_name() {
+        assert_eq!(
+            parse_mcp_tool_name("mcp__github__create_issue"),
+            Some(("github", "create_issue"))
+        );
+        assert_eq!(
+            parse_mcp_tool_name("mcp__my_server__do__thing"),
+            Some(("my_server", "do__thing"))
+        );
+        assert_eq!(parse_mcp_tool_name("Bash"), None);
+        assert_eq!(parse_mcp_tool_name("mcp__incomplete"), None);
+    }
+
+    #[tokio::test]
+    async fn test_list_mcp_tool_calls() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let lines = [
+            tool_use("t1", "mcp__github__create_issue"),
+            tool_use("t2", "Bash"),
+            tool_use("t3", "mcp__slack__post"),
+            tool_use("t4", "mcp__github__list_prs"),
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let mcp = list_mcp_tool_calls(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(mcp.servers, vec!["github", "slack"]);
+        assert_eq!(mcp.calls.len(), 3);
+        assert_eq!(mcp.calls[1].tool, "post");
+        assert_eq!(mcp.calls[1].line_index, 2);
+    }
+
+    #[tokio::test]
+    async fn test_declared_tools() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let lines = [
+            r#"{"type":"system","subtype":"init","tools":["Bash","Read","mcp__github__search"]}"#
+                .to_string(),
+            r#"{"type":"system","tools":[{"name":"Read","description":"x"},{"name":"WebFetch"}]}"#
+                .to_string(),
+            tool_use("t1", "Bash"),
+            r#"{"type":"system","tools":["Late"]}"#.to_string(),
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let tools = declared_tools(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            tools,
+            vec!["Bash", "Read", "mcp__github__search", "WebFetch"]
+        );
+
+        fs::write(&path, tool_use("t1", "Bash")).unwrap();
+        let tools = declared_tools(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert!(tools.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_agentic_ratio_groups_blocks_by_message_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("session.jsonl");
+        let text = |id: &str| {
+            format!(
+                r#"{{"type":"assistant","message":{{"id":"{id}","role":"assistant","content":[{{"type":"text","text":"hi"}}]}}}}"#
+            )
+        };
+        let lines = [
+            r#"{"type":"user","message":{"role":"user","content":"go"}}"#.to_string(),
+            // Text and tool use of one response on separate lines
+            text("msg_1"),
+            r#"{"type":"assistant","message":{"id":"msg_1","role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{}}]}}"#.to_string(),
+            tool_result("t1", false),
+            text("msg_2"),
+            tool_use("t2", "Read"),
+        ];
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let ratio = agentic_ratio(path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(ratio.tool_turns, 2);
+        assert_eq!(ratio.text_turns, 1);
+        assert!((ratio.ratio - 2.0 / 3.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,152 @@
+//! Error triage
+//!
+//! Finds the sessions of a project that hit tool errors or were interrupted,
+//! so problematic runs can be reviewed without opening each one.
+
+use super::raw::map_session_file;
+use super::summary::parse_conversation_entry;
+use super::timing::{is_interruption, INTERRUPTION_MARKER};
+use crate::commands::file_limit::with_file_permit;
+use crate::utils::find_line_ranges;
+use memchr::memmem;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tauri::command;
+use walkdir::WalkDir;
+
+/// A session with tool errors or interruptions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorSessionSummary {
+    pub session_id: String,
+    pub file_path: String,
+    /// `tool_result` blocks with `is_error: true`
+    pub tool_errors: usize,
+    /// Turns interrupted by the user
+    pub interruptions: usize,
+    /// `tool_errors + interruptions`
+    pub error_count: usize,
+}
+
+/// Count tool errors and interruptions in one session file.
+///
+/// Only lines containing `is_error` or the interruption marker are parsed.
+fn scan_session_errors(path: &Path) -> Option<ErrorSessionSummary> {
+    let mmap = map_session_file(&path.to_string_lossy()).ok()?;
+    let error_finder = memmem::Finder::new(b"\"is_error\"");
+    let interruption_finder = memmem::Finder::new(INTERRUPTION_MARKER.as_bytes());
+
+    let mut tool_errors = 0;
+    let mut interruptions = 0;
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let line = &mmap[start..end];
+        let has_error_flag = error_finder.find(line).is_some();
+        let has_marker = interruption_finder.find(line).is_some();
+        if !has_error_flag && !has_marker {
+            continue;
+        }
+        let Some(entry) = parse_conversation_entry(line) else {
+            continue;
+        };
+        let Some(message) = entry.message else {
+            continue;
+        };
+
+        if has_error_flag {
+            tool_errors += message
+                .content
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|block| {
+                    block.get("type").and_then(Value::as_str) == Some("tool_result")
+                        && block.get("is_error").and_then(Value::as_bool) == Some(true)
+                })
+                .count();
+        }
+        if has_marker && entry.message_type == "user" && is_interruption(&message.content) {
+            interruptions += 1;
+        }
+    }
+
+    Some(ErrorSessionSummary {
+        session_id: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        file_path: path.to_string_lossy().to_string(),
+        tool_errors,
+        interruptions,
+        error_count: tool_errors + interruptions,
+    })
+}
+
+/// List the sessions of a project that contain tool errors or interruptions.
+///
+/// Sessions are scanned in parallel; sessions without either are left out.
+/// Sorted by total error count (descending), then file path.
+#[command]
+pub async fn sessions_with_errors(
+    project_path: String,
+) -> Result<Vec<ErrorSessionSummary>, String> {
+    let project_dir = PathBuf::from(&project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Project directory not found: {project_path}"));
+    }
+
+    let session_files: Vec<PathBuf> = WalkDir::new(&project_dir)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let mut sessions: Vec<ErrorSessionSummary> = session_files
+        .par_iter()
+        .filter_map(|path| with_file_permit(|| scan_session_errors(path)))
+        .filter(|summary| summary.error_count > 0)
+        .collect();
+    sessions.sort_by(|a, b| {
+        b.error_count
+            .cmp(&a.error_count)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const TOOL_ERROR: &str = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"boom","is_error":true}]}}"#;
+    const TOOL_OK: &str = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t2","content":"ok","is_error":false}]}}"#;
+    const INTERRUPTED: &str = r#"{"type":"user","message":{"role":"user","content":[{"type":"text","text":"[Request interrupted by user]"}]}}"#;
+
+    #[tokio::test]
+    async fn test_sessions_with_errors_sorted_by_count() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("one.jsonl"), TOOL_ERROR).unwrap();
+        fs::write(
+            temp_dir.path().join("three.jsonl"),
+            [TOOL_ERROR, TOOL_OK, TOOL_ERROR, INTERRUPTED].join("\n"),
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("clean.jsonl"), TOOL_OK).unwrap();
+
+        let sessions = sessions_with_errors(temp_dir.path().to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        let summary: Vec<(&str, usize, usize)> = sessions
+            .iter()
+            .map(|s| (s.session_id.as_str(), s.tool_errors, s.interruptions))
+            .collect();
+        assert_eq!(summary, vec![("three", 2, 1), ("one", 1, 0)]);
+        assert_eq!(sessions[0].error_count, 3);
+    }
+}
@@ -0,0 +1,101 @@
+//! Web deep link module
+//!
+//! Builds a browser URL for a session so it can be opened in Claude's web UI.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use tauri::command;
+
+/// Environment variable overriding the web base URL
+pub const WEB_BASE_URL_ENV: &str = "CLAUDE_WEB_BASE_URL";
+
+/// Default base URL for conversation deep links
+const DEFAULT_WEB_BASE_URL: &str = "https://claude.ai/chat";
+
+lazy_static! {
+    /// Canonical UUID pattern (8-4-4-4-12 hex digits)
+    static ref UUID_REGEX: Regex = Regex::new(
+        r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$"
+    )
+    .unwrap();
+}
+
+/// Build a deep link from a base URL and session id.
+/// Returns None for non-UUID ids or non-http(s) base URLs.
+fn build_web_url(base_url: &str, session_id: &str) -> Option<String> {
+    if !UUID_REGEX.is_match(session_id) {
+        return None;
+    }
+
+    let base = base_url.trim().trim_end_matches('/');
+    if !(base.starts_with("https://") || base.starts_with("http://")) {
+        return None;
+    }
+
+    Some(format!("{base}/{}", session_id.to_lowercase()))
+}
+
+/// Get the web UI URL for a session.
+///
+/// # Arguments
+/// * `session_id` - Session UUID
+/// * `base_url` - Base URL; falls back to `CLAUDE_WEB_BASE_URL`, then `https://claude.ai/chat`
+///
+/// Returns `None` if the session id is not a UUID or the base URL is not http(s).
+#[command]
+pub async fn session_web_url(session_id: String, base_url: Option<String>) -> Option<String> {
+    let base = base_url
+        .filter(|b| !b.trim().is_empty())
+        .or_else(|| std::env::var(WEB_BASE_URL_ENV).ok())
+        .unwrap_or_else(|| DEFAULT_WEB_BASE_URL.to_string());
+
+    build_web_url(&base, &session_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_web_url_valid() {
+        assert_eq!(
+            build_web_url(
+                "https://claude.ai/chat/",
+                "3F2504E0-4F89-11D3-9A0C-0305E82C3301"
+            ),
+            Some("https://claude.ai/chat/3f2504e0-4f89-11d3-9a0c-0305e82c3301".to_string())
+        );
+    }
+
+    #[test]
+    fn test_build_web_url_rejects_invalid_input() {
+        assert_eq!(build_web_url("https://claude.ai/chat", "not-a-uuid"), None);
+        assert_eq!(
+            build_web_url(
+                "https://claude.ai/chat",
+                "3f2504e0-4f89-11d3-9a0c-0305e82c3301/../x"
+            ),
+            None
+        );
+        assert_eq!(
+            build_web_url(
+                "javascript:alert(1)",
+                "3f2504e0-4f89-11d3-9a0c-0305e82c3301"
+            ),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_web_url_explicit_base() {
+        let url = session_web_url(
+            "3f2504e0-4f89-11d3-9a0c-0305e82c3301".to_string(),
+            Some("http://localhost:3000/chat".to_string()),
+        )
+        .await;
+        assert_eq!(
+            url.as_deref(),
+            Some("http://localhost:3000/chat/3f2504e0-4f89-11d3-9a0c-0305e82c3301")
+        );
+    }
+}
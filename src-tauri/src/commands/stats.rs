@@ -1,18 +1,24 @@
-use crate::commands::session::load_session_messages;
+use crate::commands::file_limit::with_file_permit;
+use crate::commands::session::{
+    derive_title_from_file, load_session_messages, map_session_file, user_prompts,
+};
 #[cfg(test)]
 use crate::models::MessageContent;
 use crate::models::{
-    ActivityHeatmap, ClaudeMessage, DailyStats, GlobalStatsSummary, ModelStats, ProjectRanking,
-    ProjectStatsSummary, RawLogEntry, SessionComparison, SessionTokenStats, TokenDistribution,
-    TokenUsage, ToolUsageStats,
+    cache_hit_ratio, ActivityHeatmap, CacheStats, ClaudeMessage, CostSummary, DailyStats,
+    GlobalStatsSummary, ModelCacheStats, ModelCost, ModelPricing, ModelStats, ProjectRanking,
+    ProjectStatsSummary, PromptLengthBucket, PromptStats, RawLogEntry, ReadingStats,
+    SessionComparison, SessionCost, SessionCostRanking, SessionReadingStats, SessionTokenStats,
+    TokenDistribution, TokenSummary, TokenUsage, ToolUsageStats, TurnPoint,
 };
-use crate::utils::find_line_ranges;
-use chrono::{DateTime, Datelike, Timelike, Utc};
+use crate::utils::{find_line_ranges, parse_message_timestamp, timezone_offset};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Months, NaiveDate, Timelike, Utc};
 use memmap2::Mmap;
 use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 /// Parse a line using simd-json (requires mutable slice)
@@ -440,7 +446,7 @@ fn extract_token_usage(message: &ClaudeMessage) -> TokenUsage {
 #[tauri::command]
 pub async fn get_session_token_stats(session_path: String) -> Result<SessionTokenStats, String> {
     let start = std::time::Instant::now();
-    let messages = load_session_messages(session_path.clone()).await?;
+    let messages = load_session_messages(session_path.clone(), None, None, None, None).await?;
     let load_time = start.elapsed();
 
     if messages.is_empty() {
@@ -539,7 +545,9 @@ pub struct PaginatedTokenStats {
 
 /// Synchronous version of session token stats extraction for parallel processing
 #[allow(unsafe_code)] // Required for mmap performance optimization
-fn extract_session_token_stats_sync(session_path: &PathBuf) -> Option<SessionTokenStats> {
+pub(crate) fn extract_session_token_stats_sync(
+    session_path: &PathBuf,
+) -> Option<SessionTokenStats> {
     let file = fs::File::open(session_path).ok()?;
 
     // SAFETY: We're only reading the file, and the file handle is kept open
@@ -644,6 +652,15 @@ fn extract_session_token_stats_sync(session_path: &PathBuf) -> Option<SessionTok
     })
 }
 
+/// Sum all token counts across session files, in parallel
+pub(crate) fn total_session_tokens(session_files: &[PathBuf]) -> u64 {
+    session_files
+        .par_iter()
+        .filter_map(|path| with_file_permit(|| extract_session_token_stats_sync(path)))
+        .map(|stats| u64::from(stats.total_tokens))
+        .sum()
+}
+
 #[tauri::command]
 pub async fn get_project_token_stats(
     project_path: String,
@@ -679,7 +696,7 @@ pub async fn get_project_token_stats(
     // Process all sessions in parallel using sync function
     let mut all_stats: Vec<SessionTokenStats> = session_files
         .par_iter()
-        .filter_map(extract_session_token_stats_sync)
+        .filter_map(|path| with_file_permit(|| extract_session_token_stats_sync(path)))
         .collect();
 
     #[cfg(debug_assertions)]
@@ -798,7 +815,7 @@ pub async fn get_project_stats_summary(
     // Phase 2: Process all session files in parallel
     let mut file_stats: Vec<ProjectSessionFileStats> = session_files
         .par_iter()
-        .filter_map(process_session_file_for_project_stats)
+        .filter_map(|path| with_file_permit(|| process_session_file_for_project_stats(path)))
         .collect();
 
     // Filter by date
@@ -954,6 +971,611 @@ pub async fn get_project_stats_summary(
     Ok(summary)
 }
 
+/// Tools whose `file_path`/`notebook_path` input reveals the language being written
+const FILE_WRITING_TOOLS: [&str; 4] = ["Write", "Edit", "MultiEdit", "NotebookEdit"];
+
+/// Bucket for extensions and fence tags without a known language
+const OTHER_LANGUAGE: &str = "other";
+
+/// File extensions and code fence tags mapped to language names
+const LANGUAGE_TABLE: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("rust", "Rust"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("typescript", "TypeScript"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("javascript", "JavaScript"),
+    ("py", "Python"),
+    ("python", "Python"),
+    ("go", "Go"),
+    ("golang", "Go"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("kotlin", "Kotlin"),
+    ("swift", "Swift"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cpp", "C++"),
+    ("cc", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("csharp", "C#"),
+    ("rb", "Ruby"),
+    ("ruby", "Ruby"),
+    ("php", "PHP"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("zsh", "Shell"),
+    ("shell", "Shell"),
+    ("sql", "SQL"),
+    ("html", "HTML"),
+    ("css", "CSS"),
+    ("scss", "CSS"),
+    ("json", "JSON"),
+    ("yaml", "YAML"),
+    ("yml", "YAML"),
+    ("toml", "TOML"),
+    ("md", "Markdown"),
+    ("markdown", "Markdown"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("ipynb", "Jupyter Notebook"),
+];
+
+/// Map a file extension or code fence tag to a language name
+fn language_for(tag: &str) -> &'static str {
+    let tag = tag.to_lowercase();
+    LANGUAGE_TABLE
+        .iter()
+        .find(|(key, _)| *key == tag)
+        .map_or(OTHER_LANGUAGE, |(_, language)| language)
+}
+
+/// Target path of a Write/Edit/MultiEdit/NotebookEdit `tool_use` block
+fn written_file_path(tool_use: &serde_json::Value) -> Option<&str> {
+    let name = tool_use.get("name").and_then(|v| v.as_str())?;
+    if !FILE_WRITING_TOOLS.contains(&name) {
+        return None;
+    }
+    let input = tool_use.get("input")?;
+    input
+        .get("file_path")
+        .or_else(|| input.get("notebook_path"))
+        .and_then(|v| v.as_str())
+}
+
+/// Tally the languages of fenced code blocks (triple backtick + tag) in a text block
+fn count_fence_languages(text: &str, counts: &mut HashMap<String, usize>) {
+    let mut in_block = false;
+    for line in text.lines() {
+        let Some(info) = line.trim_start().strip_prefix("```") else {
+            continue;
+        };
+        if in_block {
+            in_block = false;
+            continue;
+        }
+        in_block = true;
+        if let Some(tag) = info.split_whitespace().next() {
+            *counts.entry(language_for(tag).to_string()).or_insert(0) += 1;
+        }
+    }
+}
+
+/// Collect language counts from Write/Edit tool calls and code fences in one session
+fn process_session_file_for_languages(session_path: &PathBuf) -> Option<HashMap<String, usize>> {
+    let mmap = map_session_file(&session_path.to_string_lossy()).ok()?;
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let mut line_bytes = mmap[start..end].to_vec();
+        let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) else {
+            continue;
+        };
+        let Some(message) = log_entry.message else {
+            continue;
+        };
+
+        match &message.content {
+            serde_json::Value::String(text) => count_fence_languages(text, &mut counts),
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    match item.get("type").and_then(|v| v.as_str()) {
+                        Some("text") => {
+                            if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
+                                count_fence_languages(text, &mut counts);
+                            }
+                        }
+                        Some("tool_use") => {
+                            if let Some(path) = written_file_path(item) {
+                                let language = std::path::Path::new(path)
+                                    .extension()
+                                    .and_then(|e| e.to_str())
+                                    .map_or(OTHER_LANGUAGE, language_for);
+                                *counts.entry(language.to_string()).or_insert(0) += 1;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(counts)
+}
+
+/// Language breakdown for a project, based on file extensions in Write/Edit
+/// tool calls and fenced code block languages across all sessions.
+///
+/// Returns `(language, count)` pairs sorted by count descending. Unrecognized
+/// extensions and fence tags are counted under `other`.
+#[tauri::command]
+pub async fn project_language_stats(project_path: String) -> Result<Vec<(String, usize)>, String> {
+    if project_path.trim().is_empty() {
+        return Err("project_path is required".to_string());
+    }
+    let project_path_buf = PathBuf::from(&project_path);
+    if !project_path_buf.is_absolute() {
+        return Err("project_path must be absolute".to_string());
+    }
+
+    let session_files: Vec<PathBuf> = WalkDir::new(&project_path)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let totals = session_files
+        .par_iter()
+        .filter_map(|path| with_file_permit(|| process_session_file_for_languages(path)))
+        .reduce(HashMap::new, |mut acc, counts| {
+            for (language, count) in counts {
+                *acc.entry(language).or_insert(0) += count;
+            }
+            acc
+        });
+
+    let mut languages: Vec<(String, usize)> = totals.into_iter().collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    Ok(languages)
+}
+
+/// Bucket for edited files without an extension
+const NO_EXTENSION: &str = "(none)";
+
+/// File extensions edited in a session, for a compact tech-stack badge.
+///
+/// Counts distinct files written by Write/Edit/MultiEdit/NotebookEdit tool
+/// calls per lowercase extension; files without one are counted under
+/// `(none)`. Returns `(extension, file count)` pairs sorted by count
+/// descending.
+#[tauri::command]
+pub async fn edited_extensions(file_path: String) -> Result<Vec<(String, usize)>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let mut files: HashSet<String> = HashSet::new();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let mut line_bytes = mmap[start..end].to_vec();
+        let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) else {
+            continue;
+        };
+        let Some(serde_json::Value::Array(items)) = log_entry.message.map(|m| m.content) else {
+            continue;
+        };
+        for item in &items {
+            if item.get("type").and_then(|v| v.as_str()) != Some("tool_use") {
+                continue;
+            }
+            if let Some(path) = written_file_path(item) {
+                files.insert(path.to_string());
+            }
+        }
+    }
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for path in &files {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map_or_else(|| NO_EXTENSION.to_string(), str::to_lowercase);
+        *counts.entry(extension).or_insert(0) += 1;
+    }
+
+    let mut extensions: Vec<(String, usize)> = counts.into_iter().collect();
+    extensions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(extensions)
+}
+
+/// Characters of rendered text in message content: string content and
+/// `text` blocks. None when there is no text (tool calls and results only).
+fn rendered_text_len(content: &serde_json::Value) -> Option<usize> {
+    match content {
+        serde_json::Value::String(text) => Some(text.chars().count()),
+        serde_json::Value::Array(items) => {
+            let lengths: Vec<usize> = items
+                .iter()
+                .filter(|item| item.get("type").and_then(|v| v.as_str()) == Some("text"))
+                .filter_map(|item| item.get("text").and_then(|v| v.as_str()))
+                .map(|text| text.chars().count())
+                .collect();
+            (!lengths.is_empty()).then(|| lengths.iter().sum())
+        }
+        _ => None,
+    }
+}
+
+/// Rendered text length of each message in order, for a verbosity sparkline.
+///
+/// # Arguments
+/// * `file_path` - Session JSONL file
+/// * `role` - Only include `user` or `assistant` messages (default: both)
+///
+/// Lengths are in characters of text content; tool inputs and outputs are
+/// excluded, and messages carrying only tool traffic are skipped.
+#[tauri::command]
+pub async fn message_length_series(
+    file_path: String,
+    role: Option<String>,
+) -> Result<Vec<usize>, String> {
+    let mmap = map_session_file(&file_path)?;
+
+    Ok(find_line_ranges(&mmap)
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let mut line_bytes = mmap[start..end].to_vec();
+            let log_entry = parse_raw_log_entry_simd(&mut line_bytes)?;
+            if log_entry.is_meta.unwrap_or(false) {
+                return None;
+            }
+            let message = log_entry.message?;
+            if role.as_deref().is_some_and(|r| r != message.role) {
+                return None;
+            }
+            rendered_text_len(&message.content)
+        })
+        .collect())
+}
+
+/// Whether message content consists only of `tool_result` blocks
+fn is_tool_result_only(content: &serde_json::Value) -> bool {
+    content.as_array().is_some_and(|items| {
+        !items.is_empty()
+            && items
+                .iter()
+                .all(|item| item.get("type").and_then(|v| v.as_str()) == Some("tool_result"))
+    })
+}
+
+/// Running balance of assistant and user messages, one point per message,
+/// to show where a conversation turned into an assistant monologue.
+///
+/// Computed in a single pass. Assistant lines sharing an API `message.id`
+/// are one response and count once; user lines carrying only tool results
+/// and meta lines are not conversation turns and are skipped.
+#[tauri::command]
+pub async fn turn_balance_series(file_path: String) -> Result<Vec<TurnPoint>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let mut points = Vec::new();
+    let mut user_messages = 0;
+    let mut assistant_messages = 0;
+    let mut last_assistant_id: Option<String> = None;
+
+    for (line_index, (start, end)) in find_line_ranges(&mmap).into_iter().enumerate() {
+        let mut line_bytes = mmap[start..end].to_vec();
+        let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) else {
+            continue;
+        };
+        if log_entry.is_meta.unwrap_or(false) {
+            continue;
+        }
+        let Some(message) = log_entry.message else {
+            continue;
+        };
+
+        match message.role.as_str() {
+            "assistant" => {
+                if message.id.is_some() && message.id == last_assistant_id {
+                    continue;
+                }
+                last_assistant_id = message.id;
+                assistant_messages += 1;
+            }
+            "user" if !is_tool_result_only(&message.content) => user_messages += 1,
+            _ => continue,
+        }
+
+        points.push(TurnPoint {
+            line_index,
+            role: message.role,
+            user_messages,
+            assistant_messages,
+            delta: i64::try_from(assistant_messages).unwrap_or(i64::MAX)
+                - i64::try_from(user_messages).unwrap_or(i64::MAX),
+            assistant_share: assistant_messages as f64
+                / (assistant_messages + user_messages) as f64,
+        });
+    }
+
+    Ok(points)
+}
+
+/// Most frequent words returned by `word_frequencies`
+const WORD_FREQUENCY_TOP_N: usize = 200;
+
+/// Count the lowercased words of a text block outside fenced code blocks
+fn count_words(
+    text: &str,
+    min_length: usize,
+    stopwords: &HashSet<String>,
+    counts: &mut HashMap<String, usize>,
+) {
+    let mut in_code_block = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        for token in line.split(|c: char| !(c.is_alphanumeric() || c == '\'')) {
+            let word = token.trim_matches('\'').to_lowercase();
+            if word.chars().count() < min_length
+                || word.chars().all(char::is_numeric)
+                || is_base64_blob(&word)
+                || stopwords.contains(&word)
+            {
+                continue;
+            }
+            *counts.entry(word).or_insert(0) += 1;
+        }
+    }
+}
+
+/// Most frequent words in a session's message text, for a word cloud.
+///
+/// # Arguments
+/// * `file_path` - Session JSONL file
+/// * `min_length` - Shorter words (in characters) are ignored
+/// * `stopwords` - Words to ignore, matched case-insensitively
+///
+/// Only text content of user and assistant messages is counted; tool inputs
+/// and outputs, thinking and fenced code blocks are excluded. Words are
+/// lowercased. Returns the top 200, most frequent first, ties alphabetical.
+#[tauri::command]
+pub async fn word_frequencies(
+    file_path: String,
+    min_length: usize,
+    stopwords: Vec<String>,
+) -> Result<Vec<(String, usize)>, String> {
+    let mmap = map_session_file(&file_path)?;
+    let stopwords: HashSet<String> = stopwords.iter().map(|w| w.to_lowercase()).collect();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let mut line_bytes = mmap[start..end].to_vec();
+        let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) else {
+            continue;
+        };
+        if log_entry.is_meta.unwrap_or(false) {
+            continue;
+        }
+        let Some(message) = log_entry.message else {
+            continue;
+        };
+
+        match &message.content {
+            serde_json::Value::String(text) => {
+                count_words(text, min_length, &stopwords, &mut counts);
+            }
+            serde_json::Value::Array(items) => {
+                for text in items
+                    .iter()
+                    .filter(|item| item.get("type").and_then(|v| v.as_str()) == Some("text"))
+                    .filter_map(|item| item.get("text").and_then(|v| v.as_str()))
+                {
+                    count_words(text, min_length, &stopwords, &mut counts);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut frequencies: Vec<(String, usize)> = counts.into_iter().collect();
+    frequencies.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    frequencies.truncate(WORD_FREQUENCY_TOP_N);
+    Ok(frequencies)
+}
+
+/// Reading speed used for `ReadingStats::reading_time_minutes`
+const WORDS_PER_MINUTE: u64 = 200;
+
+/// Tokens at least this long made only of base64 characters are treated as
+/// encoded blobs (images, archives) rather than words
+const BASE64_BLOB_MIN_LEN: usize = 64;
+
+fn is_base64_blob(token: &str) -> bool {
+    token.len() >= BASE64_BLOB_MIN_LEN
+        && token
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=' | b'-' | b'_'))
+}
+
+/// Add the words and fenced code blocks of an assistant text block
+fn count_reading_text(text: &str, stats: &mut SessionReadingStats) {
+    stats.words += text
+        .split_whitespace()
+        .filter(|token| !is_base64_blob(token))
+        .count() as u64;
+
+    let fences = text
+        .lines()
+        .filter(|line| line.trim_start().starts_with("```"))
+        .count();
+    // An unclosed trailing fence still opens a block
+    stats.code_blocks += u32::try_from(fences.div_ceil(2)).unwrap_or(u32::MAX);
+}
+
+/// Collect reading stats from the assistant messages of one session
+fn process_session_file_for_reading(session_path: &PathBuf) -> Option<SessionReadingStats> {
+    let mmap = map_session_file(&session_path.to_string_lossy()).ok()?;
+
+    let mut stats = SessionReadingStats {
+        session_id: session_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        file_path: session_path.to_string_lossy().to_string(),
+        ..SessionReadingStats::default()
+    };
+
+    for (start, end) in find_line_ranges(&mmap) {
+        let mut line_bytes = mmap[start..end].to_vec();
+        let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) else {
+            continue;
+        };
+        let Some(message) = log_entry.message.filter(|m| m.role == "assistant") else {
+            continue;
+        };
+
+        match &message.content {
+            serde_json::Value::String(text) => count_reading_text(text, &mut stats),
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    match item.get("type").and_then(|v| v.as_str()) {
+                        Some("text") => {
+                            if let Some(text) = item.get("text").and_then(|v| v.as_str()) {
+                                count_reading_text(text, &mut stats);
+                            }
+                        }
+                        Some("tool_use") => stats.tool_calls += 1,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(stats)
+}
+
+/// Reading statistics for a project: words Claude wrote, estimated reading
+/// time, code blocks and tool calls across all sessions.
+///
+/// Only assistant text is counted as words; tool inputs and outputs, thinking
+/// and base64 blobs are excluded. Sessions are scanned in parallel and listed
+/// individually, most words first.
+#[tauri::command]
+pub async fn project_reading_stats(project_path: String) -> Result<ReadingStats, String> {
+    if project_path.trim().is_empty() {
+        return Err("project_path is required".to_string());
+    }
+    let project_path_buf = PathBuf::from(&project_path);
+    if !project_path_buf.is_absolute() {
+        return Err("project_path must be absolute".to_string());
+    }
+
+    let session_files: Vec<PathBuf> = WalkDir::new(&project_path)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let mut sessions: Vec<SessionReadingStats> = session_files
+        .par_iter()
+        .filter_map(|path| with_file_permit(|| process_session_file_for_reading(path)))
+        .collect();
+    sessions.sort_by(|a, b| {
+        b.words
+            .cmp(&a.words)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+
+    let total_words: u64 = sessions.iter().map(|s| s.words).sum();
+    Ok(ReadingStats {
+        total_words,
+        reading_time_minutes: total_words.div_ceil(WORDS_PER_MINUTE),
+        code_blocks: sessions.iter().map(|s| s.code_blocks).sum(),
+        tool_calls: sessions.iter().map(|s| s.tool_calls).sum(),
+        sessions,
+    })
+}
+
+/// Label for assistant messages that do not name their model
+const UNKNOWN_MODEL: &str = "unknown";
+
+/// Prompt cache effectiveness of a session.
+///
+/// Sums uncached input, cache creation and cache read tokens across assistant
+/// messages, per model and overall, and computes the share of input served
+/// from the cache. Messages without usage are skipped.
+#[tauri::command]
+pub async fn cache_stats(file_path: String) -> Result<CacheStats, String> {
+    let mmap = map_session_file(&file_path)?;
+    let mut by_model: HashMap<String, ModelCacheStats> = HashMap::new();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        // simd-json requires mutable slice
+        let mut line_bytes = mmap[start..end].to_vec();
+        let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) else {
+            continue;
+        };
+        let Ok(message) = ClaudeMessage::try_from(log_entry) else {
+            continue;
+        };
+        if message.message_type != "assistant" {
+            continue;
+        }
+
+        let usage = extract_token_usage(&message);
+        if usage.input_tokens.is_none()
+            && usage.cache_creation_input_tokens.is_none()
+            && usage.cache_read_input_tokens.is_none()
+        {
+            continue;
+        }
+
+        let model = message.model.as_deref().unwrap_or(UNKNOWN_MODEL);
+        by_model
+            .entry(model.to_string())
+            .or_insert_with(|| ModelCacheStats {
+                model: model.to_string(),
+                ..ModelCacheStats::default()
+            })
+            .add_usage(&usage);
+    }
+
+    let mut by_model: Vec<ModelCacheStats> = by_model.into_values().collect();
+    by_model.sort_by(|a, b| {
+        b.cache_read_tokens
+            .cmp(&a.cache_read_tokens)
+            .then_with(|| a.model.cmp(&b.model))
+    });
+
+    let input_tokens = by_model.iter().map(|m| m.input_tokens).sum();
+    let cache_creation_tokens = by_model.iter().map(|m| m.cache_creation_tokens).sum();
+    let cache_read_tokens = by_model.iter().map(|m| m.cache_read_tokens).sum();
+    Ok(CacheStats {
+        input_tokens,
+        cache_creation_tokens,
+        cache_read_tokens,
+        hit_ratio: cache_hit_ratio(input_tokens, cache_creation_tokens, cache_read_tokens),
+        by_model,
+    })
+}
+
 /// Lightweight session stats for comparison (parallel processing)
 #[derive(Clone)]
 struct SessionComparisonStats {
@@ -1044,7 +1666,7 @@ pub async fn get_session_comparison(
     // Phase 2: Process all session files in parallel (lightweight processing)
     let all_sessions: Vec<SessionComparisonStats> = session_files
         .par_iter()
-        .filter_map(process_session_file_for_comparison)
+        .filter_map(|path| with_file_permit(|| process_session_file_for_comparison(path)))
         .collect();
     let process_time = start.elapsed();
 
@@ -1180,6 +1802,9 @@ impl TryFrom<RawLogEntry> for ClaudeMessage {
             prevented_continuation: log_entry.prevented_continuation,
             compact_metadata: log_entry.compact_metadata,
             microcompact_metadata: log_entry.microcompact_metadata,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         })
     }
 }
@@ -1223,7 +1848,7 @@ pub async fn get_global_stats_summary(claude_path: String) -> Result<GlobalStats
     // Phase 2: Process all session files in parallel
     let file_stats: Vec<SessionFileStats> = session_files
         .par_iter()
-        .filter_map(process_session_file_for_global_stats)
+        .filter_map(|path| with_file_permit(|| process_session_file_for_global_stats(path)))
         .collect();
 
     // Phase 3: Aggregate results
@@ -1394,6 +2019,586 @@ pub async fn get_global_stats_summary(claude_path: String) -> Result<GlobalStats
     Ok(summary)
 }
 
+/// Which sessions a statistics command covers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StatsScope {
+    /// A single project storage directory
+    Project { project_path: String },
+    /// Every project below `claude_path/projects`
+    All { claude_path: String },
+}
+
+impl StatsScope {
+    /// Session files covered by this scope
+    fn session_files(&self) -> Result<Vec<PathBuf>, String> {
+        let dir = match self {
+            StatsScope::Project { project_path } => PathBuf::from(project_path),
+            StatsScope::All { claude_path } => PathBuf::from(claude_path).join("projects"),
+        };
+        if !dir.is_dir() {
+            return Err(format!("Directory not found: {}", dir.display()));
+        }
+
+        Ok(WalkDir::new(&dir)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+            .map(|e| e.path().to_path_buf())
+            .collect())
+    }
+}
+
+/// Width of a time series bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeBucket {
+    Day,
+    /// ISO weeks, starting on Monday
+    Week,
+    Month,
+}
+
+impl TimeBucket {
+    /// First day of the bucket containing `date`
+    fn start_of(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            TimeBucket::Day => date,
+            TimeBucket::Week => {
+                date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+            TimeBucket::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// First day of the following bucket
+    fn next(self, start: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            TimeBucket::Day => start.succ_opt(),
+            TimeBucket::Week => start.checked_add_signed(Duration::days(7)),
+            TimeBucket::Month => start.checked_add_months(Months::new(1)),
+        }
+    }
+}
+
+/// Sum token usage of one session file into local-time buckets
+fn bucket_session_tokens(
+    session_path: &PathBuf,
+    bucket: TimeBucket,
+    offset: FixedOffset,
+) -> Option<BTreeMap<NaiveDate, TokenSummary>> {
+    let mmap = map_session_file(&session_path.to_string_lossy()).ok()?;
+    let mut buckets: BTreeMap<NaiveDate, TokenSummary> = BTreeMap::new();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        // simd-json requires mutable slice
+        let mut line_bytes = mmap[start..end].to_vec();
+        let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) else {
+            continue;
+        };
+        let Ok(message) = ClaudeMessage::try_from(log_entry) else {
+            continue;
+        };
+
+        let usage = extract_token_usage(&message);
+        if usage.input_tokens.is_none()
+            && usage.output_tokens.is_none()
+            && usage.cache_creation_input_tokens.is_none()
+            && usage.cache_read_input_tokens.is_none()
+        {
+            continue;
+        }
+        let Some(timestamp) = parse_message_timestamp(&message.timestamp) else {
+            continue;
+        };
+
+        let local_date = timestamp.with_timezone(&offset).date_naive();
+        buckets
+            .entry(bucket.start_of(local_date))
+            .or_default()
+            .add_usage(&usage);
+    }
+
+    Some(buckets)
+}
+
+/// Token usage over time for a usage trend chart.
+///
+/// # Arguments
+/// * `scope` - A project or all projects
+/// * `bucket` - Day, ISO week or month
+/// * `timezone_offset_minutes` - Local UTC offset used to assign messages to
+///   dates (default UTC)
+///
+/// Returns `(bucket start date, usage)` pairs in date order. Buckets without
+/// usage between the first and last active bucket are included with zero
+/// totals so the chart stays continuous.
+#[tauri::command]
+pub async fn token_timeseries(
+    scope: StatsScope,
+    bucket: TimeBucket,
+    timezone_offset_minutes: Option<i32>,
+) -> Result<Vec<(String, TokenSummary)>, String> {
+    let minutes = timezone_offset_minutes.unwrap_or(0);
+    let offset =
+        timezone_offset(minutes).ok_or_else(|| format!("Invalid timezone offset: {minutes}"))?;
+
+    let totals = scope
+        .session_files()?
+        .par_iter()
+        .filter_map(|path| with_file_permit(|| bucket_session_tokens(path, bucket, offset)))
+        .reduce(BTreeMap::new, |mut acc, buckets| {
+            for (date, summary) in buckets {
+                acc.entry(date).or_default().merge(&summary);
+            }
+            acc
+        });
+
+    let (Some(&first), Some(&last)) = (totals.keys().next(), totals.keys().next_back()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut series = Vec::new();
+    let mut current = Some(first);
+    while let Some(date) = current.filter(|d| *d <= last) {
+        let summary = totals.get(&date).cloned().unwrap_or_default();
+        series.push((date.format("%Y-%m-%d").to_string(), summary));
+        current = bucket.next(date);
+    }
+
+    Ok(series)
+}
+
+/// Timestamps of the messages in one session file, skipping meta entries
+/// and messages without a timestamp
+fn message_timestamps(session_path: &Path) -> Option<Vec<DateTime<Utc>>> {
+    let mmap = map_session_file(&session_path.to_string_lossy()).ok()?;
+    let mut timestamps = Vec::new();
+
+    for (start, end) in find_line_ranges(&mmap) {
+        // simd-json requires mutable slice
+        let mut line_bytes = mmap[start..end].to_vec();
+        let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) else {
+            continue;
+        };
+        if log_entry.message.is_none() || log_entry.is_meta.unwrap_or(false) {
+            continue;
+        }
+        let Some(timestamp) = log_entry
+            .timestamp
+            .as_deref()
+            .and_then(parse_message_timestamp)
+        else {
+            continue;
+        };
+        timestamps.push(timestamp);
+    }
+
+    Some(timestamps)
+}
+
+/// Count the timestamped messages of one session file by local hour of day
+fn hourly_message_counts(session_path: &Path, offset: FixedOffset) -> Option<[usize; 24]> {
+    let mut counts = [0usize; 24];
+    for timestamp in message_timestamps(session_path)? {
+        let hour = timestamp.with_timezone(&offset).hour();
+        counts[hour as usize] += 1;
+    }
+    Some(counts)
+}
+
+/// Messages per hour of day, for a "most active time" bar chart.
+///
+/// # Arguments
+/// * `scope` - A project or all projects
+/// * `timezone_offset_minutes` - Local UTC offset used to assign messages to
+///   hours (default UTC)
+///
+/// Returns 24 counts, index 0 being midnight to 1am local time. Messages
+/// without a timestamp are skipped.
+#[tauri::command]
+pub async fn activity_by_hour(
+    scope: StatsScope,
+    timezone_offset_minutes: Option<i32>,
+) -> Result<[usize; 24], String> {
+    let minutes = timezone_offset_minutes.unwrap_or(0);
+    let offset =
+        timezone_offset(minutes).ok_or_else(|| format!("Invalid timezone offset: {minutes}"))?;
+
+    Ok(scope
+        .session_files()?
+        .par_iter()
+        .filter_map(|path| with_file_permit(|| hourly_message_counts(path, offset)))
+        .reduce(
+            || [0usize; 24],
+            |mut acc, counts| {
+                for (total, count) in acc.iter_mut().zip(counts) {
+                    *total += count;
+                }
+                acc
+            },
+        ))
+}
+
+/// Messages per calendar day of one session, for a per-session timeline.
+///
+/// # Arguments
+/// * `file_path` - Session file
+/// * `timezone_offset_minutes` - Local UTC offset used to assign messages to
+///   dates (default UTC)
+///
+/// Returns `(date, count)` pairs for every day from the first to the last
+/// message, including days without messages, so sessions left open
+/// overnight show their gaps. Messages without a timestamp are skipped.
+#[tauri::command]
+pub async fn session_daily_counts(
+    file_path: String,
+    timezone_offset_minutes: Option<i32>,
+) -> Result<Vec<(String, usize)>, String> {
+    let minutes = timezone_offset_minutes.unwrap_or(0);
+    let offset =
+        timezone_offset(minutes).ok_or_else(|| format!("Invalid timezone offset: {minutes}"))?;
+    let timestamps = message_timestamps(Path::new(&file_path))
+        .ok_or_else(|| format!("Failed to read session file: {file_path}"))?;
+
+    let mut counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for timestamp in timestamps {
+        *counts
+            .entry(timestamp.with_timezone(&offset).date_naive())
+            .or_default() += 1;
+    }
+
+    let (Some(&first), Some(&last)) = (counts.keys().next(), counts.keys().next_back()) else {
+        return Ok(Vec::new());
+    };
+    Ok(first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| {
+            (
+                date.format("%Y-%m-%d").to_string(),
+                counts.get(&date).copied().unwrap_or(0),
+            )
+        })
+        .collect())
+}
+
+/// Upper bounds (exclusive, in characters) of the prompt length buckets;
+/// a final bucket holds everything longer
+const PROMPT_LENGTH_BOUNDS: [usize; 5] = [50, 200, 500, 1000, 2000];
+
+/// Characters of the longest prompt returned as its preview
+const LONGEST_PROMPT_PREVIEW_CHARS: usize = 200;
+
+/// Prompt length statistics, for a "how I prompt" report.
+///
+/// Counts the text the user typed in each prompt, in characters, as
+/// `export_user_prompts` extracts it: tool results, meta messages and
+/// subagent prompts are excluded. Sessions are read in parallel. Lengths
+/// are bucketed by `PROMPT_LENGTH_BOUNDS` for a histogram.
+#[tauri::command]
+pub async fn prompt_stats(scope: StatsScope) -> Result<PromptStats, String> {
+    let session_files = scope.session_files()?;
+
+    // (length, prompt, file) of every prompt
+    let prompts: Vec<(usize, String, &PathBuf)> = session_files
+        .par_iter()
+        .flat_map_iter(|path| {
+            let prompts = with_file_permit(|| {
+                map_session_file(&path.to_string_lossy())
+                    .map(|mmap| user_prompts(&mmap))
+                    .unwrap_or_default()
+            });
+            prompts
+                .into_iter()
+                .map(move |prompt| (prompt.chars().count(), prompt, path))
+        })
+        .collect();
+
+    let mut buckets: Vec<PromptLengthBucket> = std::iter::once(0)
+        .chain(PROMPT_LENGTH_BOUNDS)
+        .zip(PROMPT_LENGTH_BOUNDS.map(Some).into_iter().chain([None]))
+        .map(|(min_chars, max_chars)| PromptLengthBucket {
+            min_chars,
+            max_chars,
+            count: 0,
+        })
+        .collect();
+    for (length, _, _) in &prompts {
+        let index = PROMPT_LENGTH_BOUNDS.partition_point(|bound| bound <= length);
+        buckets[index].count += 1;
+    }
+
+    let total_chars: usize = prompts.iter().map(|(length, _, _)| length).sum();
+    let longest = prompts.iter().max_by_key(|(length, _, _)| *length);
+
+    Ok(PromptStats {
+        prompt_count: prompts.len(),
+        total_chars,
+        average_length: if prompts.is_empty() {
+            0.0
+        } else {
+            total_chars as f64 / prompts.len() as f64
+        },
+        longest_length: longest.map_or(0, |(length, _, _)| *length),
+        longest_prompt_preview: longest
+            .map(|(_, prompt, _)| prompt.chars().take(LONGEST_PROMPT_PREVIEW_CHARS).collect()),
+        longest_prompt_file: longest.map(|(_, _, path)| path.to_string_lossy().to_string()),
+        buckets,
+        sessions_scanned: session_files.len(),
+    })
+}
+
+/// Pricing entry for a model: the longest key contained in its name, so
+/// `claude-opus-4-5` wins over `claude-opus-4`
+fn pricing_for<'a>(
+    pricing: &'a BTreeMap<String, ModelPricing>,
+    model: &str,
+) -> Option<&'a ModelPricing> {
+    let model = model.to_lowercase();
+    pricing
+        .iter()
+        .filter(|(key, _)| model.contains(&key.to_lowercase()))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, price)| price)
+}
+
+/// Sum per-model usage of messages in one session file, limited to
+/// timestamps within `range` (inclusive) when given
+fn session_model_usage(
+    session_path: &Path,
+    range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+) -> Option<HashMap<String, ModelCost>> {
+    let mmap = map_session_file(&session_path.to_string_lossy()).ok()?;
+    let mut by_model: HashMap<String, ModelCost> = HashMap::new();
+
+    for (line_start, line_end) in find_line_ranges(&mmap) {
+        // simd-json requires mutable slice
+        let mut line_bytes = mmap[line_start..line_end].to_vec();
+        let Some(log_entry) = parse_raw_log_entry_simd(&mut line_bytes) else {
+            continue;
+        };
+        let Ok(message) = ClaudeMessage::try_from(log_entry) else {
+            continue;
+        };
+        if let Some((start, end)) = range {
+            if !parse_message_timestamp(&message.timestamp).is_some_and(|t| t >= start && t <= end)
+            {
+                continue;
+            }
+        }
+
+        let usage = extract_token_usage(&message);
+        if usage.input_tokens.is_none()
+            && usage.output_tokens.is_none()
+            && usage.cache_creation_input_tokens.is_none()
+            && usage.cache_read_input_tokens.is_none()
+        {
+            continue;
+        }
+
+        let model = message.model.as_deref().unwrap_or(UNKNOWN_MODEL);
+        by_model
+            .entry(model.to_string())
+            .or_insert_with(|| ModelCost {
+                model: model.to_string(),
+                ..ModelCost::default()
+            })
+            .add_usage(&usage);
+    }
+
+    Some(by_model)
+}
+
+/// Total spend over a date range, for budget tracking.
+///
+/// # Arguments
+/// * `scope` - A project or all projects
+/// * `start`, `end` - Inclusive RFC 3339 bounds on message timestamps
+/// * `pricing` - USD per million tokens, keyed by a model name fragment
+///   (e.g. `claude-sonnet-4`); the longest fragment contained in a model
+///   name applies
+///
+/// Models without a pricing entry are listed with their tokens and
+/// `priced: false`. Sessions are scanned in parallel; files that cannot be
+/// read are listed in `skipped_sessions`.
+#[tauri::command]
+pub async fn cost_over_range(
+    scope: StatsScope,
+    start: String,
+    end: String,
+    pricing: BTreeMap<String, ModelPricing>,
+) -> Result<CostSummary, String> {
+    let parse_bound = |value: &str| {
+        DateTime::parse_from_rfc3339(value)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| format!("Invalid date '{value}': {e}"))
+    };
+    let (start, end) = (parse_bound(&start)?, parse_bound(&end)?);
+    if start > end {
+        return Err("Start of the date range is after its end".to_string());
+    }
+
+    let session_files = scope.session_files()?;
+    let results: Vec<(&PathBuf, Option<HashMap<String, ModelCost>>)> = session_files
+        .par_iter()
+        .map(|path| {
+            (
+                path,
+                with_file_permit(|| session_model_usage(path, Some((start, end)))),
+            )
+        })
+        .collect();
+
+    let mut by_model: HashMap<String, ModelCost> = HashMap::new();
+    let mut skipped_sessions = Vec::new();
+    for (path, usage) in results {
+        let Some(usage) = usage else {
+            skipped_sessions.push(path.to_string_lossy().to_string());
+            continue;
+        };
+        for (model, cost) in usage {
+            by_model
+                .entry(model)
+                .or_insert_with(|| ModelCost {
+                    model: cost.model.clone(),
+                    ..ModelCost::default()
+                })
+                .merge(&cost);
+        }
+    }
+
+    let mut by_model: Vec<ModelCost> = by_model.into_values().collect();
+    for cost in &mut by_model {
+        cost.apply_pricing(pricing_for(&pricing, &cost.model));
+    }
+    by_model.sort_by(|a, b| {
+        b.cost_usd
+            .total_cmp(&a.cost_usd)
+            .then_with(|| a.model.cmp(&b.model))
+    });
+
+    Ok(CostSummary {
+        total_cost_usd: by_model.iter().map(|m| m.cost_usd).sum(),
+        sessions_scanned: session_files.len() - skipped_sessions.len(),
+        skipped_sessions,
+        by_model,
+    })
+}
+
+/// Default number of sessions returned by `sessions_by_cost`
+const DEFAULT_SESSIONS_BY_COST_LIMIT: usize = 20;
+
+/// Estimate what a session cost, including the subagent transcripts in its
+/// companion directory. None if the session file cannot be read.
+fn estimate_session_cost(
+    session_path: &Path,
+    pricing: &BTreeMap<String, ModelPricing>,
+) -> Option<SessionCost> {
+    let mut by_model = session_model_usage(session_path, None)?;
+    let subagents_dir = session_path.with_extension("").join("subagents");
+    for subagent in WalkDir::new(subagents_dir)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+    {
+        for (model, cost) in session_model_usage(subagent.path(), None).unwrap_or_default() {
+            by_model
+                .entry(model)
+                .or_insert_with(|| ModelCost {
+                    model: cost.model.clone(),
+                    ..ModelCost::default()
+                })
+                .merge(&cost);
+        }
+    }
+
+    let mut by_model: Vec<ModelCost> = by_model.into_values().collect();
+    for cost in &mut by_model {
+        cost.apply_pricing(pricing_for(pricing, &cost.model));
+    }
+    by_model.sort_by(|a, b| {
+        b.cost_usd
+            .total_cmp(&a.cost_usd)
+            .then_with(|| a.model.cmp(&b.model))
+    });
+
+    Some(SessionCost {
+        session_id: session_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        file_path: session_path.to_string_lossy().to_string(),
+        title: None,
+        cost_usd: by_model.iter().map(|m| m.cost_usd).sum(),
+        unpriced_models: by_model
+            .iter()
+            .filter(|m| !m.priced)
+            .map(|m| m.model.clone())
+            .collect(),
+        by_model,
+    })
+}
+
+/// The most expensive sessions of a project, for cost optimization.
+///
+/// # Arguments
+/// * `project_path` - Project storage directory
+/// * `pricing` - USD per million tokens, keyed by a model name fragment, as
+///   in `cost_over_range`
+/// * `limit` - Sessions returned (default 20)
+///
+/// Each session's cost includes its subagents. Sessions are estimated in
+/// parallel; titles are only derived for the returned ones. Sessions with
+/// usage from a model missing in `pricing` are also listed separately, since
+/// their cost is a lower bound.
+#[tauri::command]
+pub async fn sessions_by_cost(
+    project_path: String,
+    pricing: BTreeMap<String, ModelPricing>,
+    limit: Option<usize>,
+) -> Result<SessionCostRanking, String> {
+    let project_dir = PathBuf::from(&project_path);
+    if !project_dir.is_dir() {
+        return Err(format!("Project directory not found: {project_path}"));
+    }
+    let session_files: Vec<PathBuf> = WalkDir::new(&project_dir)
+        .min_depth(1)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .map(walkdir::DirEntry::into_path)
+        .collect();
+
+    let mut costs: Vec<SessionCost> = session_files
+        .par_iter()
+        .filter_map(|path| with_file_permit(|| estimate_session_cost(path, &pricing)))
+        .collect();
+    costs.sort_by(|a, b| {
+        b.cost_usd
+            .total_cmp(&a.cost_usd)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+
+    let with_title = |mut cost: SessionCost| {
+        cost.title = derive_title_from_file(&cost.file_path).ok();
+        cost
+    };
+    let unpriced_sessions = costs
+        .iter()
+        .filter(|c| !c.unpriced_models.is_empty())
+        .cloned()
+        .map(with_title)
+        .collect();
+    costs.truncate(limit.unwrap_or(DEFAULT_SESSIONS_BY_COST_LIMIT));
+
+    Ok(SessionCostRanking {
+        sessions: costs.into_par_iter().map(with_title).collect(),
+        unpriced_sessions,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1696,6 +2901,9 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
 
         let usage = extract_token_usage(&msg);
@@ -1745,6 +2953,9 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
 
         let usage = extract_token_usage(&msg);
@@ -1792,6 +3003,9 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
 
         let usage = extract_token_usage(&msg);
@@ -1835,6 +3049,9 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
 
         let usage = extract_token_usage(&msg);
@@ -1876,10 +3093,543 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
 
         let usage = extract_token_usage(&msg);
         assert!(usage.input_tokens.is_none());
         assert!(usage.output_tokens.is_none());
     }
+
+    #[test]
+    fn test_count_fence_languages() {
+        let mut counts = HashMap::new();
+        count_fence_languages(
+            "Here:\n```rust\nfn main() {}\n```\nand\n```ts\nconst a = 1;\n```\n```\nplain\n```\n```cobol\n```",
+            &mut counts,
+        );
+        assert_eq!(counts.get("Rust"), Some(&1));
+        assert_eq!(counts.get("TypeScript"), Some(&1));
+        assert_eq!(counts.get(OTHER_LANGUAGE), Some(&1));
+        assert_eq!(counts.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_project_language_stats() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let lines = [
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Write","input":{"file_path":"/p/src/main.rs","content":""}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Edit","input":{"file_path":"/p/src/lib.RS"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Read","input":{"file_path":"/p/app.py"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"```python\nprint(1)\n```"},{"type":"tool_use","id":"t4","name":"Write","input":{"file_path":"/p/data.xyz"}}]}}"#,
+        ];
+        fs::write(temp_dir.path().join("a.jsonl"), lines.join("\n")).unwrap();
+
+        let stats = project_language_stats(temp_dir.path().to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            stats,
+            vec![
+                ("Rust".to_string(), 2),
+                ("Python".to_string(), 1),
+                (OTHER_LANGUAGE.to_string(), 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_project_reading_stats() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let blob = "A".repeat(120);
+        let a = [
+            r#"{"type":"user","message":{"role":"user","content":"please ignore these five words"}}"#.to_string(),
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Here is the fix:\n```rust\nfn main() {}\n```"},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo build with many words"}}]}}"#.to_string(),
+            format!(r#"{{"type":"assistant","message":{{"role":"assistant","content":"Encoded {blob} done"}}}}"#),
+        ];
+        fs::write(temp_dir.path().join("a.jsonl"), a.join("\n")).unwrap();
+        fs::write(
+            temp_dir.path().join("b.jsonl"),
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Read","input":{}}]}}"#,
+        )
+        .unwrap();
+
+        let stats = project_reading_stats(temp_dir.path().to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        // "Here is the fix:" (4) + code block tokens (5) + "Encoded done" (2)
+        assert_eq!(stats.total_words, 11);
+        assert_eq!(stats.reading_time_minutes, 1);
+        assert_eq!(stats.code_blocks, 1);
+        assert_eq!(stats.tool_calls, 2);
+        assert_eq!(stats.sessions.len(), 2);
+        assert_eq!(stats.sessions[0].session_id, "a");
+        assert_eq!(stats.sessions[1].tool_calls, 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_timeseries_fills_empty_buckets() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project = temp_dir.path().join("projects").join("-tmp-app");
+        fs::create_dir_all(&project).unwrap();
+        let line = |ts: &str, input: u32, output: u32| {
+            format!(
+                r#"{{"type":"assistant","uuid":"u-{ts}","sessionId":"s1","timestamp":"{ts}","message":{{"role":"assistant","content":[],"usage":{{"input_tokens":{input},"output_tokens":{output}}}}}}}"#
+            )
+        };
+        fs::write(
+            project.join("a.jsonl"),
+            [
+                line("2025-01-01T10:00:00Z", 10, 5),
+                line("2025-01-01T23:30:00Z", 1, 1),
+                line("2025-01-03T08:00:00Z", 2, 2),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let scope = StatsScope::All {
+            claude_path: temp_dir.path().to_string_lossy().to_string(),
+        };
+        let series = token_timeseries(scope.clone(), TimeBucket::Day, None)
+            .await
+            .unwrap();
+        let dates: Vec<&str> = series.iter().map(|(d, _)| d.as_str()).collect();
+        assert_eq!(dates, vec!["2025-01-01", "2025-01-02", "2025-01-03"]);
+        assert_eq!(series[0].1.total_tokens, 17);
+        assert_eq!(series[0].1.message_count, 2);
+        assert_eq!(series[1].1, TokenSummary::default());
+
+        // UTC+1 moves the 23:30 message to the next day
+        let series = token_timeseries(scope.clone(), TimeBucket::Day, Some(60))
+            .await
+            .unwrap();
+        assert_eq!(series[1].0, "2025-01-02");
+        assert_eq!(series[1].1.total_tokens, 2);
+
+        let series = token_timeseries(scope, TimeBucket::Week, None)
+            .await
+            .unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].0, "2024-12-30");
+        assert_eq!(series[0].1.total_tokens, 21);
+    }
+
+    #[tokio::test]
+    async fn test_cache_stats_per_model() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let line = |model: &str, input: u32, creation: u32, read: u32| {
+            format!(
+                r#"{{"type":"assistant","uuid":"u-{model}-{read}","sessionId":"s1","timestamp":"2025-01-01T10:00:00Z","message":{{"role":"assistant","model":"{model}","content":[],"usage":{{"input_tokens":{input},"output_tokens":5,"cache_creation_input_tokens":{creation},"cache_read_input_tokens":{read}}}}}}}"#
+            )
+        };
+        let session = temp_dir.path().join("s1.jsonl");
+        fs::write(
+            &session,
+            [
+                line("claude-opus", 10, 90, 0),
+                line("claude-opus", 10, 0, 90),
+                line("claude-haiku", 5, 0, 15),
+                r#"{"type":"user","uuid":"u1","sessionId":"s1","timestamp":"2025-01-01T10:00:00Z","message":{"role":"user","content":"hi","usage":{"input_tokens":1000}}}"#.to_string(),
+                r#"{"type":"assistant","uuid":"u2","sessionId":"s1","timestamp":"2025-01-01T10:00:00Z","message":{"role":"assistant","content":[]}}"#.to_string(),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let stats = cache_stats(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(stats.input_tokens, 25);
+        assert_eq!(stats.cache_creation_tokens, 90);
+        assert_eq!(stats.cache_read_tokens, 105);
+        assert!((stats.hit_ratio - 105.0 / 220.0).abs() < f64::EPSILON);
+        assert_eq!(stats.by_model.len(), 2);
+        assert_eq!(stats.by_model[0].model, "claude-opus");
+        assert_eq!(stats.by_model[0].message_count, 2);
+        assert!((stats.by_model[0].hit_ratio - 0.45).abs() < f64::EPSILON);
+        assert!((stats.by_model[1].hit_ratio - 0.75).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_edited_extensions_counts_distinct_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let tool_use = |name: &str, path: &str| {
+            format!(
+                r#"{{"type":"assistant","message":{{"role":"assistant","content":[{{"type":"tool_use","id":"t","name":"{name}","input":{{"file_path":"{path}"}}}}]}}}}"#
+            )
+        };
+        let session = temp_dir.path().join("s.jsonl");
+        fs::write(
+            &session,
+            [
+                tool_use("Write", "/app/src/main.rs"),
+                tool_use("Edit", "/app/src/main.rs"),
+                tool_use("MultiEdit", "/app/src/lib.RS"),
+                tool_use("Edit", "/app/Makefile"),
+                tool_use("Write", "/app/README.md"),
+                tool_use("Read", "/app/Cargo.toml"),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let extensions = edited_extensions(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            extensions,
+            vec![
+                ("rs".to_string(), 2),
+                (NO_EXTENSION.to_string(), 1),
+                ("md".to_string(), 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_message_length_series_by_role() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let session = temp_dir.path().join("s.jsonl");
+        fs::write(
+            &session,
+            [
+                r#"{"type":"user","message":{"role":"user","content":"héllo"}}"#,
+                r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"abc"},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"ls -la"}},{"type":"text","text":"de"}]}}"#,
+                r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"lots of output"}]}}"#,
+                r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"Caveat"}}"#,
+                r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":""}]}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        let path = session.to_string_lossy().to_string();
+
+        assert_eq!(
+            message_length_series(path.clone(), None).await.unwrap(),
+            vec![5, 5, 0]
+        );
+        assert_eq!(
+            message_length_series(path, Some("assistant".to_string()))
+                .await
+                .unwrap(),
+            vec![5, 0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_turn_balance_series() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let session = temp_dir.path().join("s.jsonl");
+        fs::write(
+            &session,
+            [
+                r#"{"type":"user","message":{"role":"user","content":"fix it"}}"#,
+                r#"{"type":"assistant","message":{"role":"assistant","id":"msg_1","content":[{"type":"text","text":"Looking"}]}}"#,
+                r#"{"type":"assistant","message":{"role":"assistant","id":"msg_1","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{}}]}}"#,
+                r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}"#,
+                r#"{"type":"assistant","message":{"role":"assistant","id":"msg_2","content":[{"type":"text","text":"Done"}]}}"#,
+                r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"Caveat"}}"#,
+                r#"{"type":"assistant","message":{"role":"assistant","id":"msg_3","content":[{"type":"text","text":"Also"}]}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let points = turn_balance_series(session.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        let summary: Vec<(usize, usize, usize, i64)> = points
+            .iter()
+            .map(|p| (p.line_index, p.user_messages, p.assistant_messages, p.delta))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(0, 1, 0, -1), (1, 1, 1, 0), (4, 1, 2, 1), (6, 1, 3, 2)]
+        );
+        assert!((points[3].assistant_share - 0.75).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_word_frequencies() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let session = temp_dir.path().join("s.jsonl");
+        fs::write(
+            &session,
+            [
+                r#"{"type":"user","message":{"role":"user","content":"Fix the Parser, the parser's tests too. 2024"}}"#,
+                r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"The parser fix:\n```rust\nfn parser() {}\n```\nDone"},{"type":"tool_use","id":"t1","name":"Edit","input":{"new_string":"parser parser"}}]}}"#,
+                r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"parser"}]}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let frequencies = word_frequencies(
+            session.to_string_lossy().to_string(),
+            3,
+            vec!["THE".to_string(), "too".to_string()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            frequencies,
+            vec![
+                ("fix".to_string(), 2),
+                ("parser".to_string(), 2),
+                ("done".to_string(), 1),
+                ("parser's".to_string(), 1),
+                ("tests".to_string(), 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cost_over_range() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let line = |ts: &str, model: &str, input: u32, output: u32| {
+            format!(
+                r#"{{"type":"assistant","uuid":"u-{ts}","sessionId":"s1","timestamp":"{ts}","message":{{"role":"assistant","model":"{model}","content":"ok","usage":{{"input_tokens":{input},"output_tokens":{output}}}}}}}"#
+            )
+        };
+        fs::write(
+            temp_dir.path().join("a.jsonl"),
+            [
+                line(
+                    "2025-01-31T23:00:00Z",
+                    "claude-opus-4-5-20251101",
+                    1_000_000,
+                    0,
+                ),
+                line(
+                    "2025-02-10T00:00:00Z",
+                    "claude-opus-4-5-20251101",
+                    1_000_000,
+                    100_000,
+                ),
+                line(
+                    "2025-02-11T00:00:00Z",
+                    "claude-opus-4-20250514",
+                    0,
+                    1_000_000,
+                ),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("b.jsonl"),
+            line("2025-02-12T00:00:00Z", "mystery-model", 10, 10),
+        )
+        .unwrap();
+
+        let price = |input, output| ModelPricing {
+            input,
+            output,
+            ..ModelPricing::default()
+        };
+        let pricing = BTreeMap::from([
+            ("claude-opus-4-5".to_string(), price(5.0, 25.0)),
+            ("claude-opus-4".to_string(), price(15.0, 75.0)),
+        ]);
+
+        let summary = cost_over_range(
+            StatsScope::Project {
+                project_path: temp_dir.path().to_string_lossy().to_string(),
+            },
+            "2025-02-01T00:00:00Z".to_string(),
+            "2025-02-28T23:59:59Z".to_string(),
+            pricing,
+        )
+        .await
+        .unwrap();
+
+        let costs: Vec<(&str, f64, bool)> = summary
+            .by_model
+            .iter()
+            .map(|m| (m.model.as_str(), m.cost_usd, m.priced))
+            .collect();
+        assert_eq!(
+            costs,
+            vec![
+                ("claude-opus-4-20250514", 75.0, true),
+                ("claude-opus-4-5-20251101", 7.5, true),
+                ("mystery-model", 0.0, false),
+            ]
+        );
+        assert!((summary.total_cost_usd - 82.5).abs() < 1e-9);
+        assert_eq!(summary.sessions_scanned, 2);
+        assert!(summary.skipped_sessions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_activity_by_hour_uses_local_time() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let line = |ts: &str| {
+            format!(
+                r#"{{"type":"user","uuid":"u-{ts}","sessionId":"s1","timestamp":"{ts}","message":{{"role":"user","content":"hi"}}}}"#
+            )
+        };
+        fs::write(
+            temp_dir.path().join("a.jsonl"),
+            [
+                line("2025-01-01T09:15:00Z"),
+                line("2025-01-01T09:45:00Z"),
+                line("2025-01-01T23:30:00Z"),
+                r#"{"type":"user","message":{"role":"user","content":"no timestamp"}}"#.to_string(),
+                r#"{"type":"summary","summary":"t","timestamp":"2025-01-01T09:00:00Z"}"#
+                    .to_string(),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        let scope = StatsScope::Project {
+            project_path: temp_dir.path().to_string_lossy().to_string(),
+        };
+
+        let hours = activity_by_hour(scope.clone(), None).await.unwrap();
+        assert_eq!(hours[9], 2);
+        assert_eq!(hours[23], 1);
+        assert_eq!(hours.iter().sum::<usize>(), 3);
+
+        // UTC+1 wraps 23:30 to midnight
+        let hours = activity_by_hour(scope, Some(60)).await.unwrap();
+        assert_eq!(hours[10], 2);
+        assert_eq!(hours[0], 1);
+    }
+
+    #[tokio::test]
+    async fn test_session_daily_counts_fills_gaps() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("a.jsonl");
+        let line = |ts: &str| {
+            format!(
+                r#"{{"type":"user","uuid":"u-{ts}","sessionId":"s1","timestamp":"{ts}","message":{{"role":"user","content":"hi"}}}}"#
+            )
+        };
+        fs::write(
+            &path,
+            [
+                line("2025-01-01T22:30:00Z"),
+                line("2025-01-01T23:30:00Z"),
+                line("2025-01-03T10:00:00Z"),
+                r#"{"type":"user","message":{"role":"user","content":"no timestamp"}}"#.to_string(),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        let file_path = path.to_string_lossy().to_string();
+
+        let days = session_daily_counts(file_path.clone(), None).await.unwrap();
+        assert_eq!(
+            days,
+            vec![
+                ("2025-01-01".to_string(), 2),
+                ("2025-01-02".to_string(), 0),
+                ("2025-01-03".to_string(), 1),
+            ]
+        );
+
+        // UTC+1 moves 23:30 past midnight
+        let days = session_daily_counts(file_path, Some(60)).await.unwrap();
+        assert_eq!(days[0], ("2025-01-01".to_string(), 1));
+        assert_eq!(days[1], ("2025-01-02".to_string(), 1));
+    }
+
+    #[tokio::test]
+    async fn test_prompt_stats() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let prompt = |text: &str| {
+            serde_json::json!({
+                "type": "user",
+                "message": { "role": "user", "content": text }
+            })
+            .to_string()
+        };
+        let long = "x".repeat(600);
+        fs::write(
+            temp_dir.path().join("a.jsonl"),
+            [
+                prompt("fix it"),
+                r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"lots of output"}]}}"#.to_string(),
+                prompt(&long),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("b.jsonl"), prompt(&"y".repeat(100))).unwrap();
+
+        let stats = prompt_stats(StatsScope::Project {
+            project_path: temp_dir.path().to_string_lossy().to_string(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(stats.prompt_count, 3);
+        assert_eq!(stats.total_chars, 706);
+        assert_eq!(stats.longest_length, 600);
+        assert!(stats.longest_prompt_file.unwrap().ends_with("a.jsonl"));
+        assert_eq!(stats.longest_prompt_preview.unwrap().chars().count(), 200);
+        let counts: Vec<usize> = stats.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 1, 0, 1, 0, 0]);
+        assert_eq!(stats.buckets[5].max_chars, None);
+        assert_eq!(stats.sessions_scanned, 2);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_by_cost() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let usage = |model: &str, output: u32| {
+            format!(
+                r#"{{"type":"assistant","uuid":"u-{model}-{output}","sessionId":"s","timestamp":"2025-01-01T00:00:00Z","message":{{"role":"assistant","model":"{model}","content":"hi","usage":{{"input_tokens":0,"output_tokens":{output}}}}}}}"#
+            )
+        };
+        fs::write(
+            temp_dir.path().join("cheap.jsonl"),
+            usage("claude-sonnet-4", 1_000_000),
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("pricey.jsonl"),
+            usage("claude-sonnet-4", 2_000_000),
+        )
+        .unwrap();
+        // Subagent usage counts toward its parent session
+        let subagents = temp_dir.path().join("cheap").join("subagents");
+        fs::create_dir_all(&subagents).unwrap();
+        fs::write(subagents.join("agent-1.jsonl"), usage("mystery-model", 10)).unwrap();
+
+        let pricing = BTreeMap::from([(
+            "claude-sonnet-4".to_string(),
+            ModelPricing {
+                input: 3.0,
+                output: 15.0,
+                cache_write: 3.75,
+                cache_read: 0.3,
+            },
+        )]);
+        let ranking = sessions_by_cost(
+            temp_dir.path().to_string_lossy().to_string(),
+            pricing,
+            Some(1),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(ranking.sessions.len(), 1);
+        assert_eq!(ranking.sessions[0].session_id, "pricey");
+        assert!((ranking.sessions[0].cost_usd - 30.0).abs() < 1e-9);
+        assert_eq!(ranking.unpriced_sessions.len(), 1);
+        assert_eq!(ranking.unpriced_sessions[0].session_id, "cheap");
+        assert_eq!(
+            ranking.unpriced_sessions[0].unpriced_models,
+            vec!["mystery-model"]
+        );
+    }
 }
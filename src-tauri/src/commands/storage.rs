@@ -0,0 +1,1310 @@
+//! Storage reporting commands
+//!
+//! Reports how much disk space sessions take, split between the session
+//! JSONL files and their companion directories (same name without `.jsonl`).
+//! Also lists and purges soft-deleted sessions in `~/.claude/.trash`,
+//! archives whole project directories as `.tar.gz` files, finds
+//! byte-identical session copies and companion directories whose session
+//! file is gone.
+
+use super::error::CommandError;
+use super::file_limit::with_file_permit;
+use super::fs_utils::{atomic_rename, content_hash, ensure_within_root};
+use super::session::{map_session_file, validate_delete_path};
+use chrono::{DateTime, Duration, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+/// Default number of sessions returned in a project disk usage breakdown
+const DEFAULT_LARGEST_SESSIONS_LIMIT: usize = 20;
+
+/// Trash directory name inside `~/.claude`
+const TRASH_DIR_NAME: &str = ".trash";
+
+/// Extension of the sidecar file recording where a trashed entry came from
+const TRASH_INFO_EXTENSION: &str = "trashinfo";
+
+/// Disk usage of a single session (JSONL file + companion directory)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDiskUsage {
+    pub file_path: String,
+    pub session_id: String,
+    pub jsonl_bytes: u64,
+    pub companion_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Disk usage breakdown for a whole project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDiskUsage {
+    pub project_path: String,
+    pub session_count: usize,
+    pub jsonl_bytes: u64,
+    pub companion_bytes: u64,
+    pub total_bytes: u64,
+    /// Largest sessions first
+    pub largest_sessions: Vec<SessionDiskUsage>,
+}
+
+/// A soft-deleted session (or companion directory) in the trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub trash_path: String,
+    /// Where the entry lived before deletion, if recorded
+    pub original_path: Option<String>,
+    /// RFC 3339 deletion time; falls back to the entry's modification time
+    pub deleted_at: String,
+    pub size_bytes: u64,
+}
+
+/// Sidecar metadata written next to each trashed entry (`<name>.trashinfo`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrashInfo {
+    original_path: String,
+    deleted_at: String,
+}
+
+/// A trash entry `empty_trash` left in place
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedTrashEntry {
+    pub trash_path: String,
+    pub reason: String,
+}
+
+/// Outcome of purging the trash
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrashPurgeResult {
+    /// Entries permanently removed
+    pub removed: usize,
+    /// Entries that could not be checked or removed; the purge went on
+    /// without them
+    pub skipped: Vec<SkippedTrashEntry>,
+}
+
+/// Outcome of archiving one project directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectArchive {
+    pub project_path: String,
+    /// Written `.tar.gz`, None if archiving failed
+    pub archive_path: Option<String>,
+    pub archive_bytes: u64,
+    /// Regular files packed into the archive
+    pub file_count: usize,
+    /// Uncompressed size of the packed files
+    pub total_bytes: u64,
+    /// Whether the original project directory was removed
+    pub deleted: bool,
+    pub error: Option<String>,
+}
+
+/// Session files with identical contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateSet {
+    /// Size of each copy
+    pub size_bytes: u64,
+    /// Paths of all copies, sorted
+    pub paths: Vec<String>,
+    /// Space freed by keeping a single copy
+    pub reclaimable_bytes: u64,
+}
+
+/// A companion directory without its session file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedCompanion {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// Outcome of removing orphaned companion directories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanCleanupResult {
+    pub removed: Vec<String>,
+    pub bytes_freed: u64,
+}
+
+/// Outcome of removing duplicate session copies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupeResult {
+    pub kept: String,
+    pub removed: Vec<String>,
+    pub bytes_freed: u64,
+}
+
+/// Sum the sizes of all regular files below `dir` (symlinks are not followed)
+pub(crate) fn directory_size(dir: &Path) -> u64 {
+    WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Compute disk usage for a session file and its companion directory.
+/// Returns None if the JSONL file cannot be read.
+pub(crate) fn compute_session_disk_usage(file_path: &Path) -> Option<SessionDiskUsage> {
+    let metadata = fs::symlink_metadata(file_path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+
+    let companion_dir = file_path.with_extension("");
+    let companion_bytes = if companion_dir.is_dir() {
+        directory_size(&companion_dir)
+    } else {
+        0
+    };
+
+    let jsonl_bytes = metadata.len();
+    let session_id = file_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    Some(SessionDiskUsage {
+        file_path: file_path.to_string_lossy().to_string(),
+        session_id,
+        jsonl_bytes,
+        companion_bytes,
+        total_bytes: jsonl_bytes + companion_bytes,
+    })
+}
+
+/// Get the disk usage of a single session
+#[tauri::command]
+pub async fn session_disk_usage(file_path: String) -> Result<SessionDiskUsage, String> {
+    compute_session_disk_usage(Path::new(&file_path))
+        .ok_or_else(|| format!("Cannot read session file: {file_path}"))
+}
+
+/// Get a "what's taking space" breakdown for a project.
+///
+/// Session files are the `*.jsonl` files directly inside the project directory;
+/// everything inside a session's companion directory (including subagent logs)
+/// is attributed to that session.
+#[tauri::command]
+pub async fn project_disk_usage(
+    project_path: String,
+    limit: Option<usize>,
+) -> Result<ProjectDiskUsage, String> {
+    let project_path_buf = PathBuf::from(&project_path);
+    if !project_path_buf.is_dir() {
+        return Err(format!("Project directory not found: {project_path}"));
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LARGEST_SESSIONS_LIMIT);
+
+    let session_files: Vec<PathBuf> = WalkDir::new(&project_path_buf)
+        .min_depth(1)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+
+    let mut sessions: Vec<SessionDiskUsage> = session_files
+        .par_iter()
+        .filter_map(|path| compute_session_disk_usage(path))
+        .collect();
+
+    let jsonl_bytes: u64 = sessions.iter().map(|s| s.jsonl_bytes).sum();
+    let companion_bytes: u64 = sessions.iter().map(|s| s.companion_bytes).sum();
+    let session_count = sessions.len();
+
+    sessions.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes));
+    sessions.truncate(limit);
+
+    Ok(ProjectDiskUsage {
+        project_path,
+        session_count,
+        jsonl_bytes,
+        companion_bytes,
+        total_bytes: jsonl_bytes + companion_bytes,
+        largest_sessions: sessions,
+    })
+}
+
+/// Resolve `~/.claude/.trash`
+fn default_trash_dir() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir().ok_or("Cannot determine home directory")?;
+    Ok(home_dir.join(".claude").join(TRASH_DIR_NAME))
+}
+
+/// Sidecar path recording the origin of a trashed entry
+fn trash_info_path(entry_path: &Path) -> PathBuf {
+    let mut name = entry_path.as_os_str().to_os_string();
+    name.push(format!(".{TRASH_INFO_EXTENSION}"));
+    PathBuf::from(name)
+}
+
+/// Read a trashed entry and its sidecar. Symlinks and sidecars themselves
+/// are not entries.
+fn read_trash_entry(path: &Path) -> Option<TrashEntry> {
+    if path.extension().and_then(|s| s.to_str()) == Some(TRASH_INFO_EXTENSION) {
+        return None;
+    }
+    let metadata = fs::symlink_metadata(path).ok()?;
+    if metadata.file_type().is_symlink() {
+        return None;
+    }
+
+    let size_bytes = if metadata.is_dir() {
+        directory_size(path)
+    } else {
+        metadata.len()
+    };
+
+    let info: Option<TrashInfo> = fs::read_to_string(trash_info_path(path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let (original_path, deleted_at) = if let Some(info) = info {
+        (Some(info.original_path), info.deleted_at)
+    } else {
+        let modified = metadata.modified().ok()?;
+        (None, DateTime::<Utc>::from(modified).to_rfc3339())
+    };
+
+    Some(TrashEntry {
+        trash_path: path.to_string_lossy().to_string(),
+        original_path,
+        deleted_at,
+        size_bytes,
+    })
+}
+
+/// List the entries of a trash directory, most recently deleted first.
+/// A missing trash directory is an empty trash.
+fn list_trash_in(trash_dir: &Path) -> Result<Vec<TrashEntry>, String> {
+    if !trash_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries: Vec<TrashEntry> = fs::read_dir(trash_dir)
+        .map_err(|e| format!("Failed to read trash directory: {e}"))?
+        .filter_map(std::result::Result::ok)
+        .filter_map(|e| read_trash_entry(&e.path()))
+        .collect();
+
+    entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    Ok(entries)
+}
+
+/// Permanently remove one trash entry and its sidecar if it was deleted
+/// before `cutoff`. Returns whether it was removed.
+fn purge_trash_entry(
+    trash_dir: &Path,
+    entry: &TrashEntry,
+    cutoff: Option<DateTime<Utc>>,
+) -> Result<bool, String> {
+    if let Some(cutoff) = cutoff {
+        let deleted_at = DateTime::parse_from_rfc3339(&entry.deleted_at)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| format!("Invalid deletion time: {e}"))?;
+        if deleted_at >= cutoff {
+            return Ok(false);
+        }
+    }
+
+    let path = ensure_within_root(Path::new(&entry.trash_path), trash_dir)?;
+
+    if path.is_dir() {
+        fs::remove_dir_all(&path)
+    } else {
+        fs::remove_file(&path)
+    }
+    .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+
+    let info_path = trash_info_path(&path);
+    if info_path.is_file() {
+        fs::remove_file(&info_path)
+            .map_err(|e| format!("Failed to remove {}: {e}", info_path.display()))?;
+    }
+    Ok(true)
+}
+
+/// Permanently remove trash entries deleted before `cutoff` (all if None).
+/// An entry that cannot be checked or removed is skipped and reported.
+fn empty_trash_in(
+    trash_dir: &Path,
+    cutoff: Option<DateTime<Utc>>,
+) -> Result<TrashPurgeResult, String> {
+    let mut result = TrashPurgeResult::default();
+
+    for entry in list_trash_in(trash_dir)? {
+        match purge_trash_entry(trash_dir, &entry, cutoff) {
+            Ok(removed) => result.removed += usize::from(removed),
+            Err(reason) => result.skipped.push(SkippedTrashEntry {
+                trash_path: entry.trash_path,
+                reason,
+            }),
+        }
+    }
+
+    Ok(result)
+}
+
+/// List soft-deleted sessions in `~/.claude/.trash`, most recent first
+#[tauri::command]
+pub async fn list_trash() -> Result<Vec<TrashEntry>, String> {
+    list_trash_in(&default_trash_dir()?)
+}
+
+/// Permanently purge trashed sessions.
+///
+/// # Arguments
+/// * `older_than_days` - Only purge entries deleted more than this many days
+///   ago; purges everything when None
+///
+/// Entries that cannot be checked or removed are skipped and reported; the
+/// rest are still purged.
+#[tauri::command]
+pub async fn empty_trash(older_than_days: Option<u64>) -> Result<TrashPurgeResult, String> {
+    let cutoff = older_than_days
+        .map(|days| {
+            i64::try_from(days)
+                .ok()
+                .and_then(Duration::try_days)
+                .map(|age| Utc::now() - age)
+                .ok_or_else(|| format!("Invalid trash age: {days} days"))
+        })
+        .transpose()?;
+    empty_trash_in(&default_trash_dir()?, cutoff)
+}
+
+/// Pack the regular files of `project_dir` under `<name>/` into a gzipped
+/// tarball at `out`. Symlinks are skipped. Returns the file count and the
+/// uncompressed byte total.
+fn write_project_archive(
+    project_dir: &Path,
+    name: &str,
+    out: &Path,
+) -> Result<(usize, u64), String> {
+    let file = File::create(out).map_err(|e| format!("Failed to create archive file: {e}"))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let mut file_count = 0;
+    let mut total_bytes = 0;
+
+    for entry in WalkDir::new(project_dir)
+        .follow_links(false)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        let Ok(relative) = entry.path().strip_prefix(project_dir) else {
+            continue;
+        };
+        builder
+            .append_path_with_name(entry.path(), Path::new(name).join(relative))
+            .map_err(|e| format!("Failed to add {} to archive: {e}", entry.path().display()))?;
+        file_count += 1;
+        total_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+    }
+
+    builder
+        .into_inner()
+        .and_then(GzEncoder::finish)
+        .map_err(|e| format!("Failed to finish archive: {e}"))?;
+
+    Ok((file_count, total_bytes))
+}
+
+/// Read an archive back end to end and check it holds the expected number of
+/// regular files and bytes.
+fn verify_project_archive(
+    archive: &Path,
+    expected_files: usize,
+    expected_bytes: u64,
+) -> Result<(), String> {
+    let file = File::open(archive).map_err(|e| format!("Failed to reopen archive: {e}"))?;
+    let mut reader = tar::Archive::new(GzDecoder::new(file));
+    let mut file_count = 0;
+    let mut total_bytes = 0;
+
+    for entry in reader
+        .entries()
+        .map_err(|e| format!("Failed to read archive: {e}"))?
+    {
+        let mut entry = entry.map_err(|e| format!("Corrupt archive entry: {e}"))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        total_bytes += io::copy(&mut entry, &mut io::sink())
+            .map_err(|e| format!("Corrupt archive entry: {e}"))?;
+        file_count += 1;
+    }
+
+    if file_count != expected_files || total_bytes != expected_bytes {
+        return Err(format!(
+            "Archive verification failed: expected {expected_files} files ({expected_bytes} bytes), found {file_count} ({total_bytes} bytes)"
+        ));
+    }
+    Ok(())
+}
+
+/// Archive one project directory into `out_dir/<name>.tar.gz`, verify the
+/// archive, then remove the directory when `and_delete` is set.
+/// An existing archive with the same name is never overwritten.
+fn archive_project(
+    project_dir: &Path,
+    out_dir: &Path,
+    and_delete: bool,
+) -> Result<ProjectArchive, String> {
+    let name = project_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid project path: {}", project_dir.display()))?;
+    let archive_path = out_dir.join(format!("{name}.tar.gz"));
+    if archive_path.exists() {
+        return Err(format!(
+            "Archive already exists: {}",
+            archive_path.display()
+        ));
+    }
+
+    let temp_path = out_dir.join(format!(".{name}.tar.gz.tmp"));
+    let (file_count, total_bytes) = write_project_archive(project_dir, &name, &temp_path)
+        .and_then(|packed| {
+            verify_project_archive(&temp_path, packed.0, packed.1)?;
+            Ok(packed)
+        })
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })?;
+    atomic_rename(&temp_path, &archive_path)?;
+
+    let archive_bytes = fs::metadata(&archive_path).map(|m| m.len()).unwrap_or(0);
+    if and_delete {
+        fs::remove_dir_all(project_dir).map_err(|e| {
+            format!(
+                "Archived to {} but failed to remove project directory: {e}",
+                archive_path.display()
+            )
+        })?;
+    }
+
+    Ok(ProjectArchive {
+        project_path: project_dir.to_string_lossy().to_string(),
+        archive_path: Some(archive_path.to_string_lossy().to_string()),
+        archive_bytes,
+        file_count,
+        total_bytes,
+        deleted: and_delete,
+        error: None,
+    })
+}
+
+/// Require `dir` to resolve to exactly `levels` directories below
+/// `projects_root`: 1 for a project, 2 for a session companion directory
+fn check_below_projects_root(
+    dir: &Path,
+    projects_root: &Path,
+    levels: usize,
+    kind: &str,
+) -> Result<(), CommandError> {
+    let outside = || CommandError::InvalidInput(format!("Not a {kind}: {}", dir.display()));
+    let canonical = dir.canonicalize().map_err(|_| outside())?;
+    let canonical_root = projects_root.canonicalize().map_err(|_| outside())?;
+    if canonical.ancestors().nth(levels) != Some(canonical_root.as_path()) {
+        return Err(outside());
+    }
+    Ok(())
+}
+
+/// Canonical form of a directory that may not exist yet: its deepest
+/// existing ancestor is canonicalized (resolving symlinks) and the missing
+/// components are appended. `..` is refused, since it cannot be resolved
+/// against directories that do not exist.
+fn canonicalize_missing(dir: &Path) -> Result<PathBuf, CommandError> {
+    if dir.components().any(|c| matches!(c, Component::ParentDir)) {
+        return Err(CommandError::InvalidInput(format!(
+            "Path must not contain '..': {}",
+            dir.display()
+        )));
+    }
+    let mut missing = Vec::new();
+    for ancestor in dir.ancestors() {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            return Ok(missing
+                .iter()
+                .rev()
+                .fold(canonical, |path, part| path.join(part)));
+        }
+        if let Some(name) = ancestor.file_name() {
+            missing.push(name);
+        }
+    }
+    Err(CommandError::InvalidInput(format!(
+        "Cannot resolve {}",
+        dir.display()
+    )))
+}
+
+/// Archive `project_paths` after checking that each is a directory directly
+/// inside `projects_root`. Nothing is written if any check fails.
+fn archive_projects_in(
+    project_paths: Vec<String>,
+    out_dir: &Path,
+    and_delete: bool,
+    projects_root: &Path,
+) -> Result<Vec<ProjectArchive>, CommandError> {
+    let canonical_out_dir = canonicalize_missing(out_dir)?;
+    for project_path in &project_paths {
+        let project_dir = Path::new(project_path);
+        if !project_dir.is_dir() {
+            return Err(CommandError::NotFound(format!(
+                "Project directory not found: {project_path}"
+            )));
+        }
+        check_below_projects_root(project_dir, projects_root, 1, "project directory")?;
+        let canonical_project = project_dir
+            .canonicalize()
+            .map_err(|e| CommandError::io("Failed to resolve project directory", &e))?;
+        if canonical_out_dir.starts_with(&canonical_project) {
+            return Err(CommandError::InvalidInput(format!(
+                "Output directory cannot be inside archived project {project_path}"
+            )));
+        }
+    }
+
+    fs::create_dir_all(out_dir)
+        .map_err(|e| CommandError::io("Failed to create output directory", &e))?;
+
+    Ok(project_paths
+        .into_iter()
+        .map(|project_path| {
+            archive_project(Path::new(&project_path), out_dir, and_delete).unwrap_or_else(|error| {
+                ProjectArchive {
+                    project_path,
+                    archive_path: None,
+                    archive_bytes: 0,
+                    file_count: 0,
+                    total_bytes: 0,
+                    deleted: false,
+                    error: Some(error),
+                }
+            })
+        })
+        .collect())
+}
+
+/// Archive project directories as `.tar.gz` files.
+///
+/// Every project path must pass the same safety checks as `delete_session`
+/// and be a project directory directly inside `~/.claude/projects`; nothing
+/// is archived if any path fails. Each project is
+/// archived independently, so a failure is reported in its manifest entry
+/// without stopping the batch.
+///
+/// # Arguments
+/// * `project_paths` - Project directories under `~/.claude/projects`
+/// * `out_dir` - Directory receiving `<project>.tar.gz` files (created if missing)
+/// * `and_delete` - Remove each project directory once its archive has been
+///   read back successfully (default: false)
+#[tauri::command]
+pub async fn archive_projects(
+    project_paths: Vec<String>,
+    out_dir: String,
+    and_delete: Option<bool>,
+) -> Result<Vec<ProjectArchive>, CommandError> {
+    let out_dir = PathBuf::from(out_dir);
+    if !out_dir.is_absolute() {
+        return Err(CommandError::InvalidInput(
+            "Output directory must be absolute".to_string(),
+        ));
+    }
+
+    for project_path in &project_paths {
+        validate_delete_path(project_path)?;
+    }
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| CommandError::Io("Cannot determine home directory".to_string()))?;
+
+    archive_projects_in(
+        project_paths,
+        &out_dir,
+        and_delete.unwrap_or(false),
+        &home_dir.join(".claude").join("projects"),
+    )
+}
+
+/// Whether two files have the same contents, compared byte for byte
+fn same_contents(a: &Path, b: &Path) -> bool {
+    match (
+        map_session_file(&a.to_string_lossy()),
+        map_session_file(&b.to_string_lossy()),
+    ) {
+        (Ok(a), Ok(b)) => *a == *b,
+        _ => false,
+    }
+}
+
+/// Group the non-empty session files below `dir` by identical contents.
+///
+/// Files are first grouped by size, so only files sharing a size are hashed;
+/// hash matches are then confirmed byte for byte.
+fn find_identical_in(dir: &Path) -> Vec<DuplicateSet> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for entry in WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("jsonl"))
+    {
+        if let Some(size) = entry.metadata().ok().map(|m| m.len()).filter(|&s| s > 0) {
+            by_size.entry(size).or_default().push(entry.into_path());
+        }
+    }
+
+    let candidates: Vec<(u64, PathBuf)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .flat_map(|(size, paths)| paths.into_iter().map(move |path| (size, path)))
+        .collect();
+
+    let hashed: Vec<(u64, String, PathBuf)> = candidates
+        .into_par_iter()
+        .filter_map(|(size, path)| {
+            let hash = with_file_permit(|| content_hash(&path))?;
+            Some((size, hash, path))
+        })
+        .collect();
+
+    let mut by_hash: HashMap<(u64, String), Vec<PathBuf>> = HashMap::new();
+    for (size, hash, path) in hashed {
+        by_hash.entry((size, hash)).or_default().push(path);
+    }
+
+    let mut sets = Vec::new();
+    for ((size, _), mut paths) in by_hash {
+        paths.sort();
+        // Split hash collisions into groups of truly identical files
+        let mut groups: Vec<Vec<PathBuf>> = Vec::new();
+        for path in paths {
+            match groups.iter_mut().find(|g| same_contents(&g[0], &path)) {
+                Some(group) => group.push(path),
+                None => groups.push(vec![path]),
+            }
+        }
+        sets.extend(
+            groups
+                .into_iter()
+                .filter(|g| g.len() > 1)
+                .map(|g| DuplicateSet {
+                    size_bytes: size,
+                    reclaimable_bytes: size * (g.len() as u64 - 1),
+                    paths: g.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+                }),
+        );
+    }
+
+    sets.sort_by(|a, b| {
+        b.reclaimable_bytes
+            .cmp(&a.reclaimable_bytes)
+            .then_with(|| a.paths.cmp(&b.paths))
+    });
+    sets
+}
+
+/// Find byte-identical session files across all projects.
+///
+/// Worktrees and copied project directories can leave the same session in
+/// several places. Each set lists the copies and the space freed by keeping
+/// one. Files are hashed in parallel with bounded concurrency; nothing is
+/// modified. Use `dedupe` to remove chosen copies.
+#[tauri::command]
+pub async fn find_identical_sessions(claude_path: String) -> Result<Vec<DuplicateSet>, String> {
+    let projects_dir = PathBuf::from(&claude_path).join("projects");
+    if !projects_dir.is_dir() {
+        return Err(format!(
+            "Projects directory not found: {}",
+            projects_dir.display()
+        ));
+    }
+    Ok(find_identical_in(&projects_dir))
+}
+
+/// Remove `remove` after checking each is an identical copy of `keep`.
+///
+/// Paths are compared after canonicalizing, so an alias of `keep` (through
+/// `..` or a symlinked directory) or a copy listed twice is refused. Every
+/// copy is checked before the first removal.
+fn remove_duplicates(keep: &Path, remove: &[PathBuf]) -> Result<DedupeResult, CommandError> {
+    if !keep.is_file() {
+        return Err(CommandError::NotFound(format!(
+            "Session to keep not found: {}",
+            keep.display()
+        )));
+    }
+    let canonical_keep = keep
+        .canonicalize()
+        .map_err(|e| CommandError::io("Failed to resolve session to keep", &e))?;
+    let mut canonical_remove: Vec<PathBuf> = Vec::with_capacity(remove.len());
+    for path in remove {
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| CommandError::io(&format!("Failed to resolve {}", path.display()), &e))?;
+        if canonical == canonical_keep {
+            return Err(CommandError::InvalidInput(format!(
+                "Cannot remove the session being kept: {}",
+                path.display()
+            )));
+        }
+        if canonical_remove.contains(&canonical) {
+            return Err(CommandError::InvalidInput(format!(
+                "{} is listed more than once",
+                path.display()
+            )));
+        }
+        if !same_contents(&canonical_keep, &canonical) {
+            return Err(CommandError::InvalidInput(format!(
+                "{} is not an identical copy of {}",
+                path.display(),
+                keep.display()
+            )));
+        }
+        canonical_remove.push(canonical);
+    }
+
+    let mut result = DedupeResult {
+        kept: keep.to_string_lossy().to_string(),
+        removed: Vec::new(),
+        bytes_freed: 0,
+    };
+    for (path, canonical) in remove.iter().zip(&canonical_remove) {
+        let size = fs::metadata(canonical).map(|m| m.len()).unwrap_or(0);
+        fs::remove_file(canonical)
+            .map_err(|e| CommandError::io("Failed to remove duplicate session", &e))?;
+        result.removed.push(path.to_string_lossy().to_string());
+        result.bytes_freed += size;
+    }
+    Ok(result)
+}
+
+/// Remove duplicate copies of a session, keeping `keep`.
+///
+/// Every path must pass the same safety checks as `delete_session`, and each
+/// copy in `remove` is compared byte for byte with `keep` right before
+/// deletion; if any check fails nothing is removed. Companion directories of
+/// removed copies are left in place.
+#[tauri::command]
+pub async fn dedupe(keep: String, remove: Vec<String>) -> Result<DedupeResult, CommandError> {
+    validate_delete_path(&keep)?;
+    for path in &remove {
+        validate_delete_path(path)?;
+    }
+    let remove: Vec<PathBuf> = remove.into_iter().map(PathBuf::from).collect();
+    remove_duplicates(Path::new(&keep), &remove)
+}
+
+/// Whether `name` has the shape of a session id (hyphenated UUID)
+fn is_session_id(name: &str) -> bool {
+    name.len() == 36 && Uuid::try_parse(name).is_ok()
+}
+
+/// Whether `dir` is a companion directory whose `<name>.jsonl` is missing.
+/// Symlinks and directories not named like a session id (`memory`, dot
+/// directories) are never orphans.
+fn is_orphaned_companion(dir: &Path) -> bool {
+    let Some(name) = dir.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        return false;
+    };
+    let is_real_dir = fs::symlink_metadata(dir).is_ok_and(|m| m.file_type().is_dir());
+
+    is_real_dir && is_session_id(&name) && !dir.with_file_name(format!("{name}.jsonl")).exists()
+}
+
+/// Orphaned companion directories of every project below `projects_dir`,
+/// largest first
+fn find_orphaned_companions_in(projects_dir: &Path) -> Vec<OrphanedCompanion> {
+    let dirs: Vec<PathBuf> = WalkDir::new(projects_dir)
+        .min_depth(2)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_dir())
+        .map(walkdir::DirEntry::into_path)
+        .filter(|dir| is_orphaned_companion(dir))
+        .collect();
+
+    let mut orphans: Vec<OrphanedCompanion> = dirs
+        .par_iter()
+        .map(|dir| OrphanedCompanion {
+            path: dir.to_string_lossy().to_string(),
+            size_bytes: directory_size(dir),
+        })
+        .collect();
+    orphans.sort_by(|a, b| {
+        b.size_bytes
+            .cmp(&a.size_bytes)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    orphans
+}
+
+/// Find companion directories whose session file no longer exists.
+///
+/// Manual file operations can remove a session's JSONL and leave its
+/// companion directory (subagent logs, tool outputs) behind. Lists each
+/// such directory with its size, largest first. Directories that still have
+/// a matching `<name>.jsonl` are never listed; nothing is modified.
+#[tauri::command]
+pub async fn find_orphaned_companions(
+    claude_path: String,
+) -> Result<Vec<OrphanedCompanion>, String> {
+    let projects_dir = PathBuf::from(&claude_path).join("projects");
+    if !projects_dir.is_dir() {
+        return Err(format!(
+            "Projects directory not found: {}",
+            projects_dir.display()
+        ));
+    }
+    Ok(find_orphaned_companions_in(&projects_dir))
+}
+
+/// Remove companion directories after checking each sits directly in a
+/// project below `projects_root` and is still orphaned. Every directory is
+/// checked before the first removal.
+fn remove_orphaned_companions(
+    dirs: &[PathBuf],
+    projects_root: &Path,
+) -> Result<OrphanCleanupResult, CommandError> {
+    for dir in dirs {
+        check_below_projects_root(dir, projects_root, 2, "session companion directory")?;
+    }
+    if let Some(dir) = dirs.iter().find(|dir| !is_orphaned_companion(dir)) {
+        return Err(CommandError::InvalidInput(format!(
+            "Not an orphaned companion directory: {}",
+            dir.display()
+        )));
+    }
+
+    let mut result = OrphanCleanupResult {
+        removed: Vec::new(),
+        bytes_freed: 0,
+    };
+    for dir in dirs {
+        let size = directory_size(dir);
+        fs::remove_dir_all(dir)
+            .map_err(|e| CommandError::io("Failed to remove companion directory", &e))?;
+        result.removed.push(dir.to_string_lossy().to_string());
+        result.bytes_freed += size;
+    }
+    Ok(result)
+}
+
+/// Delete orphaned companion directories found by `find_orphaned_companions`.
+///
+/// Every path must pass the same safety checks as `delete_session`, be a
+/// session-id named directory directly inside a project of
+/// `~/.claude/projects` and still lack its session file; if any check fails
+/// nothing is removed.
+#[tauri::command]
+pub async fn delete_orphaned_companions(
+    paths: Vec<String>,
+) -> Result<OrphanCleanupResult, CommandError> {
+    for path in &paths {
+        validate_delete_path(path)?;
+    }
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| CommandError::Io("Cannot determine home directory".to_string()))?;
+    let dirs: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+    remove_orphaned_companions(&dirs, &home_dir.join(".claude").join("projects"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_compute_session_disk_usage_with_companion_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = temp_dir.path().join("session-1.jsonl");
+        fs::write(&session, "0123456789").unwrap();
+        let companion = temp_dir.path().join("session-1").join("subagents");
+        fs::create_dir_all(&companion).unwrap();
+        fs::write(companion.join("agent-1.jsonl"), "01234").unwrap();
+
+        let usage = compute_session_disk_usage(&session).unwrap();
+        assert_eq!(usage.session_id, "session-1");
+        assert_eq!(usage.jsonl_bytes, 10);
+        assert_eq!(usage.companion_bytes, 5);
+        assert_eq!(usage.total_bytes, 15);
+    }
+
+    #[test]
+    fn test_compute_session_disk_usage_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(compute_session_disk_usage(&temp_dir.path().join("missing.jsonl")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_project_disk_usage_sorted_and_limited() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("small.jsonl"), "1").unwrap();
+        fs::write(temp_dir.path().join("medium.jsonl"), "12345").unwrap();
+        fs::write(temp_dir.path().join("large.jsonl"), "1234567890").unwrap();
+        fs::create_dir_all(temp_dir.path().join("small")).unwrap();
+        fs::write(
+            temp_dir.path().join("small").join("artifact.txt"),
+            "1234567",
+        )
+        .unwrap();
+
+        let usage = project_disk_usage(temp_dir.path().to_string_lossy().to_string(), Some(2))
+            .await
+            .unwrap();
+
+        assert_eq!(usage.session_count, 3);
+        assert_eq!(usage.jsonl_bytes, 16);
+        assert_eq!(usage.companion_bytes, 7);
+        assert_eq!(usage.total_bytes, 23);
+        assert_eq!(usage.largest_sessions.len(), 2);
+        assert_eq!(usage.largest_sessions[0].session_id, "large");
+        assert_eq!(usage.largest_sessions[1].session_id, "small");
+    }
+
+    #[tokio::test]
+    async fn test_project_disk_usage_missing_project() {
+        let result = project_disk_usage("/nonexistent/project/path".to_string(), None).await;
+        assert!(result.is_err());
+    }
+
+    fn write_trashed(trash_dir: &Path, name: &str, deleted_at: &str) -> PathBuf {
+        let path = trash_dir.join(name);
+        fs::write(&path, "12345").unwrap();
+        let info = TrashInfo {
+            original_path: format!("/home/u/.claude/projects/p/{name}"),
+            deleted_at: deleted_at.to_string(),
+        };
+        fs::write(
+            trash_info_path(&path),
+            serde_json::to_string(&info).unwrap(),
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_list_trash_in_reads_sidecars() {
+        let temp_dir = TempDir::new().unwrap();
+        write_trashed(temp_dir.path(), "old.jsonl", "2024-01-01T00:00:00+00:00");
+        write_trashed(temp_dir.path(), "new.jsonl", "2024-06-01T00:00:00+00:00");
+        let companion = temp_dir.path().join("orphan");
+        fs::create_dir_all(&companion).unwrap();
+        fs::write(companion.join("agent.jsonl"), "123").unwrap();
+
+        let entries = list_trash_in(temp_dir.path()).unwrap();
+        assert_eq!(entries.len(), 3);
+        let named: Vec<_> = entries
+            .iter()
+            .filter(|e| e.original_path.is_some())
+            .collect();
+        assert!(named[0].trash_path.ends_with("new.jsonl"));
+        assert!(named[1].trash_path.ends_with("old.jsonl"));
+        assert_eq!(named[0].size_bytes, 5);
+
+        let orphan = entries.iter().find(|e| e.original_path.is_none()).unwrap();
+        assert_eq!(orphan.size_bytes, 3);
+
+        assert!(list_trash_in(&temp_dir.path().join("missing"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_empty_trash_in_respects_cutoff() {
+        let temp_dir = TempDir::new().unwrap();
+        let old = write_trashed(temp_dir.path(), "old.jsonl", "2024-01-01T00:00:00+00:00");
+        let new = write_trashed(temp_dir.path(), "new.jsonl", "2024-06-01T00:00:00+00:00");
+
+        let cutoff = DateTime::parse_from_rfc3339("2024-03-01T00:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            empty_trash_in(temp_dir.path(), Some(cutoff))
+                .unwrap()
+                .removed,
+            1
+        );
+        assert!(!old.exists());
+        assert!(!trash_info_path(&old).exists());
+        assert!(new.exists());
+
+        assert_eq!(empty_trash_in(temp_dir.path(), None).unwrap().removed, 1);
+        assert!(fs::read_dir(temp_dir.path()).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn test_empty_trash_in_skips_bad_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let old = write_trashed(temp_dir.path(), "old.jsonl", "2024-01-01T00:00:00+00:00");
+        let bad = write_trashed(temp_dir.path(), "bad.jsonl", "last tuesday");
+        let older = write_trashed(temp_dir.path(), "older.jsonl", "2023-01-01T00:00:00+00:00");
+
+        let cutoff = DateTime::parse_from_rfc3339("2024-03-01T00:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let result = empty_trash_in(temp_dir.path(), Some(cutoff)).unwrap();
+        assert_eq!(result.removed, 2);
+        assert_eq!(result.skipped.len(), 1);
+        assert!(result.skipped[0].trash_path.ends_with("bad.jsonl"));
+        assert!(!old.exists() && !older.exists());
+        assert!(bad.exists());
+    }
+
+    #[test]
+    fn test_archive_project_verifies_then_deletes() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("-Users-me-app");
+        fs::create_dir_all(project.join("session-1").join("subagents")).unwrap();
+        fs::write(project.join("session-1.jsonl"), "0123456789").unwrap();
+        fs::write(
+            project
+                .join("session-1")
+                .join("subagents")
+                .join("agent.jsonl"),
+            "01234",
+        )
+        .unwrap();
+        let out_dir = temp_dir.path().join("archives");
+        fs::create_dir_all(&out_dir).unwrap();
+
+        let archive = archive_project(&project, &out_dir, true).unwrap();
+        assert_eq!(archive.file_count, 2);
+        assert_eq!(archive.total_bytes, 15);
+        assert!(archive.deleted);
+        assert!(!project.exists());
+
+        let archive_path = PathBuf::from(archive.archive_path.unwrap());
+        assert_eq!(archive_path, out_dir.join("-Users-me-app.tar.gz"));
+        assert_eq!(
+            archive.archive_bytes,
+            fs::metadata(&archive_path).unwrap().len()
+        );
+        verify_project_archive(&archive_path, 2, 15).unwrap();
+
+        // A second archive of the same name is refused rather than overwritten
+        fs::create_dir_all(&project).unwrap();
+        assert!(archive_project(&project, &out_dir, false).is_err());
+        assert!(project.exists());
+    }
+
+    #[test]
+    fn test_verify_project_archive_detects_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("a.jsonl"), "abc").unwrap();
+        let out = temp_dir.path().join("project.tar.gz");
+
+        assert_eq!(
+            write_project_archive(&project, "project", &out).unwrap(),
+            (1, 3)
+        );
+        assert!(verify_project_archive(&out, 2, 3).is_err());
+
+        fs::write(&out, b"not a tarball").unwrap();
+        assert!(verify_project_archive(&out, 1, 3).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_archive_projects_rejects_unsafe_paths() {
+        let result = archive_projects(
+            vec!["relative/project".to_string()],
+            "/tmp/archives".to_string(),
+            None,
+        )
+        .await;
+        assert!(matches!(result, Err(CommandError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_archive_projects_in_requires_direct_project_children() {
+        let temp_dir = TempDir::new().unwrap();
+        let claude_dir = temp_dir.path().join(".claude");
+        let projects = claude_dir.join("projects");
+        let project = projects.join("-srv-app");
+        let todos = claude_dir.join("todos");
+        fs::create_dir_all(&project).unwrap();
+        fs::create_dir_all(&todos).unwrap();
+        fs::write(project.join("s.jsonl"), "{}").unwrap();
+        let out_dir = temp_dir.path().join("archives");
+        let path = |p: &Path| p.to_string_lossy().to_string();
+
+        for inputs in [
+            vec![path(&projects)],
+            vec![path(&todos)],
+            vec![path(&project), path(&todos)],
+        ] {
+            let refused = archive_projects_in(inputs, &out_dir, true, &projects);
+            assert!(matches!(refused, Err(CommandError::InvalidInput(_))));
+        }
+        assert!(!out_dir.exists());
+        assert!(project.is_dir() && todos.is_dir());
+
+        // An output directory reaching into the project through a symlink
+        // or `..` is refused too
+        #[cfg(unix)]
+        {
+            let alias = temp_dir.path().join("alias");
+            std::os::unix::fs::symlink(&project, &alias).unwrap();
+            let refused =
+                archive_projects_in(vec![path(&project)], &alias.join("out"), true, &projects);
+            assert!(matches!(refused, Err(CommandError::InvalidInput(_))));
+            assert!(!project.join("out").exists());
+        }
+        let sneaky = temp_dir.path().join("new").join("..").join("archives");
+        let refused = archive_projects_in(vec![path(&project)], &sneaky, true, &projects);
+        assert!(matches!(refused, Err(CommandError::InvalidInput(_))));
+
+        let archives =
+            archive_projects_in(vec![path(&project)], &out_dir, true, &projects).unwrap();
+        assert!(archives[0].deleted);
+        assert!(!project.exists());
+    }
+
+    #[test]
+    fn test_find_identical_in() {
+        let temp_dir = TempDir::new().unwrap();
+        let worktree = temp_dir.path().join("-repo-wt");
+        let main = temp_dir.path().join("-repo");
+        fs::create_dir_all(&worktree).unwrap();
+        fs::create_dir_all(&main).unwrap();
+        fs::write(main.join("a.jsonl"), "{\"x\":1}\n").unwrap();
+        fs::write(worktree.join("a.jsonl"), "{\"x\":1}\n").unwrap();
+        fs::write(worktree.join("b.jsonl"), "{\"x\":2}\n").unwrap();
+        fs::write(main.join("empty.jsonl"), "").unwrap();
+        fs::write(worktree.join("empty.jsonl"), "").unwrap();
+
+        let sets = find_identical_in(temp_dir.path());
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].paths.len(), 2);
+        assert!(sets[0].paths.iter().all(|p| p.ends_with("a.jsonl")));
+        assert_eq!(sets[0].size_bytes, 8);
+        assert_eq!(sets[0].reclaimable_bytes, 8);
+    }
+
+    #[test]
+    fn test_remove_duplicates_checks_every_copy_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let keep = temp_dir.path().join("keep.jsonl");
+        let copy = temp_dir.path().join("copy.jsonl");
+        let other = temp_dir.path().join("other.jsonl");
+        fs::write(&keep, "same").unwrap();
+        fs::write(&copy, "same").unwrap();
+        fs::write(&other, "diff").unwrap();
+
+        assert!(remove_duplicates(&keep, &[copy.clone(), other.clone()]).is_err());
+        assert!(copy.exists());
+        assert!(remove_duplicates(&keep, std::slice::from_ref(&keep)).is_err());
+
+        let result = remove_duplicates(&keep, std::slice::from_ref(&copy)).unwrap();
+        assert_eq!(result.bytes_freed, 4);
+        assert!(!copy.exists());
+        assert!(keep.exists());
+    }
+
+    #[test]
+    fn test_remove_duplicates_refuses_alias_of_keep() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("a")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("b")).unwrap();
+        let keep = temp_dir.path().join("a").join("x.jsonl");
+        fs::write(&keep, "same").unwrap();
+        let alias = temp_dir
+            .path()
+            .join("b")
+            .join("..")
+            .join("a")
+            .join("x.jsonl");
+
+        let refused = remove_duplicates(&keep, &[alias]);
+        assert!(matches!(refused, Err(CommandError::InvalidInput(_))));
+        assert!(keep.exists());
+    }
+
+    #[test]
+    fn test_remove_duplicates_refuses_repeated_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        let keep = temp_dir.path().join("keep.jsonl");
+        let copy = temp_dir.path().join("copy.jsonl");
+        fs::write(&keep, "same").unwrap();
+        fs::write(&copy, "same").unwrap();
+        let alias = temp_dir.path().join(".").join("copy.jsonl");
+
+        let refused = remove_duplicates(&keep, &[copy.clone(), alias]);
+        assert!(matches!(refused, Err(CommandError::InvalidInput(_))));
+        assert!(copy.exists());
+    }
+
+    const GONE: &str = "0b6e3f4a-1c2d-4e5f-8a9b-0c1d2e3f4a5b";
+    const KEPT: &str = "7f8e9d0c-1b2a-4c3d-9e8f-7a6b5c4d3e2f";
+
+    #[test]
+    fn test_orphaned_companions() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("-srv-app");
+        fs::create_dir_all(project.join(KEPT).join("subagents")).unwrap();
+        fs::write(project.join(format!("{KEPT}.jsonl")), "{}").unwrap();
+        fs::write(project.join(KEPT).join("subagents/a.jsonl"), "12345").unwrap();
+        fs::create_dir_all(project.join(GONE).join("tool-results")).unwrap();
+        fs::write(project.join(GONE).join("tool-results/out.txt"), "123").unwrap();
+        fs::create_dir_all(project.join("memory")).unwrap();
+        fs::create_dir_all(project.join("notes")).unwrap();
+
+        let orphans = find_orphaned_companions_in(temp_dir.path());
+        assert_eq!(orphans.len(), 1);
+        assert!(orphans[0].path.ends_with(GONE));
+        assert_eq!(orphans[0].size_bytes, 3);
+
+        // A session with its file is refused and nothing is removed
+        let refused =
+            remove_orphaned_companions(&[project.join(GONE), project.join(KEPT)], temp_dir.path());
+        assert!(matches!(refused, Err(CommandError::InvalidInput(_))));
+        assert!(project.join(GONE).is_dir());
+
+        let result = remove_orphaned_companions(&[project.join(GONE)], temp_dir.path()).unwrap();
+        assert_eq!(result.bytes_freed, 3);
+        assert!(!project.join(GONE).exists());
+        assert!(project.join(KEPT).is_dir());
+    }
+
+    #[test]
+    fn test_remove_orphaned_companions_refuses_project_and_projects_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects = temp_dir.path().join("projects");
+        let project = projects.join("-srv-app");
+        fs::create_dir_all(project.join(GONE)).unwrap();
+        let nested = project.join(GONE).join(KEPT);
+        fs::create_dir_all(&nested).unwrap();
+
+        for dir in [&projects, &project, &nested, &project.join("notes")] {
+            fs::create_dir_all(dir).unwrap();
+            let refused = remove_orphaned_companions(std::slice::from_ref(dir), &projects);
+            assert!(matches!(refused, Err(CommandError::InvalidInput(_))));
+        }
+        assert!(project.join(GONE).is_dir());
+
+        // A companion outside the projects root is refused as well
+        let stray = temp_dir.path().join("elsewhere").join(GONE);
+        fs::create_dir_all(&stray).unwrap();
+        assert!(remove_orphaned_companions(std::slice::from_ref(&stray), &projects).is_err());
+        assert!(stray.is_dir());
+    }
+}
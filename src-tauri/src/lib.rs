@@ -17,16 +17,45 @@ use crate::commands::{
         save_user_metadata, update_project_metadata, update_session_metadata, update_user_settings,
         MetadataState,
     },
-    project::{get_claude_folder_path, get_git_log, scan_projects, validate_claude_folder},
+    project::{
+        decode_project_path_with_confidence, diagnose_project_encoding, encode_project_path,
+        export_project_manifest, find_project_storage, get_claude_folder_path, get_git_log,
+        group_sessions_by_branch, list_projects_by_activity, list_projects_in, project_card,
+        project_tree_snapshot, repair_sessions_index, resolve_tool_path, scan_projects,
+        stale_projects, validate_claude_folder, validate_sessions_index,
+        verify_sessions_against_manifest,
+    },
     session::{
-        delete_session, get_recent_edits, get_session_message_count, load_project_sessions,
-        load_session_messages, load_session_messages_paginated, rename_session_native,
-        reset_session_native_name, restore_file, resume_session, search_messages,
+        agentic_ratio, anonymize_session, check_path_safe, classify_companion_files,
+        compact_session, count_interruptions, count_lines, declared_tools, delete_session,
+        derive_session_title, error_streaks, expand_multiedit, export_search_results,
+        export_session_bundle, export_session_json, export_session_ndjson_channel,
+        export_user_prompts, export_user_prompts_markdown, extract_urls, find_compaction_events,
+        find_messages_by_uuid_prefix, find_session_continuations, get_raw_line, get_recent_edits,
+        get_session_message_count, image_session_stats, import_conversation, import_session_bundle,
+        is_session_active, largest_messages, list_mcp_tool_calls, list_session_users,
+        list_sessions_with_titles, list_slash_commands, load_from_offset, load_project_sessions,
+        load_project_sessions_in, load_session_messages, load_session_messages_in,
+        load_session_messages_paginated, load_session_tail, merge_sessions, mode_transitions,
+        paired_tool_calls, probe_sessions, query_session, rename_session_native, replay_edits_to,
+        reset_session_native_name, restore_file, resume_session, scan_secrets, search_messages,
+        search_messages_in, session_edit_summary, session_environment, session_lineage,
+        session_metadata, session_preview, session_time_bounds, session_web_url,
+        session_working_dirs, sessions_referencing_file, sessions_with_errors,
+        split_session_at_compaction, thread_integrity_check, tool_error_stats,
+        truncate_large_outputs, turn_latencies, verify_companion_references,
     },
     settings::{delete_preset, get_preset, load_presets, save_preset},
     stats::{
+        activity_by_hour, cache_stats, cost_over_range, edited_extensions,
         get_global_stats_summary, get_project_stats_summary, get_project_token_stats,
-        get_session_comparison, get_session_token_stats,
+        get_session_comparison, get_session_token_stats, message_length_series,
+        project_language_stats, project_reading_stats, prompt_stats, session_daily_counts,
+        sessions_by_cost, token_timeseries, turn_balance_series, word_frequencies,
+    },
+    storage::{
+        archive_projects, dedupe, delete_orphaned_companions, empty_trash, find_identical_sessions,
+        find_orphaned_companions, list_trash, project_disk_usage, session_disk_usage,
     },
     unified_presets::{
         delete_unified_preset, get_unified_preset, load_unified_presets, save_unified_preset,
@@ -59,19 +88,56 @@ pub fn run() {
             get_claude_folder_path,
             validate_claude_folder,
             scan_projects,
+            list_projects_by_activity,
+            stale_projects,
+            group_sessions_by_branch,
+            project_tree_snapshot,
+            resolve_tool_path,
+            encode_project_path,
+            find_project_storage,
+            decode_project_path_with_confidence,
+            diagnose_project_encoding,
+            validate_sessions_index,
+            repair_sessions_index,
+            project_card,
+            export_project_manifest,
+            verify_sessions_against_manifest,
             get_git_log,
             load_project_sessions,
             load_session_messages,
             load_session_messages_paginated,
+            load_from_offset,
+            load_session_tail,
+            image_session_stats,
+            error_streaks,
+            replay_edits_to,
+            session_working_dirs,
+            list_slash_commands,
             get_session_message_count,
             search_messages,
+            export_search_results,
             get_recent_edits,
             restore_file,
+            expand_multiedit,
+            session_edit_summary,
             get_session_token_stats,
             get_project_token_stats,
             get_project_stats_summary,
             get_session_comparison,
             get_global_stats_summary,
+            token_timeseries,
+            activity_by_hour,
+            session_daily_counts,
+            prompt_stats,
+            cost_over_range,
+            sessions_by_cost,
+            project_language_stats,
+            edited_extensions,
+            message_length_series,
+            turn_balance_series,
+            word_frequencies,
+            project_reading_stats,
+            cache_stats,
             send_feedback,
             get_system_info,
             open_github_issues,
@@ -113,13 +179,84 @@ pub fn run() {
             // Native session rename commands
             rename_session_native,
             reset_session_native_name,
-            // Session deletion command
+            // Session deletion commands
             delete_session,
-            // Session resume command
+            check_path_safe,
+            // Session bundle commands
+            export_session_bundle,
+            import_session_bundle,
+            // Normalized export and external import commands
+            export_session_json,
+            export_session_ndjson_channel,
+            export_user_prompts,
+            export_user_prompts_markdown,
+            import_conversation,
+            // Session merge command
+            merge_sessions,
+            // Session anonymization and secret scanning commands
+            anonymize_session,
+            scan_secrets,
+            // Raw line access commands
+            get_raw_line,
+            count_lines,
+            find_messages_by_uuid_prefix,
+            compact_session,
+            truncate_large_outputs,
+            // Session query command
+            query_session,
+            // Session probe commands
+            probe_sessions,
+            is_session_active,
+            list_session_users,
+            session_metadata,
+            session_environment,
+            // Custom data root commands
+            list_projects_in,
+            load_project_sessions_in,
+            load_session_messages_in,
+            search_messages_in,
+            sessions_referencing_file,
+            // Session summary commands
+            derive_session_title,
+            list_sessions_with_titles,
+            session_preview,
+            largest_messages,
+            extract_urls,
+            // Session analysis commands
+            session_time_bounds,
+            turn_latencies,
+            count_interruptions,
+            tool_error_stats,
+            sessions_with_errors,
+            paired_tool_calls,
+            list_mcp_tool_calls,
+            agentic_ratio,
+            declared_tools,
+            find_compaction_events,
+            split_session_at_compaction,
+            verify_companion_references,
+            classify_companion_files,
+            thread_integrity_check,
+            mode_transitions,
+            // Session resume commands
             resume_session,
+            find_session_continuations,
+            session_lineage,
+            // Web deep link command
+            session_web_url,
             // File watcher commands
             start_file_watcher,
-            stop_file_watcher
+            stop_file_watcher,
+            // Storage commands
+            session_disk_usage,
+            project_disk_usage,
+            list_trash,
+            empty_trash,
+            archive_projects,
+            find_identical_sessions,
+            find_orphaned_companions,
+            delete_orphaned_companions,
+            dedupe
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
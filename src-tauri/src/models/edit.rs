@@ -23,6 +23,73 @@ pub struct RecentEditsResult {
     pub project_cwd: Option<String>, // Most common working directory for this project
 }
 
+/// One sub-edit of a `MultiEdit` tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SingleEdit {
+    /// 0-based position within the `MultiEdit` call
+    pub index: usize,
+    pub file_path: String,
+    pub old_string: String,
+    pub new_string: String,
+    pub replace_all: bool,
+}
+
+/// Lines changed in one file during a session
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEditStats {
+    pub file_path: String,
+    /// `Edit`, `Write` and `MultiEdit` calls targeting the file
+    pub operations: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// Lines added and removed by a session's edits
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EditSummary {
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Per-file breakdown, sorted by path
+    pub files: Vec<FileEditStats>,
+}
+
+/// Final state of one file rebuilt by replaying a session's edits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayedFile {
+    /// Path the session edited
+    pub file_path: String,
+    /// Where the rebuilt content was written
+    pub scratch_path: String,
+    /// `Write`, `Edit` and `MultiEdit` calls applied to the file
+    pub operations: usize,
+    pub size_bytes: u64,
+    /// Whether the session wrote the whole file before editing it; when
+    /// false the content is built from edits alone and may be partial
+    pub fully_written: bool,
+}
+
+/// An edit that could not be replayed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedEdit {
+    pub file_path: String,
+    /// 0-based line index of the message carrying the tool call
+    pub line_index: usize,
+    /// `Edit` or `MultiEdit`
+    pub tool: String,
+    /// Position within a `MultiEdit` call
+    pub edit_index: Option<usize>,
+    pub reason: String,
+}
+
+/// Outcome of replaying a session's edits into a scratch directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayResult {
+    /// Rebuilt files, sorted by path
+    pub files: Vec<ReplayedFile>,
+    /// Edits skipped because they did not apply, in session order
+    pub failed_edits: Vec<FailedEdit>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
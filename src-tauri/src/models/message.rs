@@ -101,6 +101,8 @@ pub struct ClaudeMessage {
     pub timestamp: String,
     #[serde(rename = "type")]
     pub message_type: String,
+    /// Message content as written; content blocks keep their source order and
+    /// unknown block types are passed through unchanged
     pub content: Option<serde_json::Value>,
     /// Project name (extracted from file path during search)
     #[serde(rename = "projectName", skip_serializing_if = "Option::is_none")]
@@ -169,6 +171,18 @@ pub struct ClaudeMessage {
         skip_serializing_if = "Option::is_none"
     )]
     pub microcompact_metadata: Option<serde_json::Value>,
+
+    // Local time rendering of `timestamp` (only when a timezone offset is requested)
+    #[serde(rename = "localTimestamp", skip_serializing_if = "Option::is_none")]
+    pub local_timestamp: Option<String>,
+
+    // 0-based index of the source line (non-empty lines), stable across load filters
+    #[serde(rename = "lineIndex", skip_serializing_if = "Option::is_none")]
+    pub line_index: Option<usize>,
+
+    // Working directory active at this message (only when cwd tracking is requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -179,6 +193,16 @@ pub struct MessagePage {
     pub next_offset: usize,
 }
 
+/// Messages read forward from a byte offset, with a cursor to resume from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadChunk {
+    pub messages: Vec<ClaudeMessage>,
+    /// Byte offset to pass to the next call
+    pub next_offset: usize,
+    /// True when every complete line up to the end of the file was consumed
+    pub eof: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -345,6 +369,9 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
 
         let serialized = serde_json::to_string(&message).unwrap();
@@ -389,6 +416,9 @@ mod tests {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
 
         let serialized = serde_json::to_string(&message).unwrap();
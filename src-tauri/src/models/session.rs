@@ -23,6 +23,79 @@ pub struct GitInfo {
     pub main_project_path: Option<String>,
 }
 
+/// How reliably a project path was decoded from its storage directory name
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodeConfidence {
+    /// Recorded `originalPath`, or every path segment exists on disk
+    High,
+    /// Leading directories exist; the remainder is a guess
+    Medium,
+    /// Heuristic split with nothing verified on disk
+    Low,
+}
+
+/// Whether a project's `sessions-index.json` agrees with the filesystem
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexStatus {
+    /// `sessions-index.json` exists and is valid JSON
+    pub has_index: bool,
+    /// `originalPath` recorded in the index
+    pub original_path: Option<String>,
+    pub original_path_exists: bool,
+    /// Path decoded from the storage directory name alone
+    pub decoded_path: String,
+    pub decode_confidence: DecodeConfidence,
+    /// `originalPath` is stale and the decoded path is verified on disk
+    pub is_mismatch: bool,
+}
+
+/// How a project path was obtained from its storage directory
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodeMethod {
+    /// `originalPath` from `sessions-index.json`
+    OriginalPath,
+    /// Every hyphen resolved against existing directories
+    Filesystem,
+    /// Leading directories resolved, the rest kept as one name
+    PartialFilesystem,
+    /// Hyphens split by heuristic, or the name was not encoded
+    Heuristic,
+}
+
+/// Why a project name may be shown wrong
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncodingDiagnosis {
+    /// Storage directory name as written by Claude
+    pub encoded_name: String,
+    pub decoded_path: String,
+    pub method: DecodeMethod,
+    pub confidence: DecodeConfidence,
+    /// `originalPath` recorded in `sessions-index.json`
+    pub original_path: Option<String>,
+    /// Encoding the decoded path gives back the storage directory name
+    pub round_trips: bool,
+    /// The storage directory name contains non-ASCII characters
+    pub name_has_non_ascii: bool,
+    /// Characters in the name other than ASCII letters, digits and hyphens
+    pub unusual_chars: Vec<String>,
+    /// The decoded path contains non-ASCII characters, which the encoding
+    /// replaces with hyphens
+    pub path_has_non_ascii: bool,
+}
+
+/// Outcome of rewriting a stale `originalPath`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexRepairResult {
+    /// Copy of the original index; `None` if nothing needed repairing
+    pub backup_path: Option<String>,
+    /// `originalPath` before the repair
+    pub previous_path: Option<String>,
+    /// Status after the repair
+    pub status: IndexStatus,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClaudeProject {
     pub name: String,
@@ -38,6 +111,143 @@ pub struct ClaudeProject {
     pub git_info: Option<GitInfo>,
 }
 
+/// Project entry ordered by most recent conversation activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSummary {
+    pub name: String,
+    /// Claude session storage path
+    pub path: String,
+    /// Decoded actual filesystem path
+    pub actual_path: String,
+    /// Latest message timestamp across all sessions (RFC 3339)
+    pub last_activity: Option<String>,
+}
+
+/// Everything a project card shows, gathered in one call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectCard {
+    #[serde(flatten)]
+    pub project: ProjectSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_info: Option<GitInfo>,
+    pub session_count: usize,
+    /// Tokens across all sessions, only computed on request
+    pub total_tokens: Option<u64>,
+    /// Size of the session storage directory
+    pub total_bytes: u64,
+}
+
+/// One session in a project manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSession {
+    pub session_id: String,
+    pub file_path: String,
+    pub title: Option<String>,
+    pub message_count: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    /// Earliest message timestamp (RFC 3339)
+    pub first_timestamp: Option<String>,
+    /// Latest message timestamp (RFC 3339)
+    pub last_timestamp: Option<String>,
+    pub size_bytes: u64,
+    /// SHA-256 of the file contents as lowercase hex
+    pub content_hash: Option<String>,
+}
+
+/// Machine-readable catalog of a project's sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectManifest {
+    #[serde(flatten)]
+    pub project: ProjectSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_info: Option<GitInfo>,
+    /// When the manifest was generated (RFC 3339)
+    pub generated_at: String,
+    /// Sessions ordered by file path
+    pub sessions: Vec<ManifestSession>,
+}
+
+/// Outcome of checking one session against a manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityStatus {
+    /// Contents hash to the recorded value
+    Matched,
+    /// Contents differ from when the manifest was generated
+    Changed,
+    /// The session file no longer exists
+    Missing,
+    /// No SHA-256 digest was recorded or the file could not be read
+    Unverifiable,
+}
+
+/// One manifest session checked against the file on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityResult {
+    pub session_id: String,
+    pub file_path: String,
+    pub status: IntegrityStatus,
+    pub expected_hash: Option<String>,
+    pub actual_hash: Option<String>,
+}
+
+/// Sessions of a manifest checked against the files on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub matched: usize,
+    pub changed: usize,
+    pub missing: usize,
+    pub unverifiable: usize,
+    /// Per-session results, in manifest order
+    pub sessions: Vec<IntegrityResult>,
+}
+
+/// Project without recent activity, for archival
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleProject {
+    #[serde(flatten)]
+    pub project: ProjectSummary,
+    /// Size of the session storage directory
+    pub total_bytes: u64,
+    /// Whether the decoded project directory still exists
+    pub actual_path_exists: bool,
+}
+
+/// Working directory a branch was worked on, with its worktree status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchWorktree {
+    pub cwd: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_info: Option<GitInfo>,
+}
+
+/// Sessions of a project that were recorded on the same git branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchGroup {
+    /// Git branch name, or `(unknown)` for sessions without one
+    pub branch: String,
+    pub session_paths: Vec<String>,
+    /// Distinct working directories; more than one usually means worktrees
+    pub worktrees: Vec<BranchWorktree>,
+}
+
+/// File or directory in a project tree snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeNode {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    /// Directories first, then by name; empty for files and for directories
+    /// at the depth limit
+    pub children: Vec<TreeNode>,
+    /// Set on the root when entries were left out because of the entry cap
+    pub truncated: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClaudeSession {
     pub session_id: String,        // Unique ID based on file path
@@ -53,6 +263,24 @@ pub struct ClaudeSession {
     pub summary: Option<String>,
 }
 
+/// Session list entry with a derived title and persisted user metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionWithTitle {
+    /// Session id (file name without `.jsonl`)
+    pub session_id: String,
+    pub file_path: String,
+    pub title: String,
+    /// Latest message timestamp (RFC 3339)
+    pub last_activity: Option<String>,
+    /// Estimated from the file size
+    pub message_count_estimate: usize,
+    /// User-assigned name from the app metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitCommit {
     pub hash: String,
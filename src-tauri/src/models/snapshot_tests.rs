@@ -47,6 +47,9 @@ mod claude_message_snapshots {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
 
         assert_json_snapshot!("user_message", message);
@@ -94,6 +97,9 @@ mod claude_message_snapshots {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
 
         assert_json_snapshot!("assistant_message", message);
@@ -151,6 +157,9 @@ mod claude_message_snapshots {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         };
 
         assert_json_snapshot!("message_with_tool_use", message);
@@ -1,3 +1,4 @@
+use super::TokenUsage;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +29,45 @@ pub struct DailyStats {
     pub active_hours: usize,
 }
 
+/// Token usage totals for a time bucket
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenSummary {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub total_tokens: u64,
+    /// Messages that reported usage
+    pub message_count: usize,
+}
+
+impl TokenSummary {
+    /// Add the usage of one message
+    pub fn add_usage(&mut self, usage: &TokenUsage) {
+        let input = u64::from(usage.input_tokens.unwrap_or(0));
+        let output = u64::from(usage.output_tokens.unwrap_or(0));
+        let cache_creation = u64::from(usage.cache_creation_input_tokens.unwrap_or(0));
+        let cache_read = u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+
+        self.input_tokens += input;
+        self.output_tokens += output;
+        self.cache_creation_tokens += cache_creation;
+        self.cache_read_tokens += cache_read;
+        self.total_tokens += input + output + cache_creation + cache_read;
+        self.message_count += 1;
+    }
+
+    /// Merge another summary into this one
+    pub fn merge(&mut self, other: &TokenSummary) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.total_tokens += other.total_tokens;
+        self.message_count += other.message_count;
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolUsageStats {
     pub tool_name: String,
@@ -120,6 +160,221 @@ pub struct GlobalStatsSummary {
     pub top_projects: Vec<ProjectRanking>,
 }
 
+/// Reading statistics of a single session
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SessionReadingStats {
+    pub session_id: String,
+    pub file_path: String,
+    pub words: u64,
+    pub code_blocks: u32,
+    pub tool_calls: u32,
+}
+
+/// Reading statistics aggregated across a project
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ReadingStats {
+    /// Words in assistant text, excluding tool JSON and base64 blobs
+    pub total_words: u64,
+    /// Estimated reading time at 200 words per minute (rounded up)
+    pub reading_time_minutes: u64,
+    /// Fenced code blocks in assistant text
+    pub code_blocks: u32,
+    pub tool_calls: u32,
+    /// Per-session contributions, most words first
+    pub sessions: Vec<SessionReadingStats>,
+}
+
+/// Prompt cache usage of one model within a session
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ModelCacheStats {
+    pub model: String,
+    /// Assistant messages that reported usage
+    pub message_count: usize,
+    /// Uncached input tokens
+    pub input_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// Share of all input tokens served from the cache (0.0 - 1.0)
+    pub hit_ratio: f64,
+}
+
+impl ModelCacheStats {
+    /// Add one message's usage and refresh the hit ratio
+    pub fn add_usage(&mut self, usage: &TokenUsage) {
+        self.message_count += 1;
+        self.input_tokens += u64::from(usage.input_tokens.unwrap_or(0));
+        self.cache_creation_tokens += u64::from(usage.cache_creation_input_tokens.unwrap_or(0));
+        self.cache_read_tokens += u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+        self.hit_ratio = cache_hit_ratio(
+            self.input_tokens,
+            self.cache_creation_tokens,
+            self.cache_read_tokens,
+        );
+    }
+}
+
+/// Prompt cache effectiveness of a session
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CacheStats {
+    pub input_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// Share of all input tokens served from the cache (0.0 - 1.0)
+    pub hit_ratio: f64,
+    /// Per-model breakdown, most cache reads first
+    pub by_model: Vec<ModelCacheStats>,
+}
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+/// Token usage and cost of one model over a date range
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ModelCost {
+    pub model: String,
+    /// Messages that reported usage
+    pub message_count: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost_usd: f64,
+    /// False when no pricing entry matched the model; `cost_usd` is then 0
+    pub priced: bool,
+}
+
+impl ModelCost {
+    /// Add one message's usage
+    pub fn add_usage(&mut self, usage: &TokenUsage) {
+        self.message_count += 1;
+        self.input_tokens += u64::from(usage.input_tokens.unwrap_or(0));
+        self.output_tokens += u64::from(usage.output_tokens.unwrap_or(0));
+        self.cache_creation_tokens += u64::from(usage.cache_creation_input_tokens.unwrap_or(0));
+        self.cache_read_tokens += u64::from(usage.cache_read_input_tokens.unwrap_or(0));
+    }
+
+    /// Merge another model total into this one
+    pub fn merge(&mut self, other: &ModelCost) {
+        self.message_count += other.message_count;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+    }
+
+    /// Set `cost_usd` from the accumulated tokens
+    pub fn apply_pricing(&mut self, pricing: Option<&ModelPricing>) {
+        self.priced = pricing.is_some();
+        self.cost_usd = pricing.map_or(0.0, |p| {
+            (self.input_tokens as f64 * p.input
+                + self.output_tokens as f64 * p.output
+                + self.cache_creation_tokens as f64 * p.cache_write
+                + self.cache_read_tokens as f64 * p.cache_read)
+                / 1_000_000.0
+        });
+    }
+}
+
+/// Spend across all sessions within a date range
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CostSummary {
+    pub total_cost_usd: f64,
+    /// Per-model breakdown, most expensive first
+    pub by_model: Vec<ModelCost>,
+    /// Session files read
+    pub sessions_scanned: usize,
+    /// Session files that could not be read
+    pub skipped_sessions: Vec<String>,
+}
+
+/// Estimated spend of one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCost {
+    pub session_id: String,
+    pub file_path: String,
+    pub title: Option<String>,
+    /// Sum of the priced models; a lower bound when `unpriced_models` is set
+    pub cost_usd: f64,
+    /// Per-model breakdown, most expensive first
+    pub by_model: Vec<ModelCost>,
+    /// Models with usage but no pricing entry
+    pub unpriced_models: Vec<String>,
+}
+
+/// Most expensive sessions of a project
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SessionCostRanking {
+    /// Most expensive first
+    pub sessions: Vec<SessionCost>,
+    /// Every session with usage from an unpriced model, whose cost is
+    /// therefore incomplete, most expensive first
+    pub unpriced_sessions: Vec<SessionCost>,
+}
+
+/// Prompts whose length falls in a range
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptLengthBucket {
+    /// Inclusive lower bound in characters
+    pub min_chars: usize,
+    /// Exclusive upper bound; `None` for the last, open-ended bucket
+    pub max_chars: Option<usize>,
+    pub count: usize,
+}
+
+/// How the user writes prompts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptStats {
+    pub prompt_count: usize,
+    pub total_chars: usize,
+    /// Mean prompt length in characters; 0.0 without prompts
+    pub average_length: f64,
+    pub longest_length: usize,
+    /// Start of the longest prompt
+    pub longest_prompt_preview: Option<String>,
+    /// Session containing the longest prompt
+    pub longest_prompt_file: Option<String>,
+    /// Length histogram, shortest bucket first
+    pub buckets: Vec<PromptLengthBucket>,
+    pub sessions_scanned: usize,
+}
+
+/// Running assistant/user balance after one message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnPoint {
+    /// 0-based line index of the message
+    pub line_index: usize,
+    /// `user` or `assistant`
+    pub role: String,
+    pub user_messages: usize,
+    pub assistant_messages: usize,
+    /// `assistant_messages - user_messages`
+    pub delta: i64,
+    /// Assistant messages as a fraction of all messages so far
+    pub assistant_share: f64,
+}
+
+/// Cache reads divided by all input tokens (uncached, written and read);
+/// 0.0 when there was no input
+pub fn cache_hit_ratio(
+    input_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+) -> f64 {
+    let total = input_tokens + cache_creation_tokens + cache_read_tokens;
+    if total == 0 {
+        0.0
+    } else {
+        cache_read_tokens as f64 / total as f64
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,6 +201,9 @@ impl MessageBuilder {
             prevented_continuation: None,
             compact_metadata: None,
             microcompact_metadata: None,
+            local_timestamp: None,
+            line_index: None,
+            cwd: None,
         }
     }
 
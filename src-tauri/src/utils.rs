@@ -1,7 +1,8 @@
-use crate::models::{GitInfo, GitWorktreeType};
+use crate::models::{DecodeConfidence, GitInfo, GitWorktreeType};
+use chrono::{DateTime, FixedOffset, Utc};
 use memchr::memchr_iter;
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 /// Estimated average bytes per JSONL line (used for capacity pre-allocation)
 /// Based on typical Claude message sizes (800-1200 bytes average)
@@ -123,19 +124,64 @@ pub fn estimate_message_count_from_size(file_size: u64) -> usize {
 /// This function uses filesystem existence checks to correctly decode paths
 /// where the project name itself contains hyphens.
 pub fn decode_project_path(session_storage_path: &str) -> String {
-    // 1. Try reading originalPath from sessions-index.json (most reliable)
+    decode_project_path_with(session_storage_path, false)
+}
+
+/// Decode a session storage path, optionally verifying `originalPath`.
+///
+/// With `verify_exists`, the `originalPath` recorded in `sessions-index.json`
+/// is only trusted if that directory still exists; otherwise the encoded
+/// directory name is decoded instead. This avoids returning stale paths for
+/// moved or deleted projects at the cost of an extra filesystem check.
+pub fn decode_project_path_with(session_storage_path: &str, verify_exists: bool) -> String {
+    decode_project_path_scored(session_storage_path, verify_exists).0
+}
+
+/// Decode a session storage path and report how trustworthy the result is.
+///
+/// - [`High`]: `originalPath` was used, or every segment exists on disk
+/// - [`Medium`]: leading directories exist; the rest is kept as one name
+/// - [`Low`]: pure heuristic split, or the path was not an encoded one
+///
+/// [`High`]: DecodeConfidence::High
+/// [`Medium`]: DecodeConfidence::Medium
+/// [`Low`]: DecodeConfidence::Low
+pub fn decode_project_path_with_confidence(
+    session_storage_path: &str,
+) -> (String, DecodeConfidence) {
+    decode_project_path_scored(session_storage_path, false)
+}
+
+/// The absolute `originalPath` recorded in a storage directory's
+/// `sessions-index.json`, if any
+pub fn recorded_original_path(session_storage_path: &str) -> Option<String> {
     let index_path = Path::new(session_storage_path).join("sessions-index.json");
-    if let Ok(content) = std::fs::read_to_string(&index_path) {
-        if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&content) {
-            if let Some(original) = parsed.get("originalPath").and_then(|v| v.as_str()) {
-                if !original.is_empty() && Path::new(original).is_absolute() {
-                    return original.to_string();
-                }
-            }
+    let content = std::fs::read_to_string(&index_path).ok()?;
+    let parsed = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+    let original = parsed.get("originalPath")?.as_str()?;
+    (!original.is_empty() && Path::new(original).is_absolute()).then(|| original.to_string())
+}
+
+fn decode_project_path_scored(
+    session_storage_path: &str,
+    verify_exists: bool,
+) -> (String, DecodeConfidence) {
+    // 1. Try reading originalPath from sessions-index.json (most reliable)
+    if let Some(original) = recorded_original_path(session_storage_path) {
+        if !verify_exists || Path::new(&original).is_dir() {
+            return (original, DecodeConfidence::High);
         }
     }
 
     // 2. Fallback: decode from encoded directory name
+    decode_storage_dir_name(session_storage_path)
+}
+
+/// Decode a session storage path from its encoded directory name alone,
+/// ignoring any `originalPath` recorded in `sessions-index.json`.
+///
+/// Used to cross-check a recorded `originalPath` against what is on disk.
+pub fn decode_storage_dir_name(session_storage_path: &str) -> (String, DecodeConfidence) {
     const MARKER: &str = ".claude/projects/";
     // Also check Windows-style backslash marker
     const MARKER_WIN: &str = ".claude\\projects\\";
@@ -155,18 +201,11 @@ pub fn decode_project_path(session_storage_path: &str) -> String {
         if let Some(stripped) = encoded.strip_prefix('-') {
             // Try exact filesystem-based decoding (recursive)
             if let Some(path) = decode_with_filesystem_check(stripped) {
-                return path;
+                return (path, DecodeConfidence::High);
             }
 
-            // Fallback: heuristic decoding (reliable for Unix paths)
-            let parts: Vec<&str> = encoded.splitn(4, '-').collect();
-            if parts.len() >= 4 {
-                return format!("/{}/{}/{}", parts[1], parts[2], parts[3]);
-            } else if parts.len() == 3 {
-                return format!("/{}/{}", parts[1], parts[2]);
-            } else if parts.len() == 2 {
-                return format!("/{}", parts[1]);
-            }
+            // Fallback: heuristics for projects no longer on disk
+            return decode_unix_fallback(stripped, MIN_VERIFIED_UNIX_SEGMENTS);
         }
 
         // Windows format: C--Users-Username-path
@@ -180,7 +219,7 @@ pub fn decode_project_path(session_storage_path: &str) -> String {
             // Try exact filesystem-based decoding with Windows drive as base
             let win_base = format!("{drive_letter}:");
             if let Some(path) = decode_recursive(after_drive, &win_base) {
-                return path;
+                return (path, DecodeConfidence::High);
             }
 
             // Fallback: partial filesystem decode (handles deleted project dirs)
@@ -188,26 +227,98 @@ pub fn decode_project_path(session_storage_path: &str) -> String {
             let (deepest, remaining) = find_deepest_existing_dir(after_drive, &win_base, "\\", 0);
             let sep_count = deepest.matches('\\').count();
             if sep_count >= 3 && !remaining.is_empty() {
-                return format!("{deepest}\\{remaining}");
+                return (format!("{deepest}\\{remaining}"), DecodeConfidence::Medium);
             } else if sep_count >= 3 {
-                return deepest;
+                return (deepest, DecodeConfidence::High);
             }
 
             // Last resort: heuristic decoding for Windows
             let parts: Vec<&str> = after_drive.splitn(3, '-').collect();
-            if parts.len() >= 3 {
-                return format!(
+            let path = if parts.len() >= 3 {
+                format!(
                     "{}:\\{}\\{}\\{}",
                     drive_letter, parts[0], parts[1], parts[2]
-                );
+                )
             } else if parts.len() == 2 {
-                return format!("{}:\\{}\\{}", drive_letter, parts[0], parts[1]);
-            } else if parts.len() == 1 {
-                return format!("{}:\\{}", drive_letter, parts[0]);
-            }
+                format!("{}:\\{}\\{}", drive_letter, parts[0], parts[1])
+            } else {
+                format!("{}:\\{}", drive_letter, parts[0])
+            };
+            return (path, DecodeConfidence::Low);
         }
     }
-    session_storage_path.to_string()
+    (session_storage_path.to_string(), DecodeConfidence::Low)
+}
+
+/// Leading directories of a Unix path that must exist on disk before the
+/// undecoded remainder is trusted as a single directory name
+/// (`/Users/jack` or `/home/jack`; `/tmp` alone is not enough)
+const MIN_VERIFIED_UNIX_SEGMENTS: usize = 2;
+
+/// Heuristic splits of an encoded Unix path, from most to fewest components
+const UNIX_SPLIT_ARITIES: [usize; 3] = [3, 2, 1];
+
+/// `tmp-feature-my-project` split into at most `components` path components,
+/// the last one keeping its hyphens
+fn unix_path_with_components(encoded: &str, components: usize) -> String {
+    format!(
+        "/{}",
+        encoded
+            .splitn(components, '-')
+            .collect::<Vec<_>>()
+            .join("/")
+    )
+}
+
+/// Decode an encoded Unix path (without its leading `-`) that
+/// `decode_with_filesystem_check` could not resolve.
+///
+/// 1. Split into 3, 2 or 1 components and keep the first candidate that
+///    exists, following symlinks (e.g. `/tmp` on macOS)
+/// 2. Decode as deep as existing directories allow and keep the rest as the
+///    project name, if at least `min_verified_segments` directories matched
+/// 3. Fall back to splitting into 3 components
+fn decode_unix_fallback(encoded: &str, min_verified_segments: usize) -> (String, DecodeConfidence) {
+    if let Some(path) = UNIX_SPLIT_ARITIES
+        .iter()
+        .map(|&components| unix_path_with_components(encoded, components))
+        .find(|candidate| Path::new(candidate).is_dir())
+    {
+        return (path, DecodeConfidence::High);
+    }
+
+    let (deepest, remaining) = find_deepest_existing_dir(encoded, "", "/", 0);
+    if deepest.matches('/').count() >= min_verified_segments {
+        return if remaining.is_empty() {
+            (deepest, DecodeConfidence::High)
+        } else {
+            (format!("{deepest}/{remaining}"), DecodeConfidence::Medium)
+        };
+    }
+
+    (
+        unix_path_with_components(encoded, UNIX_SPLIT_ARITIES[0]),
+        DecodeConfidence::Low,
+    )
+}
+
+/// Encode a real project path into Claude's session storage directory name
+///
+/// Every character other than an ASCII letter or digit becomes a hyphen,
+/// which is how Claude names the directories under `~/.claude/projects/`:
+/// - `/Users/jack/my_project` → `-Users-jack-my-project`
+/// - `C:\Users\jack\app` → `C--Users-jack-app`
+///
+/// The encoding is lossy. Hyphens, dots, underscores and spaces in directory
+/// names are indistinguishable from separators, so `/a/b-c` and `/a/b/c`
+/// both encode to `-a-b-c`. [`decode_project_path`] only round-trips paths
+/// whose components are purely alphanumeric, or resolves the ambiguity by
+/// checking which candidate directories exist.
+pub fn encode_project_path(real_path: &str) -> String {
+    real_path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
 }
 
 /// Decode path by checking filesystem existence at each possible split point
@@ -420,10 +531,154 @@ pub fn detect_git_worktree_info(project_path: &str) -> Option<GitInfo> {
     })
 }
 
+/// Epoch values above this are treated as milliseconds rather than seconds
+const EPOCH_MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Parse a message timestamp (RFC 3339 or epoch seconds/milliseconds) as UTC
+pub fn parse_message_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    let trimmed = timestamp.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(trimmed) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    let epoch: i64 = trimmed.parse().ok()?;
+    if epoch.abs() >= EPOCH_MILLIS_THRESHOLD {
+        DateTime::from_timestamp_millis(epoch)
+    } else {
+        DateTime::from_timestamp(epoch, 0)
+    }
+}
+
+/// Build a fixed UTC offset from minutes east of UTC (e.g. 540 for KST, -300 for EST)
+pub fn timezone_offset(offset_minutes: i32) -> Option<FixedOffset> {
+    FixedOffset::east_opt(offset_minutes.checked_mul(60)?)
+}
+
+/// Format a message timestamp in the given local offset as `YYYY-MM-DD HH:MM:SS`.
+/// Returns None for missing or unparseable timestamps.
+pub fn format_local_timestamp(timestamp: &str, offset: FixedOffset) -> Option<String> {
+    parse_message_timestamp(timestamp).map(|dt| {
+        dt.with_timezone(&offset)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    })
+}
+
+// ===== Path Resolution =====
+
+/// Normalize a path lexically: `.` components are dropped and `..` removes
+/// the preceding component, without touching the filesystem (symlinks are
+/// not resolved). Returns None if `..` would climb above the start.
+pub fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) {
+                    return None;
+                }
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    Some(normalized)
+}
+
+/// Resolve a path from a tool call against a project directory.
+///
+/// Relative paths are joined to `root`; absolute paths are kept. The result
+/// is normalized with `normalize_path` and must stay inside `root`.
+pub fn resolve_path_within(root: &Path, path: &str) -> Result<PathBuf, String> {
+    let root = normalize_path(root).ok_or_else(|| format!("Invalid root: {}", root.display()))?;
+    normalize_path(&root.join(path))
+        .filter(|resolved| resolved.starts_with(&root))
+        .ok_or_else(|| format!("Path escapes the project directory: {path}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // ===== Timestamp Tests =====
+
+    #[test]
+    fn test_format_local_timestamp_crosses_day_boundary() {
+        let kst = timezone_offset(540).unwrap();
+        assert_eq!(
+            format_local_timestamp("2025-06-26T20:30:00Z", kst),
+            Some("2025-06-27 05:30:00".to_string())
+        );
+
+        let est = timezone_offset(-300).unwrap();
+        assert_eq!(
+            format_local_timestamp("2025-06-26T02:00:00.123Z", est),
+            Some("2025-06-25 21:00:00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_local_timestamp_epoch_values() {
+        let utc = timezone_offset(0).unwrap();
+        assert_eq!(
+            format_local_timestamp("1750932000", utc),
+            Some("2025-06-26 10:00:00".to_string())
+        );
+        assert_eq!(
+            format_local_timestamp("1750932000000", utc),
+            Some("2025-06-26 10:00:00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_local_timestamp_invalid() {
+        let utc = timezone_offset(0).unwrap();
+        assert_eq!(format_local_timestamp("", utc), None);
+        assert_eq!(format_local_timestamp("not a date", utc), None);
+        assert!(timezone_offset(24 * 60).is_none());
+    }
+
+    // ===== Path Resolution Tests =====
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path(Path::new("/p/src/../lib/./mod.rs")),
+            Some(PathBuf::from("/p/lib/mod.rs"))
+        );
+        assert_eq!(
+            normalize_path(Path::new("a/b/../../c")),
+            Some(PathBuf::from("c"))
+        );
+        assert_eq!(normalize_path(Path::new("/..")), None);
+        assert_eq!(normalize_path(Path::new("a/../..")), None);
+    }
+
+    #[test]
+    fn test_resolve_path_within() {
+        let root = Path::new("/work/app");
+        assert_eq!(
+            resolve_path_within(root, "src/../README.md").unwrap(),
+            PathBuf::from("/work/app/README.md")
+        );
+        assert_eq!(
+            resolve_path_within(root, "./src/./main.rs").unwrap(),
+            PathBuf::from("/work/app/src/main.rs")
+        );
+        assert_eq!(
+            resolve_path_within(root, "/work/app/src/lib.rs").unwrap(),
+            PathBuf::from("/work/app/src/lib.rs")
+        );
+        // Traversal out of the project
+        assert!(resolve_path_within(root, "../other/secret").is_err());
+        assert!(resolve_path_within(root, "src/../../app-evil/x").is_err());
+        assert!(resolve_path_within(root, "/etc/passwd").is_err());
+    }
+
     // ===== Line Utils Tests =====
 
     #[test]
@@ -643,11 +898,146 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_project_path_short_unix_paths() {
+        for (encoded, expected) in [
+            ("-tmp-foo", "/tmp/foo"),
+            ("-opt-data", "/opt/data"),
+            ("-home-user", "/home/user"),
+        ] {
+            assert_eq!(
+                decode_project_path(&format!("/u/.claude/projects/{encoded}")),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_project_path_with_confidence() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = temp_dir.path().join(".claude/projects/-srv-app");
+        std::fs::create_dir_all(&storage).unwrap();
+        let storage = storage.to_string_lossy().to_string();
+
+        assert_eq!(
+            decode_project_path_with_confidence(&storage),
+            ("/srv/app".to_string(), DecodeConfidence::Low)
+        );
+
+        std::fs::write(
+            Path::new(&storage).join("sessions-index.json"),
+            r#"{"originalPath":"/srv/my-app"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            decode_project_path_with_confidence(&storage),
+            ("/srv/my-app".to_string(), DecodeConfidence::High)
+        );
+
+        assert_eq!(
+            decode_project_path_with_confidence("/some/other/path").1,
+            DecodeConfidence::Low
+        );
+    }
+
+    #[test]
+    fn test_decode_unix_fallback_prefers_existing_dirs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base = temp_dir.path().join("work");
+        std::fs::create_dir_all(base.join("feature-my-project")).unwrap();
+        let base = base.to_string_lossy().to_string();
+        let encoded = base.trim_start_matches('/').replace('/', "-");
+
+        // Existing hyphenated directory wins over the 3-component split
+        let (path, confidence) = decode_unix_fallback(&format!("{encoded}-feature-my-project"), 2);
+        assert!(path.ends_with("/work/feature-my-project"));
+        assert_eq!(confidence, DecodeConfidence::Medium);
+
+        // Deleted project below verified directories keeps its hyphens
+        assert_eq!(
+            decode_unix_fallback(&format!("{encoded}-gone-app"), 2),
+            (format!("{base}/gone-app"), DecodeConfidence::Medium)
+        );
+        // Too few verified directories: plain heuristic split
+        assert_eq!(
+            decode_unix_fallback("nonexistent-feature-my-project", 2),
+            (
+                "/nonexistent/feature/my-project".to_string(),
+                DecodeConfidence::Low
+            )
+        );
+    }
+
     #[test]
     fn test_decode_project_path_regular() {
         assert_eq!(decode_project_path("/some/other/path"), "/some/other/path");
     }
 
+    #[test]
+    fn test_decode_project_path_verify_exists() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = temp_dir
+            .path()
+            .join(".claude/projects/-nonexistent-moved-project");
+        std::fs::create_dir_all(&storage).unwrap();
+        std::fs::write(
+            storage.join("sessions-index.json"),
+            r#"{"originalPath":"/nonexistent/moved-project"}"#,
+        )
+        .unwrap();
+        let storage = storage.to_string_lossy().to_string();
+
+        // Default trusts the recorded path
+        assert_eq!(decode_project_path(&storage), "/nonexistent/moved-project");
+        // Verification falls back to decoding the directory name
+        assert_eq!(
+            decode_project_path_with(&storage, true),
+            "/nonexistent/moved/project"
+        );
+
+        let live = temp_dir.path().to_string_lossy().to_string();
+        std::fs::write(
+            Path::new(&storage).join("sessions-index.json"),
+            format!(
+                r#"{{"originalPath":{}}}"#,
+                serde_json::to_string(&live).unwrap()
+            ),
+        )
+        .unwrap();
+        assert_eq!(decode_project_path_with(&storage, true), live);
+    }
+
+    #[test]
+    fn test_encode_project_path() {
+        assert_eq!(
+            encode_project_path("/Users/jack/my_project.v2"),
+            "-Users-jack-my-project-v2"
+        );
+        assert_eq!(
+            encode_project_path("C:\\Users\\jack\\app"),
+            "C--Users-jack-app"
+        );
+    }
+
+    #[test]
+    fn test_encode_project_path_round_trip() {
+        for real_path in ["/Users/jack/project", "/srv/app", "C:\\Users\\jack\\app"] {
+            let storage = format!(
+                "/home/u/.claude/projects/{}",
+                encode_project_path(real_path)
+            );
+            assert_eq!(decode_project_path(&storage), real_path);
+        }
+    }
+
+    #[test]
+    fn test_encode_project_path_hyphens_are_ambiguous() {
+        assert_eq!(
+            encode_project_path("/srv/my-app"),
+            encode_project_path("/srv/my/app")
+        );
+    }
+
     #[test]
     fn test_extract_main_git_dir_valid() {
         assert_eq!(
@@ -1,6 +1,7 @@
 import { useState, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { isAbsolutePath } from "@/utils/pathUtils";
+import { getCommandErrorMessage } from "@/utils/commandError";
 
 export interface DeleteSessionResult {
   success: boolean;
@@ -55,7 +56,7 @@ export const useSessionDelete = (): UseSessionDeleteReturn => {
         });
         return result;
       } catch (err) {
-        const errorMessage = err instanceof Error ? err.message : String(err);
+        const errorMessage = getCommandErrorMessage(err);
         setError(errorMessage);
         throw new Error(errorMessage);
       } finally {
@@ -24,3 +24,21 @@ export interface AppError {
   type: AppErrorType;
   message: string;
 }
+
+// ============================================================================
+// Command Error
+// ============================================================================
+
+/** Stable error kinds returned by structured backend commands */
+export type CommandErrorKind =
+  | "not_found"
+  | "permission_denied"
+  | "outside_claude_dir"
+  | "invalid_input"
+  | "io";
+
+/** Structured error rejected by commands such as `delete_session` */
+export interface CommandError {
+  kind: CommandErrorKind;
+  message: string;
+}
@@ -217,7 +217,7 @@ export type {
 
 // Error Types
 export { AppErrorType } from "./error.types";
-export type { AppError } from "./error.types";
+export type { AppError, CommandError, CommandErrorKind } from "./error.types";
 
 // Analytics Types
 export type {
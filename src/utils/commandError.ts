@@ -0,0 +1,24 @@
+import type { CommandError } from "../types";
+
+/**
+ * Check whether a rejected `invoke` value is a structured `CommandError`.
+ */
+export function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as CommandError).kind === "string" &&
+    typeof (error as CommandError).message === "string"
+  );
+}
+
+/**
+ * Get a human-readable message from a rejected `invoke` value.
+ * Handles structured `CommandError`s as well as plain string errors.
+ */
+export function getCommandErrorMessage(error: unknown): string {
+  if (isCommandError(error)) {
+    return error.message;
+  }
+  return error instanceof Error ? error.message : String(error);
+}